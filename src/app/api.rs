@@ -0,0 +1,554 @@
+//! Local control API (`--serve <port>`): a small HTTP/JSON server bound to localhost, to observe and drive a
+//! game from another process (web dashboards, external bots). The game starts paused in manual mode:
+//! - `GET /state`: time step, mode and end reasons
+//! - `GET /topology`: alive planets and links
+//! - `GET /planets`, `GET /explorers`: the states shown by the GUI
+//! - `GET /events?since=N`: the orchestrator events from the N-th on, poll it again with the returned `next`
+//! - `POST /actions`: schedules and runs a manual action, e.g. `{"type": "send_sunray", "planet_id": 3}`
+//! - `POST /step`: plays a time step
+//! - `POST /mode`: `{"mode": "auto"}` plays a step every `game_tick_seconds`, `{"mode": "manual"}` pauses
+//! - `POST /quit`: ends the server
+//!
+//! Requests are read by a listener thread and served by the game loop one at a time, between two steps. A
+//! client has `REQUEST_TIMEOUT` to send its whole request and then to read the answer, and a request head
+//! bigger than `MAX_HEAD_SIZE` is refused, so that a stalled or trickling client cannot block the others.
+
+use std::io::{BufRead, BufReader, ErrorKind, Read, Write};
+use std::net::TcpListener;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender};
+use serde_json::{Value, json};
+
+use super::AppConfig;
+use crate::gui::GameEvent;
+use crate::orchestrator::{Orchestrator, OrchestratorManualAction, OrchestratorMode, resource_name};
+
+#[derive(Debug, Clone, PartialEq)]
+struct HttpRequest {
+    method: String,
+    path: String,
+    query: String,
+    body: String
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct HttpResponse {
+    status: u16,
+    body: Value
+}
+
+impl HttpResponse {
+    fn ok(body: Value) -> Self { HttpResponse { status: 200, body } }
+
+    fn error(status: u16, message: impl Into<String>) -> Self {
+        HttpResponse { status, body: json!({ "error": message.into() }) }
+    }
+}
+
+/// A request waiting for the game loop to answer it.
+struct ApiCall {
+    request: HttpRequest,
+    reply: Sender<HttpResponse>
+}
+
+/// Biggest request body accepted, actions are much smaller
+const MAX_BODY_SIZE: usize = 64 * 1024;
+
+/// Biggest request line and headers accepted, together
+const MAX_HEAD_SIZE: usize = 16 * 1024;
+
+/// Time given to a client to send its whole request, and then to read the answer
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A client that stopped sending gets a 408, any other failure to read the request a 400.
+fn read_error(context: &str, e: &std::io::Error) -> HttpResponse {
+    match e.kind() {
+        ErrorKind::WouldBlock | ErrorKind::TimedOut => timed_out(context),
+        _ => HttpResponse::error(400, format!("{context}: {e}"))
+    }
+}
+
+fn timed_out(context: &str) -> HttpResponse { HttpResponse::error(408, format!("{context}: timed out")) }
+
+/// Reads a line of the request head, `budget` is what is left of `MAX_HEAD_SIZE`. The deadline is checked
+/// between two reads, a client sending a byte at a time cannot keep the connection.
+fn read_head_line(
+    reader: &mut impl BufRead,
+    deadline: Instant,
+    budget: &mut usize,
+    context: &str
+) -> Result<String, HttpResponse> {
+    let mut line = Vec::new();
+    loop {
+        if Instant::now() > deadline {
+            return Err(timed_out(context));
+        }
+        let available = reader.fill_buf().map_err(|e| read_error(context, &e))?;
+        if available.is_empty() {
+            break; // end of the stream
+        }
+        let (read, complete) = match available.iter().position(|&byte| byte == b'\n') {
+            Some(end) => (end + 1, true),
+            None => (available.len(), false)
+        };
+        if read > *budget {
+            return Err(HttpResponse::error(400, format!("The request head is bigger than {MAX_HEAD_SIZE} bytes")));
+        }
+        *budget -= read;
+        line.extend_from_slice(&available[..read]);
+        reader.consume(read);
+        if complete {
+            break;
+        }
+    }
+    String::from_utf8(line).map_err(|_| HttpResponse::error(400, "The request head is not UTF-8"))
+}
+
+fn read_request(reader: &mut impl BufRead, deadline: Instant) -> Result<HttpRequest, HttpResponse> {
+    let mut budget = MAX_HEAD_SIZE;
+    let line = read_head_line(reader, deadline, &mut budget, "Failed to read the request")?;
+    let mut parts = line.split_whitespace();
+    let (Some(method), Some(target)) = (parts.next(), parts.next()) else {
+        return Err(HttpResponse::error(400, format!("Malformed request line '{}'", line.trim())));
+    };
+    let (method, target) = (method.to_string(), target.to_string());
+
+    let mut content_length = 0;
+    loop {
+        let line = read_head_line(reader, deadline, &mut budget, "Failed to read the request headers")?;
+        let header = line.trim();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':')
+            && name.eq_ignore_ascii_case("content-length")
+        {
+            content_length = value
+                .trim()
+                .parse()
+                .map_err(|_| HttpResponse::error(400, format!("Invalid content length '{}'", value.trim())))?;
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        return Err(HttpResponse::error(400, format!("Request body of {content_length} bytes is too big")));
+    }
+
+    let mut body = vec![0; content_length];
+    let mut filled = 0;
+    while filled < content_length {
+        if Instant::now() > deadline {
+            return Err(timed_out("Failed to read the request body"));
+        }
+        match reader.read(&mut body[filled..]) {
+            Ok(0) => return Err(HttpResponse::error(400, "The request body is shorter than its content length")),
+            Ok(read) => filled += read,
+            Err(e) => return Err(read_error("Failed to read the request body", &e))
+        }
+    }
+    let body = String::from_utf8(body).map_err(|_| HttpResponse::error(400, "The request body is not UTF-8"))?;
+    let (path, query) = target.split_once('?').unwrap_or((&target, ""));
+    Ok(HttpRequest { method, path: path.to_string(), query: query.to_string(), body })
+}
+
+fn write_response(writer: &mut impl Write, response: &HttpResponse) -> std::io::Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        500 => "Internal Server Error",
+        _ => "Error"
+    };
+    let body = response.body.to_string();
+    write!(writer, "HTTP/1.1 {} {reason}\r\n", response.status)?;
+    write!(writer, "Content-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", body.len())?;
+    writer.write_all(body.as_bytes())?;
+    writer.flush()
+}
+
+/// Forwards the requests to the game loop and writes back its answers, until the game loop is gone.
+fn listen(listener: &TcpListener, calls: &Sender<ApiCall>, timeout: Duration) {
+    for stream in listener.incoming() {
+        let mut stream = match stream {
+            Ok(stream) => stream,
+            Err(e) => {
+                log::warn!("Failed to accept an API connection: {e}");
+                continue;
+            }
+        };
+        if let Err(e) = stream.set_read_timeout(Some(timeout)).and_then(|()| stream.set_write_timeout(Some(timeout))) {
+            log::warn!("Failed to set the timeouts of an API connection: {e}");
+            continue;
+        }
+        let deadline = Instant::now() + timeout;
+        let response = match read_request(&mut BufReader::new(&stream), deadline) {
+            Ok(request) => {
+                let (reply, answer) = crossbeam_channel::bounded(1);
+                if calls.send(ApiCall { request, reply }).is_err() {
+                    return;
+                }
+                answer.recv().unwrap_or_else(|_| HttpResponse::error(500, "The game stopped"))
+            }
+            Err(response) => response
+        };
+        if let Err(e) = write_response(&mut stream, &response) {
+            log::warn!("Failed to answer an API request: {e}");
+        }
+    }
+}
+
+/// The game served by the API, with every event it produced so far.
+struct ApiGame {
+    orchestrator: Orchestrator,
+    events: Vec<(u32, GameEvent)>, // time step the event was collected at, event
+    quit: bool
+}
+
+impl ApiGame {
+    fn new(orchestrator: Orchestrator) -> Self { ApiGame { orchestrator, events: vec![], quit: false } }
+
+    fn is_auto(&self) -> bool { matches!(self.orchestrator.get_mode(), OrchestratorMode::Auto) }
+
+    fn collect_events(&mut self) {
+        let time = self.orchestrator.get_time();
+        let events = self.orchestrator.get_gui_events_buffer().drain_game_events();
+        self.events.extend(events.into_iter().map(|event| (time, event)));
+    }
+
+    fn step(&mut self) -> Result<(), String> {
+        if self.orchestrator.is_game_over() {
+            return Err("The game is over".to_string());
+        }
+        let result = self.orchestrator.manual_step().map_err(|e| e.to_string());
+        self.collect_events();
+        result
+    }
+
+    fn handle(&mut self, request: &HttpRequest) -> HttpResponse {
+        match (request.method.as_str(), request.path.as_str()) {
+            ("GET", "/state") => HttpResponse::ok(self.state()),
+            ("GET", "/topology") => HttpResponse::ok(self.topology()),
+            ("GET", "/planets") => HttpResponse::ok(self.planets()),
+            ("GET", "/explorers") => HttpResponse::ok(self.explorers()),
+            ("GET", "/events") => self.events(&request.query),
+            ("POST", "/actions") => self.action(&request.body),
+            ("POST", "/step") => match self.step() {
+                Ok(()) => HttpResponse::ok(self.state()),
+                Err(e) => HttpResponse::error(409, e)
+            },
+            ("POST", "/mode") => self.mode(&request.body),
+            ("POST", "/quit") => {
+                self.quit = true;
+                HttpResponse::ok(json!({ "quit": true }))
+            }
+            (method, path) => HttpResponse::error(404, format!("No endpoint {method} {path}"))
+        }
+    }
+
+    fn state(&self) -> Value {
+        let reasons: Vec<String> =
+            self.orchestrator.end_reasons().unwrap_or_default().iter().map(ToString::to_string).collect();
+        json!({
+            "time": self.orchestrator.get_time(),
+            "mode": if self.is_auto() { "auto" } else { "manual" },
+            "game_over": self.orchestrator.is_game_over(),
+            "end_reasons": reasons
+        })
+    }
+
+    fn topology(&self) -> Value {
+        let mut planets = self.orchestrator.get_alive_planets();
+        planets.sort_unstable();
+        let mut edges = self.orchestrator.get_topology();
+        edges.sort_unstable();
+        json!({ "planets": planets, "edges": edges })
+    }
+
+    fn planets(&self) -> Value {
+        let planets: Vec<Value> = self
+            .orchestrator
+            .get_planets_info()
+            .iter()
+            .map(|(id, info)| {
+                json!({
+                    "id": id,
+                    "type": format!("{:?}", info.name),
+                    "status": format!("{:?}", info.status).to_lowercase(),
+                    "energy_cells": info.energy_cells,
+                    "charged_cells_count": info.charged_cells_count,
+                    "rocket": info.rocket
+                })
+            })
+            .collect();
+        Value::from(planets)
+    }
+
+    fn explorers(&self) -> Value {
+        let explorers: Vec<Value> = self
+            .orchestrator
+            .get_explorer_states()
+            .iter()
+            .map(|(&id, info)| {
+                let bag: serde_json::Map<String, Value> = info
+                    .bag
+                    .content
+                    .iter()
+                    .filter(|(_, qty)| **qty > 0)
+                    .map(|(&resource, &qty)| (resource_name(resource), Value::from(qty)))
+                    .collect();
+                json!({
+                    "id": id,
+                    "type": self.orchestrator.get_explorer_type(id),
+                    "status": format!("{:?}", info.status).to_lowercase(),
+                    "current_planet_id": info.current_planet_id,
+                    "bag": bag
+                })
+            })
+            .collect();
+        Value::from(explorers)
+    }
+
+    fn events(&self, query: &str) -> HttpResponse {
+        let since = match query.split('&').find_map(|param| param.strip_prefix("since=")) {
+            Some(since) => match since.parse::<usize>() {
+                Ok(since) => since,
+                Err(_) => return HttpResponse::error(400, format!("Invalid since '{since}'"))
+            },
+            None => 0
+        };
+        let events: Vec<Value> = self
+            .events
+            .iter()
+            .skip(since)
+            .map(|(time, event)| json!({ "time": time, "event": event }))
+            .collect();
+        HttpResponse::ok(json!({ "next": self.events.len(), "events": events }))
+    }
+
+    fn action(&mut self, body: &str) -> HttpResponse {
+        let action: OrchestratorManualAction = match serde_json::from_str(body) {
+            Ok(action) => action,
+            Err(e) => return HttpResponse::error(400, format!("Invalid action: {e}"))
+        };
+        self.orchestrator.schedule_manual_action(action);
+        let results = self.orchestrator.process_commands();
+        self.collect_events();
+        match results {
+            Ok(results) => {
+                let results: Vec<String> = results.iter().map(ToString::to_string).collect();
+                HttpResponse::ok(json!({ "result": results.join(", ") }))
+            }
+            Err(e) => HttpResponse::error(409, e.to_string())
+        }
+    }
+
+    fn mode(&mut self, body: &str) -> HttpResponse {
+        let mode = serde_json::from_str::<Value>(body).ok().and_then(|body| body["mode"].as_str().map(str::to_string));
+        match mode.as_deref() {
+            Some("auto") => self.orchestrator.set_mode(OrchestratorMode::Auto),
+            Some("manual") => self.orchestrator.set_mode(OrchestratorMode::Manual),
+            _ => return HttpResponse::error(400, r#"Expected {"mode": "auto"} or {"mode": "manual"}"#)
+        }
+        HttpResponse::ok(self.state())
+    }
+}
+
+/// Serves the requests until a quit request or the end of the listener, playing a step every tick in auto
+/// mode.
+fn serve(game: &mut ApiGame, calls: &Receiver<ApiCall>, tick: Duration) {
+    let mut next_step = Instant::now() + tick;
+    while !game.quit {
+        let call = if game.is_auto() {
+            calls.recv_deadline(next_step)
+        } else {
+            calls.recv().map_err(|_| RecvTimeoutError::Disconnected)
+        };
+        match call {
+            Ok(call) => {
+                let response = game.handle(&call.request);
+                let _ = call.reply.send(response); // the client may be gone already
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                if !game.orchestrator.is_game_over()
+                    && let Err(e) = game.step()
+                {
+                    log::error!("Orchestrator terminated with error: {e}");
+                    return;
+                }
+                next_step = Instant::now() + tick;
+            }
+            Err(RecvTimeoutError::Disconnected) => return
+        }
+    }
+}
+
+/// Plays a game driven by the API on `127.0.0.1:<port>`.
+pub(crate) fn run_api(port: u16) {
+    let orchestrator = Orchestrator::from_config(OrchestratorMode::Manual).and_then(|mut orchestrator| {
+        orchestrator.manual_init()?;
+        Ok(orchestrator)
+    });
+    let mut game = match orchestrator {
+        Ok(orchestrator) => ApiGame::new(orchestrator),
+        Err(e) => {
+            log::error!("Failed to create orchestrator: {e}");
+            std::process::exit(1);
+        }
+    };
+    game.collect_events();
+
+    let listener = match TcpListener::bind(("127.0.0.1", port)) {
+        Ok(listener) => listener,
+        Err(e) => {
+            log::error!("Failed to listen on port {port}: {e}");
+            std::process::exit(1);
+        }
+    };
+    log::info!("Serving the game API on http://127.0.0.1:{port}");
+    let (calls, requests) = crossbeam_channel::unbounded();
+    thread::spawn(move || listen(&listener, &calls, REQUEST_TIMEOUT));
+
+    let tick = Duration::from_secs_f32(AppConfig::get().game_tick_seconds);
+    serve(&mut game, &requests, tick);
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpStream;
+
+    use super::*;
+
+    fn request(method: &str, path: &str, body: &str) -> HttpRequest {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        HttpRequest {
+            method: method.to_string(),
+            path: path.to_string(),
+            query: query.to_string(),
+            body: body.to_string()
+        }
+    }
+
+    #[test]
+    fn test_read_request() {
+        let raw = "POST /actions?x=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 2\r\n\r\n{}";
+        let deadline = Instant::now() + REQUEST_TIMEOUT;
+        let request = read_request(&mut raw.as_bytes(), deadline).unwrap();
+        assert_eq!(request, HttpRequest {
+            method: "POST".to_string(),
+            path: "/actions".to_string(),
+            query: "x=1".to_string(),
+            body: "{}".to_string()
+        });
+        assert!(read_request(&mut "\r\n".as_bytes(), deadline).is_err());
+        assert!(read_request(&mut "GET / HTTP/1.1\r\nContent-Length: 9\r\n\r\n{}".as_bytes(), deadline).is_err());
+        let long_header = format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", "a".repeat(MAX_HEAD_SIZE));
+        assert_eq!(read_request(&mut long_header.as_bytes(), deadline).unwrap_err().status, 400);
+        let expired = Instant::now().checked_sub(Duration::from_secs(1)).unwrap();
+        assert_eq!(read_request(&mut "GET / HTTP/1.1\r\n\r\n".as_bytes(), expired).unwrap_err().status, 408);
+    }
+
+    #[test]
+    fn test_api_requests() {
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 3, vec![], 8).unwrap();
+        orchestrator.manual_init().unwrap();
+        let mut game = ApiGame::new(orchestrator);
+        let planet_id = game.orchestrator.get_alive_planets()[0];
+
+        let topology = game.handle(&request("GET", "/topology", ""));
+        assert_eq!(topology.body["planets"].as_array().unwrap().len(), 3);
+        assert_eq!(game.handle(&request("GET", "/planets", "")).body.as_array().unwrap().len(), 3);
+
+        let action = format!(r#"{{"type": "send_sunray", "planet_id": {planet_id}}}"#);
+        assert_eq!(game.handle(&request("POST", "/actions", &action)).status, 200);
+        let events = game.handle(&request("GET", "/events?since=0", ""));
+        assert!(events.body["events"].as_array().unwrap().iter().any(|e| e["event"]["type"] == "sunray_sent"));
+        let next = events.body["next"].as_u64().unwrap();
+        let events = game.handle(&request("GET", &format!("/events?since={next}"), ""));
+        assert!(events.body["events"].as_array().unwrap().is_empty());
+
+        assert_eq!(game.handle(&request("POST", "/step", "")).body["time"], 1);
+        assert_eq!(game.handle(&request("POST", "/mode", r#"{"mode": "auto"}"#)).body["mode"], "auto");
+
+        assert_eq!(game.handle(&request("POST", "/actions", r#"{"type": "teleport"}"#)).status, 400);
+        let unknown_planet = r#"{"type": "send_asteroid", "planet_id": 99}"#;
+        assert_eq!(game.handle(&request("POST", "/mode", r#"{"mode": "manual"}"#)).status, 200);
+        assert_eq!(game.handle(&request("POST", "/actions", unknown_planet)).status, 409);
+        assert_eq!(game.handle(&request("GET", "/nowhere", "")).status, 404);
+    }
+
+    #[test]
+    fn test_api_over_tcp() {
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 2, vec![], 9).unwrap();
+        orchestrator.manual_init().unwrap();
+        let mut game = ApiGame::new(orchestrator);
+
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let (calls, requests) = crossbeam_channel::unbounded();
+        thread::spawn(move || listen(&listener, &calls, REQUEST_TIMEOUT));
+        let client = thread::spawn(move || {
+            let send = |raw: &str| {
+                let mut stream = TcpStream::connect(address).unwrap();
+                stream.write_all(raw.as_bytes()).unwrap();
+                let mut response = String::new();
+                stream.read_to_string(&mut response).unwrap();
+                response
+            };
+            let state = send("GET /state HTTP/1.1\r\n\r\n");
+            send("POST /quit HTTP/1.1\r\n\r\n");
+            state
+        });
+
+        serve(&mut game, &requests, Duration::from_secs(1));
+        let state = client.join().unwrap();
+        let (head, body) = state.split_once("\r\n\r\n").unwrap();
+        assert!(head.starts_with("HTTP/1.1 200 OK\r\n"));
+        let body: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(body, json!({ "time": 0, "mode": "manual", "game_over": false, "end_reasons": [] }));
+    }
+
+    #[test]
+    fn test_stalled_client_times_out() {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let (calls, requests) = crossbeam_channel::unbounded();
+        thread::spawn(move || listen(&listener, &calls, Duration::from_millis(100)));
+
+        let mut stalled = TcpStream::connect(address).unwrap();
+        stalled.write_all(b"GET /state HTTP/1.1\r\n").unwrap(); // the headers never end
+        let mut response = String::new();
+        stalled.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"), "{response}");
+
+        // A byte at a time, every read is quicker than the timeout but the whole request is not
+        let mut trickling = TcpStream::connect(address).unwrap();
+        trickling.set_read_timeout(Some(Duration::from_millis(20))).unwrap();
+        trickling.write_all(b"GET /state HTTP/1.1\r\nX-Slow: ").unwrap();
+        let started = Instant::now();
+        let mut response = Vec::new();
+        while !response.ends_with(b"\r\n\r\n") && started.elapsed() < Duration::from_secs(2) {
+            let _ = trickling.write_all(b"a"); // fails once the server closed the connection
+            let mut buffer = [0; 256];
+            match trickling.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => response.extend_from_slice(&buffer[..read]),
+                Err(_) => {} // nothing to read yet
+            }
+        }
+        let response = String::from_utf8_lossy(&response);
+        assert!(response.starts_with("HTTP/1.1 408 Request Timeout\r\n"), "{response}");
+
+        // The next client is served
+        let mut client = TcpStream::connect(address).unwrap();
+        client.write_all(b"GET /state HTTP/1.1\r\n\r\n").unwrap();
+        let call = requests.recv_timeout(Duration::from_secs(1)).unwrap();
+        assert_eq!(call.request.path, "/state");
+        call.reply.send(HttpResponse::ok(json!({}))).unwrap();
+        let mut response = String::new();
+        client.read_to_string(&mut response).unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"), "{response}");
+    }
+}
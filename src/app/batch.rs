@@ -0,0 +1,389 @@
+//! Headless batch mode (`--runs N`): plays N games back-to-back and prints an aggregated report.
+//! With `--jobs J` the games are split between J child processes, each one prints the statistics of its
+//! games as JSON lines that are merged by the parent. The lines are tagged with `RESULT_TAG`, since explorers
+//! may print to stdout too.
+//! Game `i` of the batch uses the seed `seed + i`, so a batch with a fixed seed is reproducible.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+
+use common_game::utils::ID;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppConfig;
+use crate::explorers::{BagContent, ExplorerFactory};
+use crate::gui::OrchestratorEvent;
+use crate::orchestrator::{GalaxyLayout, Orchestrator, OrchestratorError, OrchestratorMode, resource_name};
+
+/// Starts the lines of a batch worker holding the statistics of a game
+const RESULT_TAG: &str = "rusty-crab-game-stats ";
+
+/// What happened in a single game.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct GameStats {
+    pub seed: u64,
+    pub turns: u32,
+    pub planets: usize,
+    pub planets_destroyed: usize,
+    pub rockets_fired: usize, // asteroids that did not destroy their planet
+    /// Increases of the explorers bags between two turns, a resource generated and consumed in the same
+    /// turn is not counted.
+    pub resources_generated: BTreeMap<String, usize>,
+    /// Sum of the final bags of the explorers of each type
+    pub final_bags: BTreeMap<String, BTreeMap<String, usize>>,
+    pub explorers: Vec<ExplorerResult>
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ExplorerResult {
+    pub id: ID,
+    pub explorer_type: String,
+    pub died: bool, // killed while other planets were still alive
+    pub bag: BTreeMap<String, usize>
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Summary {
+    pub mean: f64,
+    pub min: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub max: f64
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub(crate) struct BatchReport {
+    pub games: usize,
+    pub failed_games: usize,
+    pub turns: Summary,
+    pub planets_destroyed: Summary,
+    pub planets_destroyed_per_turn: Summary,
+    pub rockets_fired: Summary,
+    /// Mean over the games
+    pub resources_generated: BTreeMap<String, f64>,
+    /// Mean over the games, by explorer type
+    pub final_bags: BTreeMap<String, BTreeMap<String, f64>>
+}
+
+pub(crate) fn run_batch(runs: u32) {
+    let config = AppConfig::get();
+    let seed = config.seed.unwrap_or_else(rand::random);
+
+    if config.batch_worker {
+        for game in 0..runs {
+            match play_game(seed.wrapping_add(u64::from(game))) {
+                Ok(stats) =>
+                    println!("{RESULT_TAG}{}", serde_json::to_string(&stats).expect("Game stats are serializable")),
+                Err(e) => log::error!("Game {game} of the batch failed: {e}")
+            }
+        }
+        return;
+    }
+
+    log::info!("Playing {runs} games with {} processes, first seed {seed}", config.jobs);
+    let results = if config.jobs <= 1 {
+        (0..runs).map(|game| play_game(seed.wrapping_add(u64::from(game)))).collect()
+    } else {
+        run_workers(runs, config.jobs, seed)
+    };
+
+    let failed = results.iter().filter(|result| result.is_err()).count();
+    for e in results.iter().filter_map(|result| result.as_ref().err()) {
+        log::error!("Game of the batch failed: {e}");
+    }
+    let games: Vec<GameStats> = results.into_iter().filter_map(Result::ok).collect();
+    let mut report = BatchReport::from_games(&games);
+    report.failed_games = failed;
+
+    if config.json {
+        println!("{}", serde_json::to_string_pretty(&report).expect("Batch report is serializable"));
+    } else {
+        print!("{report}");
+    }
+}
+
+/// Splits the games between `jobs` child processes and collects their results.
+fn run_workers(runs: u32, jobs: u32, seed: u64) -> Vec<Result<GameStats, String>> {
+    let mut first_game = 0;
+    let workers: Vec<(u32, Result<Child, String>)> = (0..jobs.min(runs))
+        .map(|job| {
+            let games = runs / jobs + u32::from(job < runs % jobs);
+            let worker = spawn_worker(games, seed.wrapping_add(u64::from(first_game)));
+            first_game += games;
+            (games, worker)
+        })
+        .collect();
+
+    let mut results = Vec::new();
+    for (games, worker) in workers {
+        let games = games as usize;
+        let mut child = match worker {
+            Ok(child) => child,
+            Err(e) => {
+                results.extend((0..games).map(|_| Err(e.clone())));
+                continue;
+            }
+        };
+        let stdout = child.stdout.take().expect("Worker stdout is piped");
+        let worker_results = read_worker_results(BufReader::new(stdout));
+        if let Err(e) = child.wait() {
+            log::error!("Failed to wait for batch worker: {e}");
+        }
+        // Games that did not print their statistics failed, the worker logged the reason
+        let missing = games.saturating_sub(worker_results.len());
+        results.extend(worker_results);
+        results.extend((0..missing).map(|_| Err("Game failed in a batch worker".to_string())));
+    }
+    results
+}
+
+/// The results printed by a worker, the untagged lines are printed by the explorers and skipped.
+fn read_worker_results(output: impl BufRead) -> Vec<Result<GameStats, String>> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = match line {
+                Ok(line) => line,
+                Err(e) => return Some(Err(format!("Failed to read worker output: {e}")))
+            };
+            let Some(json) = line.strip_prefix(RESULT_TAG) else {
+                log::debug!("Batch worker output: {line}");
+                return None;
+            };
+            Some(serde_json::from_str(json).map_err(|e| format!("Failed to parse worker output: {e}")))
+        })
+        .collect()
+}
+
+fn spawn_worker(games: u32, seed: u64) -> Result<Child, String> {
+    let config = AppConfig::get();
+    let exe = std::env::current_exe().map_err(|e| format!("Failed to find the executable: {e}"))?;
+    Command::new(exe)
+        .args(["--config", &config.config_file, "--log-level", &config.log_level])
+        .args(["--runs", &games.to_string(), "--batch-worker"])
+        .env("RUSTY_CRAB_SEED", seed.to_string())
+        .stdout(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Failed to start batch worker: {e}"))
+}
+
+fn new_game(seed: u64) -> Result<Orchestrator, OrchestratorError> {
+    let config = AppConfig::get();
+    match &config.galaxy_file {
+        Some(galaxy_file) => {
+            let layout = GalaxyLayout::from_file(galaxy_file).map_err(OrchestratorError::Config)?;
+            Orchestrator::from_layout_with_seed(OrchestratorMode::Auto, layout, seed)
+        }
+        None => {
+            let explorers = config.explorers.iter().map(ExplorerFactory::make_from_name).collect();
+            Orchestrator::new_with_seed(OrchestratorMode::Auto, config.number_of_planets, explorers, seed)
+        }
+    }
+}
+
+fn play_game(seed: u64) -> Result<GameStats, String> {
+    new_game(seed).and_then(|orchestrator| play(orchestrator, seed)).map_err(|e| e.to_string())
+}
+
+pub(super) fn play(mut orchestrator: Orchestrator, seed: u64) -> Result<GameStats, OrchestratorError> {
+    orchestrator.manual_init()?;
+    let mut stats = GameStats { seed, planets: orchestrator.get_alive_planets().len(), ..GameStats::default() };
+    let mut bags: HashMap<ID, BagContent> = HashMap::new();
+    let mut died = HashSet::new();
+
+    while !orchestrator.is_game_over() {
+        orchestrator.manual_step()?;
+        stats.count_events(&orchestrator.get_gui_events_buffer().drain_events());
+
+        for id in orchestrator.get_explorer_ids() {
+            // At the end of the game all the explorers are dead, only the ones killed before count
+            if orchestrator.get_explorer_current_planet(id).is_none() && !orchestrator.is_game_over() {
+                died.insert(id);
+            }
+            let Some(bag) = orchestrator.get_explorer_bag(id) else {
+                continue;
+            };
+            let previous = bags.get(&id);
+            for (&resource, &qty) in &bag.content {
+                let before = previous.and_then(|b| b.content.get(&resource)).copied().unwrap_or(0);
+                if qty > before {
+                    *stats.resources_generated.entry(resource_name(resource)).or_default() += qty - before;
+                }
+            }
+            bags.insert(id, bag.clone());
+        }
+    }
+
+    stats.turns = orchestrator.get_time();
+    for id in orchestrator.get_explorer_ids() {
+        let explorer_type = orchestrator.get_explorer_type(id).unwrap_or("unknown").to_string();
+        let bag: BTreeMap<String, usize> = bags
+            .remove(&id)
+            .unwrap_or_default()
+            .content
+            .into_iter()
+            .filter(|(_, qty)| *qty > 0)
+            .map(|(resource, qty)| (resource_name(resource), qty))
+            .collect();
+        let total = stats.final_bags.entry(explorer_type.clone()).or_default();
+        for (resource, qty) in &bag {
+            *total.entry(resource.clone()).or_default() += qty;
+        }
+        stats.explorers.push(ExplorerResult { id, explorer_type, died: died.contains(&id), bag });
+    }
+    Ok(stats)
+}
+
+impl GameStats {
+    /// Events of a single turn.
+    fn count_events(&mut self, events: &[OrchestratorEvent]) {
+        for event in events {
+            match event {
+                OrchestratorEvent::PlanetDestroyed { .. } => self.planets_destroyed += 1,
+                OrchestratorEvent::AsteroidSent { planet_id } => {
+                    let destroyed = events.contains(&OrchestratorEvent::PlanetDestroyed { planet_id: *planet_id });
+                    if !destroyed {
+                        self.rockets_fired += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+impl Summary {
+    #[allow(clippy::cast_precision_loss, clippy::cast_possible_truncation, clippy::cast_sign_loss)] // few values
+    fn new(mut values: Vec<f64>) -> Self {
+        if values.is_empty() {
+            return Summary::default();
+        }
+        values.sort_by(f64::total_cmp);
+        let percentile = |p: f64| values[((p * values.len() as f64).ceil() as usize).clamp(1, values.len()) - 1];
+        Summary {
+            mean: values.iter().sum::<f64>() / values.len() as f64,
+            min: values[0],
+            p50: percentile(0.5),
+            p90: percentile(0.9),
+            max: values[values.len() - 1]
+        }
+    }
+
+    fn of(games: &[GameStats], value: impl Fn(&GameStats) -> f64) -> Self {
+        Self::new(games.iter().map(value).collect())
+    }
+}
+
+impl BatchReport {
+    #[allow(clippy::cast_precision_loss)] // counts are small
+    fn from_games(games: &[GameStats]) -> Self {
+        let n = games.len().max(1) as f64;
+        let mut resources_generated = BTreeMap::new();
+        let mut final_bags: BTreeMap<String, BTreeMap<String, f64>> = BTreeMap::new();
+        for game in games {
+            for (resource, &qty) in &game.resources_generated {
+                *resources_generated.entry(resource.clone()).or_default() += qty as f64 / n;
+            }
+            for (explorer_type, bag) in &game.final_bags {
+                let mean_bag = final_bags.entry(explorer_type.clone()).or_default();
+                for (resource, &qty) in bag {
+                    *mean_bag.entry(resource.clone()).or_default() += qty as f64 / n;
+                }
+            }
+        }
+
+        BatchReport {
+            games: games.len(),
+            failed_games: 0,
+            turns: Summary::of(games, |g| f64::from(g.turns)),
+            planets_destroyed: Summary::of(games, |g| g.planets_destroyed as f64),
+            planets_destroyed_per_turn: Summary::of(games, |g| g.planets_destroyed as f64 / f64::from(g.turns.max(1))),
+            rockets_fired: Summary::of(games, |g| g.rockets_fired as f64),
+            resources_generated,
+            final_bags
+        }
+    }
+}
+
+impl std::fmt::Display for Summary {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "mean {:.2}, min {:.2}, p50 {:.2}, p90 {:.2}, max {:.2}",
+            self.mean, self.min, self.p50, self.p90, self.max
+        )
+    }
+}
+
+impl std::fmt::Display for BatchReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(f, "Games played: {} ({} failed)", self.games, self.failed_games)?;
+        writeln!(f, "Turns survived:            {}", self.turns)?;
+        writeln!(f, "Planets destroyed:         {}", self.planets_destroyed)?;
+        writeln!(f, "Planets destroyed / turn:  {}", self.planets_destroyed_per_turn)?;
+        writeln!(f, "Rockets fired:             {}", self.rockets_fired)?;
+        writeln!(f, "Resources generated (mean per game):")?;
+        for (resource, qty) in &self.resources_generated {
+            writeln!(f, "  {resource}: {qty:.2}")?;
+        }
+        writeln!(f, "Final bags (mean per game):")?;
+        for (explorer_type, bag) in &self.final_bags {
+            let content: Vec<String> = bag.iter().map(|(resource, qty)| format!("{resource} {qty:.2}")).collect();
+            writeln!(f, "  {explorer_type}: {}", content.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary() {
+        let summary = Summary::new((1..=10).map(f64::from).collect());
+        assert_eq!(summary, Summary { mean: 5.5, min: 1.0, p50: 5.0, p90: 9.0, max: 10.0 });
+        assert_eq!(Summary::new(vec![]), Summary::default());
+    }
+
+    #[test]
+    fn test_count_rockets() {
+        let mut stats = GameStats::default();
+        stats.count_events(&[
+            OrchestratorEvent::AsteroidSent { planet_id: 1 },
+            OrchestratorEvent::AsteroidSent { planet_id: 2 },
+            OrchestratorEvent::PlanetDestroyed { planet_id: 2 }
+        ]);
+        assert_eq!(stats.rockets_fired, 1);
+        assert_eq!(stats.planets_destroyed, 1);
+    }
+
+    #[test]
+    fn test_batch_report() {
+        let games: Vec<GameStats> = (0..3)
+            .map(|seed| {
+                let orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, 5, vec![], seed).unwrap();
+                play(orchestrator, seed).unwrap()
+            })
+            .collect();
+        for game in &games {
+            assert_eq!(game.planets_destroyed, 5);
+            assert!(game.turns > 0);
+        }
+        let report = BatchReport::from_games(&games);
+        assert_eq!(report.games, 3);
+        assert_eq!(report.planets_destroyed.mean, 5.0);
+    }
+
+    #[test]
+    fn test_worker_output_noise_is_skipped() {
+        let stats = GameStats { seed: 4, turns: 12, ..GameStats::default() };
+        let output = format!(
+            "{{\"Carbon\": 2}}\n[explorer 8] started\n{RESULT_TAG}{}\n",
+            serde_json::to_string(&stats).unwrap()
+        );
+        let results = read_worker_results(output.as_bytes());
+        assert_eq!(results, vec![Ok(stats)]);
+    }
+}
@@ -4,6 +4,10 @@ use clap::Parser;
 use config::{Config, Environment, File};
 use serde::Deserialize;
 
+use crate::explorers::ScoringModel;
+use crate::orchestrator::{EndCondition, ExplorerPlacement, FaultPolicy, GalaxyTopology, PlanetMix, PlanetOptions,
+                          SchedulerConfig};
+
 macro_rules! config_fields {
     ( $( $field:ident: $ty:ty = $default:expr ),* $(,)? ) => {
         #[allow(unused)]
@@ -14,6 +18,18 @@ macro_rules! config_fields {
             $( pub $field: $ty, )*
             pub log_level: String,
             pub log_file: Option<String>,
+            pub resume: Option<String>,
+            pub replay: Option<String>,
+            pub config_file: String,
+            pub runs: Option<u32>,
+            pub tournament: Option<u32>,
+            pub scenario: Option<String>,
+            pub repl: bool,
+            pub serve: Option<u16>,
+            pub remote_explorer: Option<String>,
+            pub jobs: u32,
+            pub json: bool,
+            pub batch_worker: bool,
         }
 
         impl AppConfig {
@@ -23,6 +39,18 @@ macro_rules! config_fields {
                     $( $field: settings.get(stringify!($field)).unwrap_or($default), )*
                     log_level: args.log_level,
                     log_file: args.log_file,
+                    resume: args.resume,
+                    replay: args.replay,
+                    config_file: args.config,
+                    runs: args.runs,
+                    tournament: args.tournament,
+                    scenario: args.scenario,
+                    repl: args.repl,
+                    serve: args.serve,
+                    remote_explorer: args.remote_explorer,
+                    jobs: args.jobs,
+                    json: args.json,
+                    batch_worker: args.batch_worker,
                 }
             }
         }
@@ -41,6 +69,25 @@ config_fields! {
     show_gui: bool = false,
     explorer_probability_estimator_sensitivity: f32 = 0.1,
     initial_planet_id: u32 = 1, // from 1 to <number_of_planets>
+    explorer_placement: ExplorerPlacement = ExplorerPlacement::Fixed,
+    snapshot_file: Option<String> = None, // where to save checkpoints of the game
+    snapshot_interval: u32 = 10, // time steps between two checkpoints
+    seed: Option<u64> = None, // random if not set
+    topology: GalaxyTopology = GalaxyTopology::FullyConnected,
+    galaxy_file: Option<String> = None, // overrides number_of_planets, topology and explorers
+    scoring: ScoringModel = ScoringModel::default(),
+    record_file: Option<String> = None, // where to record the game events
+    explorer_max_strikes: u32 = 3, // faults before a misbehaving explorer is evicted
+    explorer_fault_policy: FaultPolicy = FaultPolicy::Restart,
+    scheduler: SchedulerConfig = SchedulerConfig::Sigmoid,
+    real_time: bool = false, // explorers play asynchronously instead of in turns
+    real_time_tick_ms: u64 = 200, // duration of a time step in real-time mode
+    end_condition: EndCondition = EndCondition::AllPlanetsDestroyed,
+    stats_file: Option<String> = None, // where to save the per-turn statistics as CSV
+    planet_mix: PlanetMix = PlanetMix::Cyclic,
+    planet_options: PlanetOptions = PlanetOptions::default(),
+    undo_history: usize = 50, // manual actions that can be undone, 0 disables undo
+    remote_explorer_wait_seconds: u64 = 60, // time given to a remote explorer to connect
 }
 
 #[derive(Parser, Debug)]
@@ -54,7 +101,42 @@ pub struct CliArgs {
     pub log_level: String,
     /// Log file path
     #[arg(long)]
-    pub log_file: Option<String>
+    pub log_file: Option<String>,
+    /// Resume the game saved in a snapshot file
+    #[arg(long)]
+    pub resume: Option<String>,
+    /// Replay a game recorded with the record_file option, without gui (cannot be used with show_gui)
+    #[arg(long, conflicts_with = "resume")]
+    pub replay: Option<String>,
+    /// Play N games without gui and print a statistics report
+    #[arg(long, conflicts_with_all = ["resume", "replay"])]
+    pub runs: Option<u32>,
+    /// Number of processes playing the batch games in parallel
+    #[arg(long, default_value_t = 1, requires = "runs")]
+    pub jobs: u32,
+    /// Play a round-robin tournament between the configured explorers (all of them if less than two) on
+    /// N seeds and print the leaderboard
+    #[arg(long, conflicts_with_all = ["resume", "replay", "runs"])]
+    pub tournament: Option<u32>,
+    /// Play a scripted scenario file and check its expectations
+    #[arg(long, conflicts_with_all = ["resume", "replay", "runs", "tournament"])]
+    pub scenario: Option<String>,
+    /// Play a manual game typing the commands in the terminal
+    #[arg(long, conflicts_with_all = ["resume", "replay", "runs", "tournament", "scenario"])]
+    pub repl: bool,
+    /// Play a game driven by a JSON API served on this localhost port
+    #[arg(long, conflicts_with_all = ["runs", "tournament", "scenario", "repl"])]
+    pub serve: Option<u16>,
+    /// Play the reference remote explorer in the game waiting on this address (`127.0.0.1:7000` or
+    /// `unix:/tmp/explorer.sock`)
+    #[arg(long, conflicts_with_all = ["resume", "replay", "runs", "tournament", "scenario", "repl", "serve"])]
+    pub remote_explorer: Option<String>,
+    /// Print the batch report or the tournament leaderboard as JSON
+    #[arg(long)]
+    pub json: bool,
+    /// Internal, prints the statistics of every game as a JSON line for the parent process
+    #[arg(long, hide = true, requires = "runs")]
+    pub batch_worker: bool
 }
 
 static CONFIG: OnceLock<AppConfig> = OnceLock::new();
@@ -67,6 +149,17 @@ impl AppConfig {
                 config: "config.toml".to_string(),
                 log_level: "info".to_string(),
                 log_file: None,
+                resume: None,
+                replay: None,
+                runs: None,
+                tournament: None,
+                scenario: None,
+                repl: false,
+                serve: None,
+                remote_explorer: None,
+                jobs: 1,
+                json: false,
+                batch_worker: false
             }
         } else {
             CliArgs::parse()
@@ -77,7 +170,13 @@ impl AppConfig {
             .add_source(Environment::with_prefix("RUSTY_CRAB").separator("_"))
             .build()
             .expect("Failed to build configuration"); // we cannot use logging here since it's not initialized yet
-        CONFIG.set(AppConfig::from_settings(&settings, args)).expect("AppConfig can only be initialized once");
+        let config = AppConfig::from_settings(&settings, args);
+        if config.replay.is_some() && config.show_gui {
+            // The GUI builds its own orchestrator, replays are headless only
+            eprintln!("--replay cannot be used with show_gui, disable show_gui to replay a recorded game");
+            std::process::exit(1);
+        }
+        CONFIG.set(config).expect("AppConfig can only be initialized once");
     }
 
     pub fn get() -> &'static AppConfig { CONFIG.get().expect("AppConfig is not initialized") }
@@ -23,8 +23,15 @@ pub fn setup_logger() -> Result<(), fern::InitError> {
         .level(log_level.parse().unwrap_or_else(|e| {
             eprintln!("Failed to parse log level: {e}. Defaulting to Info.");
             log::LevelFilter::Info
-        }))
-        .chain(std::io::stdout());
+        }));
+
+    // In batch mode the standard output is reserved to the report, in REPL mode to the commands output
+    let config = AppConfig::get();
+    log = if config.runs.is_some() || config.tournament.is_some() || config.scenario.is_some() || config.repl {
+        log.chain(std::io::stderr())
+    } else {
+        log.chain(std::io::stdout())
+    };
 
     if let Some(log_file) = &AppConfig::get().log_file {
         log = log.chain(fern::log_file(log_file)?);
@@ -1,7 +1,18 @@
-//! This module contains app and lifecycle related code. Like the config management, and the logging.
+//! This module contains app and lifecycle related code. Like the config management, the logging and
+//! the batch, tournament, scenario, REPL and API modes.
 
+mod api;
+mod batch;
 mod config;
 mod logging;
+mod repl;
+mod scenario;
+mod tournament;
 
+pub(crate) use api::run_api;
+pub(crate) use batch::run_batch;
 pub(crate) use config::AppConfig;
 pub(crate) use logging::setup_logger;
+pub(crate) use repl::run_repl;
+pub(crate) use scenario::run_scenario;
+pub(crate) use tournament::run_tournament;
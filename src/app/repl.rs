@@ -0,0 +1,429 @@
+//! Interactive terminal mode (`--repl`): plays a manual game from the command line, one command per line
+//! (e.g. `sunray 3`, `move 8 4`, `gen 8 Carbon`). The commands map to `OrchestratorManualAction`s and
+//! their result is printed, `help` lists them. Up/down arrows browse the history and tab completes the
+//! commands, the ids of the alive planets and explorers and the resource names.
+
+use common_game::components::resource::ResourceType;
+use common_game::utils::ID;
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+
+use crate::orchestrator::{ALL_RESOURCES, Orchestrator, OrchestratorError, OrchestratorManualAction,
+                          OrchestratorMode, resource_from_name, resource_name};
+
+/// Name, arguments (optional ones in brackets) and description of every command.
+const COMMANDS: [(&str, &str, &str); 23] = [
+    ("sunray", "<planet>", "send a sunray to a planet"),
+    ("asteroid", "<planet>", "send an asteroid to a planet"),
+    ("move", "<explorer> <planet>", "move an explorer to a neighbour planet"),
+    ("gen", "<explorer> <basic resource>", "generate a basic resource on the explorer planet"),
+    ("combine", "<explorer> <complex resource>", "combine a complex resource on the explorer planet"),
+    ("state", "<planet>", "show the energy cells and the rocket of a planet"),
+    ("bag", "<explorer>", "show the bag of an explorer"),
+    ("where", "<explorer>", "show the planet of an explorer"),
+    ("resources", "<explorer>", "show the basic resources available to an explorer"),
+    ("start", "<planet|explorer> <id>", "start the AI of a planet or an explorer"),
+    ("stop", "<planet|explorer> <id>", "stop the AI of a planet or an explorer"),
+    ("reset", "<planet|explorer> <id>", "restart the AI of a planet or an explorer"),
+    ("kill", "<explorer>", "remove an explorer from the game"),
+    ("link", "<planet> <planet>", "connect two planets"),
+    ("cut", "<planet> <planet>", "disconnect two planets"),
+    ("topology", "", "show the planets, their links and the explorers"),
+    ("step", "[turns]", "end the time step, or play some manual steps"),
+    ("auto", "<turns>", "play some turns in auto mode"),
+    ("undo", "", "take back the last action or step"),
+    ("redo", "", "play again the last undone action or step"),
+    ("help", "", "show this list"),
+    ("quit", "", "end the game"),
+    ("exit", "", "same as quit")
+];
+
+#[derive(Debug)]
+enum ReplCommand {
+    Action(OrchestratorManualAction),
+    Step { turns: u32 },
+    Topology,
+    Undo,
+    Redo,
+    Help,
+    Quit
+}
+
+/// What a command argument is, to parse and complete it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Argument {
+    Command,
+    Planet,
+    Explorer,
+    /// `planet` or `explorer`
+    Target,
+    BasicResource,
+    ComplexResource,
+    Number
+}
+
+/// The argument at `index` (0 is the command itself) of a command line starting with `words`.
+fn argument_kind(words: &[&str], index: usize) -> Option<Argument> {
+    if index == 0 {
+        return Some(Argument::Command);
+    }
+    let (_, arguments, _) = COMMANDS.iter().find(|(name, ..)| *name == words[0])?;
+    let argument = arguments.split_whitespace().nth(index - 1)?;
+    Some(match argument.trim_matches(['<', '>', '[', ']']) {
+        "planet" => Argument::Planet,
+        "explorer" => Argument::Explorer,
+        "planet|explorer" => Argument::Target,
+        "basic" => Argument::BasicResource,
+        "complex" => Argument::ComplexResource,
+        "id" if words.get(1) == Some(&"explorer") => Argument::Explorer,
+        "id" => Argument::Planet,
+        _ => Argument::Number
+    })
+}
+
+fn usage(command: &str) -> String {
+    match COMMANDS.iter().find(|(name, ..)| *name == command) {
+        Some((name, arguments, _)) if arguments.is_empty() => format!("usage: {name}"),
+        Some((name, arguments, _)) => format!("usage: {name} {arguments}"),
+        None => format!("unknown command '{command}', type 'help' for the list")
+    }
+}
+
+fn parse_command(line: &str) -> Result<ReplCommand, String> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    let Some(&command) = words.first() else {
+        return Err("empty command".to_string());
+    };
+    let (_, arguments, _) = COMMANDS.iter().find(|(name, ..)| *name == command).ok_or_else(|| usage(command))?;
+    // Resource names are a single word, the multi-word placeholders count as one argument
+    let expected = arguments.split('<').skip(1).count();
+    let optional = arguments.split('[').skip(1).count();
+    if words.len() - 1 < expected || words.len() - 1 > expected + optional {
+        return Err(usage(command));
+    }
+
+    let id = |index: usize| -> Result<ID, String> {
+        words[index].parse().map_err(|_| format!("'{}' is not a valid id, {}", words[index], usage(command)))
+    };
+    let resource = |index: usize| {
+        resource_from_name(words[index]).ok_or_else(|| format!("unknown resource '{}'", words[index]))
+    };
+
+    let action = match command {
+        "sunray" => OrchestratorManualAction::SendSunray { planet_id: id(1)? },
+        "asteroid" => OrchestratorManualAction::SendAsteroid { planet_id: id(1)? },
+        "move" => OrchestratorManualAction::MoveExplorer { explorer_id: id(1)?, destination_planet_id: id(2)? },
+        "gen" => match resource(2)? {
+            ResourceType::Basic(resource) => OrchestratorManualAction::GenerateBasic { explorer_id: id(1)?, resource },
+            ResourceType::Complex(_) => return Err(format!("{} is a complex resource, use combine", words[2]))
+        },
+        "combine" => match resource(2)? {
+            ResourceType::Complex(resource) =>
+                OrchestratorManualAction::GenerateComplex { explorer_id: id(1)?, resource },
+            ResourceType::Basic(_) => return Err(format!("{} is a basic resource, use gen", words[2]))
+        },
+        "state" => OrchestratorManualAction::QueryPlanetState { planet_id: id(1)? },
+        "bag" => OrchestratorManualAction::QueryExplorerBag { explorer_id: id(1)? },
+        "where" => OrchestratorManualAction::QueryExplorerPlanet { explorer_id: id(1)? },
+        "resources" => OrchestratorManualAction::QueryExplorerResources { explorer_id: id(1)? },
+        "start" | "stop" | "reset" => match (command, words[1], id(2)?) {
+            ("start", "planet", planet_id) => OrchestratorManualAction::StartPlanet { planet_id },
+            ("stop", "planet", planet_id) => OrchestratorManualAction::StopPlanet { planet_id },
+            ("reset", "planet", planet_id) => OrchestratorManualAction::ResetPlanet { planet_id },
+            ("start", "explorer", explorer_id) => OrchestratorManualAction::StartExplorer { explorer_id },
+            ("stop", "explorer", explorer_id) => OrchestratorManualAction::StopExplorer { explorer_id },
+            ("reset", "explorer", explorer_id) => OrchestratorManualAction::ResetExplorer { explorer_id },
+            _ => return Err(usage(command))
+        },
+        "kill" => OrchestratorManualAction::KillExplorer { explorer_id: id(1)? },
+        "link" => OrchestratorManualAction::AddEdge { from: id(1)?, to: id(2)? },
+        "cut" => OrchestratorManualAction::CutEdge { from: id(1)?, to: id(2)? },
+        "auto" => OrchestratorManualAction::AdvanceTurns { turns: turns(words[1])? },
+        "step" => return Ok(ReplCommand::Step { turns: words.get(1).map_or(Ok(1), |word| turns(word))? }),
+        "topology" => return Ok(ReplCommand::Topology),
+        "undo" => return Ok(ReplCommand::Undo),
+        "redo" => return Ok(ReplCommand::Redo),
+        "help" => return Ok(ReplCommand::Help),
+        "quit" | "exit" => return Ok(ReplCommand::Quit),
+        _ => return Err(format!("unknown command '{command}'"))
+    };
+    Ok(ReplCommand::Action(action))
+}
+
+fn turns(word: &str) -> Result<u32, String> {
+    word.parse().map_err(|_| format!("'{word}' is not a valid number of turns"))
+}
+
+/// The start of the word under the cursor and its completions.
+fn complete_line(line: &str, planets: &[ID], explorers: &[ID]) -> (usize, Vec<String>) {
+    let prefix = line.split(char::is_whitespace).next_back().unwrap_or_default();
+    let start = line.len() - prefix.len();
+    let words: Vec<&str> = line[..start].split_whitespace().collect();
+
+    let ids = |ids: &[ID]| ids.iter().map(ToString::to_string).collect::<Vec<_>>();
+    let resources = |basic: bool| {
+        ALL_RESOURCES
+            .into_iter()
+            .filter(|resource| matches!(resource, ResourceType::Basic(_)) == basic)
+            .map(resource_name)
+            .collect::<Vec<_>>()
+    };
+    let candidates = match argument_kind(&words, words.len()) {
+        Some(Argument::Command) => COMMANDS.iter().map(|(name, ..)| (*name).to_string()).collect(),
+        Some(Argument::Planet) => ids(planets),
+        Some(Argument::Explorer) => ids(explorers),
+        Some(Argument::Target) => vec!["planet".to_string(), "explorer".to_string()],
+        Some(Argument::BasicResource) => resources(true),
+        Some(Argument::ComplexResource) => resources(false),
+        Some(Argument::Number) | None => vec![]
+    };
+    let matching =
+        candidates.into_iter().filter(|candidate| candidate.to_lowercase().starts_with(&prefix.to_lowercase()));
+    (start, matching.collect())
+}
+
+/// Completes the command line with the ids of the game as they were before the last command.
+#[derive(Default)]
+struct ReplHelper {
+    planets: Vec<ID>,
+    explorers: Vec<ID>
+}
+
+impl ReplHelper {
+    fn update(&mut self, orchestrator: &Orchestrator) {
+        self.planets = orchestrator.get_alive_planets();
+        self.planets.sort_unstable();
+        self.explorers = orchestrator.get_explorer_ids();
+        self.explorers.sort_unstable();
+    }
+}
+
+impl Completer for ReplHelper {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, candidates) = complete_line(&line[..pos], &self.planets, &self.explorers);
+        let pairs = candidates.into_iter().map(|candidate| Pair { display: candidate.clone(), replacement: candidate });
+        Ok((start, pairs.collect()))
+    }
+}
+
+impl Hinter for ReplHelper {
+    type Hint = String;
+}
+
+impl Highlighter for ReplHelper {}
+
+impl Validator for ReplHelper {}
+
+impl Helper for ReplHelper {}
+
+fn print_help() {
+    for (name, arguments, description) in COMMANDS {
+        println!("  {:<40} {description}", format!("{name} {arguments}"));
+    }
+}
+
+fn print_topology(orchestrator: &Orchestrator) {
+    let mut planets = orchestrator.get_alive_planets();
+    planets.sort_unstable();
+    let topology = orchestrator.get_topology();
+    for planet in planets {
+        let mut neighbours: Vec<ID> = topology
+            .iter()
+            .filter_map(|&(a, b)| {
+                if a == planet {
+                    Some(b)
+                } else if b == planet {
+                    Some(a)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        neighbours.sort_unstable();
+        neighbours.dedup();
+        let neighbours: Vec<String> = neighbours.iter().map(ToString::to_string).collect();
+        let planet_type = orchestrator.get_planet_types().get(&planet).map(|t| format!("{t:?}")).unwrap_or_default();
+        println!("  planet {planet} ({planet_type}) -> {}", neighbours.join(", "));
+    }
+    let mut explorers = orchestrator.get_explorer_ids();
+    explorers.sort_unstable();
+    for explorer in explorers {
+        let explorer_type = orchestrator.get_explorer_type(explorer).unwrap_or("unknown");
+        match orchestrator.get_explorer_current_planet(explorer) {
+            Some(planet) => println!("  explorer {explorer} ({explorer_type}) on planet {planet}"),
+            None => println!("  explorer {explorer} ({explorer_type}) is dead")
+        }
+    }
+}
+
+/// Runs a command, returns false when the game should stop.
+fn run_command(orchestrator: &mut Orchestrator, command: ReplCommand) -> Result<bool, OrchestratorError> {
+    match command {
+        ReplCommand::Action(action) => println!("{}", orchestrator.execute(action)?),
+        ReplCommand::Step { turns } => {
+            for _ in 0..turns {
+                if orchestrator.is_game_over() {
+                    break;
+                }
+                orchestrator.manual_step()?;
+            }
+            println!("time step {}", orchestrator.get_time());
+        }
+        ReplCommand::Topology => print_topology(orchestrator),
+        ReplCommand::Undo => match orchestrator.undo()? {
+            Some(action) => println!("undone {action:?}"),
+            None => println!("nothing to undo")
+        },
+        ReplCommand::Redo => match orchestrator.redo()? {
+            Some(action) => println!("redone {action:?}"),
+            None => println!("nothing to redo")
+        },
+        ReplCommand::Help => print_help(),
+        ReplCommand::Quit => return Ok(false)
+    }
+    Ok(true)
+}
+
+/// Plays a manual game driven by the commands typed in the terminal.
+pub(crate) fn run_repl() {
+    let mut orchestrator = match Orchestrator::from_config(OrchestratorMode::Manual).and_then(|mut orchestrator| {
+        orchestrator.manual_init()?;
+        Ok(orchestrator)
+    }) {
+        Ok(orchestrator) => orchestrator,
+        Err(e) => {
+            log::error!("Failed to create orchestrator: {e}");
+            std::process::exit(1);
+        }
+    };
+    let mut editor = match Editor::<ReplHelper, DefaultHistory>::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            log::error!("Failed to open the terminal: {e}");
+            std::process::exit(1);
+        }
+    };
+    editor.set_helper(Some(ReplHelper::default()));
+
+    println!("Manual game started, type 'help' for the commands");
+    loop {
+        if let Some(helper) = editor.helper_mut() {
+            helper.update(&orchestrator);
+        }
+        if let Some(reasons) = orchestrator.end_reasons() {
+            let reasons: Vec<String> = reasons.iter().map(ToString::to_string).collect();
+            println!("Game over after {} turns: {}", orchestrator.get_time(), reasons.join(", "));
+            break;
+        }
+
+        let line = match editor.readline(&format!("t={}> ", orchestrator.get_time())) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted | ReadlineError::Eof) => break,
+            Err(e) => {
+                log::error!("Failed to read the command: {e}");
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        if let Err(e) = editor.add_history_entry(line.as_str()) {
+            log::warn!("Failed to save the command in the history: {e}");
+        }
+
+        match parse_command(&line).map(|command| run_command(&mut orchestrator, command)) {
+            Ok(Ok(true)) => {}
+            Ok(Ok(false)) => break,
+            Ok(Err(e)) => println!("error: {e}"),
+            Err(e) => println!("{e}")
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_game::components::resource::{BasicResourceType, ComplexResourceType};
+
+    use super::*;
+
+    #[test]
+    fn test_parse_command() {
+        assert!(matches!(
+            parse_command("move 8 4"),
+            Ok(ReplCommand::Action(OrchestratorManualAction::MoveExplorer { explorer_id: 8, destination_planet_id: 4 }))
+        ));
+        assert!(matches!(
+            parse_command("  gen 8 carbon "),
+            Ok(ReplCommand::Action(OrchestratorManualAction::GenerateBasic {
+                explorer_id: 8,
+                resource: BasicResourceType::Carbon
+            }))
+        ));
+        assert!(matches!(
+            parse_command("combine 8 Water"),
+            Ok(ReplCommand::Action(OrchestratorManualAction::GenerateComplex {
+                explorer_id: 8,
+                resource: ComplexResourceType::Water
+            }))
+        ));
+        assert!(matches!(
+            parse_command("reset explorer 2"),
+            Ok(ReplCommand::Action(OrchestratorManualAction::ResetExplorer { explorer_id: 2 }))
+        ));
+        assert!(matches!(
+            parse_command("auto 10"),
+            Ok(ReplCommand::Action(OrchestratorManualAction::AdvanceTurns { turns: 10 }))
+        ));
+        assert!(matches!(parse_command("step"), Ok(ReplCommand::Step { turns: 1 })));
+        assert!(matches!(parse_command("step 3"), Ok(ReplCommand::Step { turns: 3 })));
+        assert!(matches!(parse_command("topology"), Ok(ReplCommand::Topology)));
+        assert!(matches!(parse_command("undo"), Ok(ReplCommand::Undo)));
+        assert!(matches!(parse_command("quit"), Ok(ReplCommand::Quit)));
+        assert!(matches!(parse_command("exit"), Ok(ReplCommand::Quit)));
+    }
+
+    #[test]
+    fn test_parse_command_errors() {
+        assert!(parse_command("sunray").is_err());
+        assert!(parse_command("sunray 1 2").is_err());
+        assert!(parse_command("sunray x").is_err());
+        assert!(parse_command("gen 8 Water").is_err());
+        assert!(parse_command("combine 8 Gold").is_err());
+        assert!(parse_command("stop moon 1").is_err());
+        assert!(parse_command("dance").is_err());
+    }
+
+    #[test]
+    fn test_complete_line() {
+        let planets = [1, 2, 12];
+        let explorers = [8];
+        assert_eq!(complete_line("su", &planets, &explorers), (0, vec!["sunray".to_string()]));
+        assert_eq!(complete_line("sunray 1", &planets, &explorers), (7, vec!["1".to_string(), "12".to_string()]));
+        assert_eq!(complete_line("move ", &planets, &explorers), (5, vec!["8".to_string()]));
+        assert_eq!(complete_line("gen 8 c", &planets, &explorers), (6, vec!["Carbon".to_string()]));
+        let complex = complete_line("combine 8 d", &planets, &explorers);
+        assert_eq!(complex, (10, vec!["Diamond".to_string(), "Dolphin".to_string()]));
+        assert_eq!(complete_line("stop e", &planets, &explorers), (5, vec!["explorer".to_string()]));
+        assert_eq!(complete_line("stop explorer ", &planets, &explorers), (14, vec!["8".to_string()]));
+        assert!(complete_line("topology ", &planets, &explorers).1.is_empty());
+    }
+
+    #[test]
+    fn test_run_commands() {
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 3, vec![], 5).unwrap();
+        orchestrator.manual_init().unwrap();
+        let planet = orchestrator.get_alive_planets()[0];
+        assert!(run_command(&mut orchestrator, parse_command(&format!("sunray {planet}")).unwrap()).unwrap());
+        assert!(run_command(&mut orchestrator, parse_command("step 2").unwrap()).unwrap());
+        assert_eq!(orchestrator.get_time(), 2);
+        assert!(run_command(&mut orchestrator, parse_command("state 99").unwrap()).is_err());
+        assert!(!run_command(&mut orchestrator, parse_command("quit").unwrap()).unwrap());
+    }
+}
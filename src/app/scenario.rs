@@ -0,0 +1,267 @@
+//! Scripted scenarios (`--scenario <file>`): a galaxy and a list of timed steps, each one either a manual
+//! action or an expectation on the state of the game. The runner drives a manual orchestrator through the
+//! steps, so the same scenario always plays the same game. See the `scenarios` folder for examples.
+
+use common_game::components::resource::ResourceType;
+use common_game::utils::ID;
+use config::{Config, File, FileFormat};
+use serde::Deserialize;
+
+use crate::orchestrator::{GalaxyLayout, Orchestrator, OrchestratorError, OrchestratorManualAction, OrchestratorMode,
+                          resource_from_name};
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct Scenario {
+    #[serde(default)]
+    pub seed: u64,
+    pub galaxy: GalaxyLayout,
+    pub steps: Vec<ScenarioStep>
+}
+
+/// The actions and expectations of the same time step are run in file order, before the step ends.
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ScenarioStep {
+    pub time: u32,
+    #[serde(default)]
+    pub action: Option<ScenarioAction>,
+    #[serde(default)]
+    pub expect: Option<Expectation>
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ScenarioAction {
+    /// On every alive planet if `planet` is omitted
+    Sunray {
+        #[serde(default)]
+        planet: Option<ID>
+    },
+    /// On every alive planet if `planet` is omitted
+    Asteroid {
+        #[serde(default)]
+        planet: Option<ID>
+    },
+    Move { explorer: ID, planet: ID },
+    /// Basic or complex resource, by name (e.g. "Water")
+    Generate { explorer: ID, resource: String }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum Expectation {
+    PlanetDestroyed { planet: ID },
+    PlanetAlive { planet: ID },
+    ExplorerOn { explorer: ID, planet: ID },
+    ExplorerDead { explorer: ID },
+    BagContains { explorer: ID, resource: String, at_least: usize }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(crate) struct ScenarioReport {
+    pub expectations: usize,
+    pub failures: Vec<String>
+}
+
+impl ScenarioReport {
+    pub fn is_success(&self) -> bool { self.failures.is_empty() }
+}
+
+impl Scenario {
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        Self::from_source(File::with_name(path)).map_err(|e| format!("Failed to load scenario {path}: {e}"))
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub fn from_content(content: &str, format: FileFormat) -> Result<Self, String> {
+        Self::from_source(File::from_str(content, format)).map_err(|e| format!("Failed to parse scenario: {e}"))
+    }
+
+    fn from_source<S: config::Source + Send + Sync + 'static>(source: S) -> Result<Self, String> {
+        let scenario: Scenario =
+            Config::builder().add_source(source).build().and_then(Config::try_deserialize).map_err(|e| e.to_string())?;
+        scenario.validate()?;
+        Ok(scenario)
+    }
+
+    /// Checks that every step does exactly one thing and names known resources.
+    fn validate(&self) -> Result<(), String> {
+        self.galaxy.validate()?;
+        for step in &self.steps {
+            let resource = match (&step.action, &step.expect) {
+                (Some(_), Some(_)) | (None, None) =>
+                    return Err(format!("Step at time {} must have either an action or an expectation", step.time)),
+                (Some(ScenarioAction::Generate { resource, .. }), None)
+                | (None, Some(Expectation::BagContains { resource, .. })) => Some(resource),
+                _ => None
+            };
+            if let Some(resource) = resource
+                && resource_from_name(resource).is_none()
+            {
+                return Err(format!("Unknown resource '{resource}' at time {}", step.time));
+            }
+        }
+        Ok(())
+    }
+
+    /// Plays the scenario, the failed actions and expectations are collected in the report. An error is
+    /// returned only if the game itself cannot go on.
+    pub fn run(&self) -> Result<ScenarioReport, OrchestratorError> {
+        let mut orchestrator =
+            Orchestrator::from_layout_with_seed(OrchestratorMode::Manual, self.galaxy.clone(), self.seed)?;
+        orchestrator.manual_init()?;
+
+        let mut report = ScenarioReport::default();
+        let end = self.steps.iter().map(|step| step.time).max().unwrap_or(0);
+        for time in 0..=end {
+            for step in self.steps.iter().filter(|step| step.time == time) {
+                if let Some(action) = &step.action
+                    && let Err(e) = perform(&mut orchestrator, action)
+                {
+                    report.failures.push(format!("t={time}: {action:?} failed: {e}"));
+                }
+                if let Some(expectation) = &step.expect {
+                    report.expectations += 1;
+                    if let Err(reason) = check(&orchestrator, expectation) {
+                        report.failures.push(format!("t={time}: {reason}"));
+                    }
+                }
+            }
+            orchestrator.manual_step()?;
+        }
+        Ok(report)
+    }
+}
+
+fn perform(orchestrator: &mut Orchestrator, action: &ScenarioAction) -> Result<(), OrchestratorError> {
+    let targets = |planet: Option<ID>| planet.map_or_else(|| orchestrator.get_alive_planets(), |id| vec![id]);
+    let actions = match action {
+        ScenarioAction::Sunray { planet } => targets(*planet)
+            .into_iter()
+            .map(|planet_id| OrchestratorManualAction::SendSunray { planet_id })
+            .collect(),
+        ScenarioAction::Asteroid { planet } => targets(*planet)
+            .into_iter()
+            .map(|planet_id| OrchestratorManualAction::SendAsteroid { planet_id })
+            .collect(),
+        ScenarioAction::Move { explorer, planet } => vec![OrchestratorManualAction::MoveExplorer {
+            explorer_id: *explorer,
+            destination_planet_id: *planet
+        }],
+        ScenarioAction::Generate { explorer, resource } => vec![match resource_from_name(resource) {
+            Some(ResourceType::Basic(resource)) =>
+                OrchestratorManualAction::GenerateBasic { explorer_id: *explorer, resource },
+            Some(ResourceType::Complex(resource)) =>
+                OrchestratorManualAction::GenerateComplex { explorer_id: *explorer, resource },
+            None => return Err(OrchestratorError::Config(format!("Unknown resource '{resource}'")))
+        }]
+    };
+    // One at a time, so that the error belongs to the action that failed
+    for action in actions {
+        orchestrator.schedule_manual_action(action);
+        orchestrator.process_commands()?;
+    }
+    Ok(())
+}
+
+fn check(orchestrator: &Orchestrator, expectation: &Expectation) -> Result<(), String> {
+    match expectation {
+        Expectation::PlanetDestroyed { planet } if orchestrator.get_alive_planets().contains(planet) =>
+            Err(format!("expected planet {planet} to be destroyed, but it is alive")),
+        Expectation::PlanetAlive { planet } if !orchestrator.get_alive_planets().contains(planet) =>
+            Err(format!("expected planet {planet} to be alive, but it is destroyed")),
+        Expectation::ExplorerOn { explorer, planet } => match orchestrator.get_explorer_current_planet(*explorer) {
+            Some(current) if current == *planet => Ok(()),
+            Some(current) => Err(format!("expected explorer {explorer} on planet {planet}, but it is on {current}")),
+            None => Err(format!("expected explorer {explorer} on planet {planet}, but it is dead"))
+        },
+        Expectation::ExplorerDead { explorer } if orchestrator.get_explorer_current_planet(*explorer).is_some() =>
+            Err(format!("expected explorer {explorer} to be dead, but it is alive")),
+        Expectation::BagContains { explorer, resource, at_least } => {
+            let quantity = resource_from_name(resource)
+                .and_then(|resource| orchestrator.get_explorer_bag(*explorer)?.content.get(&resource).copied())
+                .unwrap_or(0);
+            if quantity >= *at_least {
+                Ok(())
+            } else {
+                Err(format!(
+                    "expected at least {at_least} {resource} in the bag of explorer {explorer}, found {quantity}"
+                ))
+            }
+        }
+        _ => Ok(())
+    }
+}
+
+/// Runs the scenario file and prints the outcome, exiting with an error code if it fails.
+pub(crate) fn run_scenario(path: &str) {
+    let report = Scenario::from_file(path).map_err(OrchestratorError::Config).and_then(|scenario| scenario.run());
+    match report {
+        Ok(report) if report.is_success() => println!("Scenario {path}: {} expectations passed", report.expectations),
+        Ok(report) => {
+            println!("Scenario {path} failed ({} expectations checked):", report.expectations);
+            for failure in &report.failures {
+                println!("  {failure}");
+            }
+            std::process::exit(1);
+        }
+        Err(e) => {
+            log::error!("Scenario {path} could not be played: {e}");
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCENARIO: &str = r#"
+        seed = 3
+
+        [galaxy]
+        planets = [{ id = 1, type = "rustrelli" }, { id = 2, type = "rust_eze" }]
+        edges = [[1, 2]]
+
+        [[steps]]
+        time = 0
+        action = { type = "asteroid", planet = 2 }
+
+        [[steps]]
+        time = 1
+        expect = { type = "planet_destroyed", planet = 2 }
+
+        [[steps]]
+        time = 1
+        expect = { type = "planet_destroyed", planet = 1 }
+    "#;
+
+    #[test]
+    fn test_failed_expectations_are_reported() {
+        let scenario = Scenario::from_content(SCENARIO, FileFormat::Toml).unwrap();
+        let report = scenario.run().unwrap();
+        assert_eq!(report.expectations, 2);
+        assert_eq!(report.failures, vec![
+            "t=1: expected planet 1 to be destroyed, but it is alive".to_string()
+        ]);
+    }
+
+    #[test]
+    fn test_step_with_action_and_expectation() {
+        let content = SCENARIO.replace(
+            "action = { type = \"asteroid\", planet = 2 }",
+            "action = { type = \"asteroid\", planet = 2 }\nexpect = { type = \"planet_alive\", planet = 1 }"
+        );
+        let error = Scenario::from_content(&content, FileFormat::Toml).unwrap_err();
+        assert!(error.contains("either an action or an expectation"));
+    }
+
+    #[test]
+    fn test_bundled_scenarios() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/scenarios");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let report = Scenario::from_file(path.to_str().unwrap()).unwrap().run().unwrap();
+            assert!(report.is_success(), "Scenario {} failed: {:?}", path.display(), report.failures);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+//! Round-robin tournament between explorer types (`--tournament N`): every pair of explorers plays a
+//! game on each of N seeds, and at the end of every game each explorer is scored with the configured
+//! `ScoringModel`. The leaderboard ranks the explorers by mean score, with a 95% confidence interval.
+
+use serde::Serialize;
+
+use crate::app::AppConfig;
+use crate::app::batch::{ExplorerResult, play};
+use crate::explorers::{BagContent, ExplorerBuilder, ExplorerFactory, ScoringModel};
+use crate::orchestrator::{Orchestrator, OrchestratorError, OrchestratorMode, resource_from_name};
+
+const Z_95: f64 = 1.96;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct LeaderboardEntry {
+    pub explorer_type: String,
+    pub games: usize,
+    pub mean_score: f64,
+    pub ci_low: f64,
+    pub ci_high: f64,
+    pub wins: usize,
+    pub draws: usize,
+    pub losses: usize,
+    pub deaths: usize
+}
+
+pub(crate) fn run_tournament(seeds: u32) {
+    let config = AppConfig::get();
+    let mut explorer_types: Vec<String> = config.explorers.iter().map(|name| name.to_ascii_lowercase()).collect();
+    explorer_types.sort_unstable();
+    explorer_types.dedup();
+    if explorer_types.len() < 2 {
+        explorer_types = ExplorerFactory::EXPLORER_NAMES.iter().map(ToString::to_string).collect();
+    }
+    let seed = config.seed.unwrap_or_else(rand::random);
+    log::info!("Tournament between {explorer_types:?} on {seeds} seeds, first seed {seed}");
+
+    let mut entries: Vec<(LeaderboardEntry, Vec<f64>)> = explorer_types
+        .iter()
+        .map(|explorer_type| (LeaderboardEntry { explorer_type: explorer_type.clone(), ..Default::default() }, vec![]))
+        .collect();
+
+    for game_seed in (0..seeds).map(|i| seed.wrapping_add(u64::from(i))) {
+        for a in 0..explorer_types.len() {
+            for b in a + 1..explorer_types.len() {
+                let explorers = vec![
+                    ExplorerFactory::make_from_name(&explorer_types[a]),
+                    ExplorerFactory::make_from_name(&explorer_types[b])
+                ];
+                match play_match(explorers, game_seed, &config.scoring) {
+                    Ok((score_a, died_a, score_b, died_b)) => {
+                        record_match(&mut entries, a, score_a, died_a, score_b);
+                        record_match(&mut entries, b, score_b, died_b, score_a);
+                    }
+                    Err(e) => log::error!(
+                        "Game {} vs {} with seed {game_seed} failed: {e}",
+                        explorer_types[a],
+                        explorer_types[b]
+                    )
+                }
+            }
+        }
+    }
+
+    let leaderboard = make_leaderboard(entries);
+    if config.json {
+        println!("{}", serde_json::to_string_pretty(&leaderboard).expect("Leaderboard is serializable"));
+    } else {
+        print_leaderboard(&leaderboard);
+    }
+}
+
+/// Plays a game between two explorers, returning their scores and whether they died.
+fn play_match(
+    explorers: Vec<Box<dyn ExplorerBuilder>>,
+    seed: u64,
+    scoring: &ScoringModel
+) -> Result<(f64, bool, f64, bool), OrchestratorError> {
+    let orchestrator =
+        Orchestrator::new_with_seed(OrchestratorMode::Auto, AppConfig::get().number_of_planets, explorers, seed)?;
+    let stats = play(orchestrator, seed)?;
+    let [result_a, result_b] = stats.explorers.as_slice() else {
+        let found = stats.explorers.len();
+        return Err(OrchestratorError::Config(format!("Expected 2 explorers in the game, found {found}")));
+    };
+    Ok((
+        scoring.score(&bag_content(result_a), result_a.died),
+        result_a.died,
+        scoring.score(&bag_content(result_b), result_b.died),
+        result_b.died
+    ))
+}
+
+fn bag_content(result: &ExplorerResult) -> BagContent {
+    let content = result.bag.iter().filter_map(|(name, &qty)| Some((resource_from_name(name)?, qty))).collect();
+    BagContent { content }
+}
+
+fn record_match(entries: &mut [(LeaderboardEntry, Vec<f64>)], index: usize, score: f64, died: bool, opponent: f64) {
+    let (entry, scores) = &mut entries[index];
+    scores.push(score);
+    entry.games += 1;
+    entry.deaths += usize::from(died);
+    match score.total_cmp(&opponent) {
+        std::cmp::Ordering::Greater => entry.wins += 1,
+        std::cmp::Ordering::Equal => entry.draws += 1,
+        std::cmp::Ordering::Less => entry.losses += 1
+    }
+}
+
+/// Fills the score statistics and sorts the entries by mean score.
+#[allow(clippy::cast_precision_loss)] // few games
+fn make_leaderboard(entries: Vec<(LeaderboardEntry, Vec<f64>)>) -> Vec<LeaderboardEntry> {
+    let mut leaderboard: Vec<LeaderboardEntry> = entries
+        .into_iter()
+        .map(|(mut entry, scores)| {
+            let n = scores.len() as f64;
+            if n > 0.0 {
+                entry.mean_score = scores.iter().sum::<f64>() / n;
+            }
+            let half_width = if n > 1.0 {
+                let variance = scores.iter().map(|s| (s - entry.mean_score).powi(2)).sum::<f64>() / (n - 1.0);
+                Z_95 * variance.sqrt() / n.sqrt()
+            } else {
+                0.0
+            };
+            entry.ci_low = entry.mean_score - half_width;
+            entry.ci_high = entry.mean_score + half_width;
+            entry
+        })
+        .collect();
+    leaderboard.sort_by(|a, b| b.mean_score.total_cmp(&a.mean_score));
+    leaderboard
+}
+
+fn print_leaderboard(leaderboard: &[LeaderboardEntry]) {
+    println!(
+        "{:<4} {:<12} {:>6} {:>10} {:>22} {:>5} {:>5} {:>6} {:>6}",
+        "Rank", "Explorer", "Games", "Score", "95% CI", "Wins", "Draws", "Losses", "Deaths"
+    );
+    for (rank, entry) in leaderboard.iter().enumerate() {
+        let confidence_interval = format!("[{:.2}, {:.2}]", entry.ci_low, entry.ci_high);
+        println!(
+            "{:<4} {:<12} {:>6} {:>10.2} {:>22} {:>5} {:>5} {:>6} {:>6}",
+            rank + 1,
+            entry.explorer_type,
+            entry.games,
+            entry.mean_score,
+            confidence_interval,
+            entry.wins,
+            entry.draws,
+            entry.losses,
+            entry.deaths
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_leaderboard() {
+        let mut entries: Vec<(LeaderboardEntry, Vec<f64>)> = ["a", "b"]
+            .iter()
+            .map(|name| (LeaderboardEntry { explorer_type: (*name).to_string(), ..Default::default() }, vec![]))
+            .collect();
+        for (score_a, score_b) in [(1.0, 5.0), (3.0, 5.0), (2.0, 2.0)] {
+            record_match(&mut entries, 0, score_a, false, score_b);
+            record_match(&mut entries, 1, score_b, true, score_a);
+        }
+
+        let leaderboard = make_leaderboard(entries);
+        assert_eq!(leaderboard[0].explorer_type, "b");
+        assert_eq!(leaderboard[0].mean_score, 4.0);
+        assert_eq!((leaderboard[0].wins, leaderboard[0].draws, leaderboard[0].deaths), (2, 1, 3));
+        assert_eq!(leaderboard[1].mean_score, 2.0);
+        assert_eq!(leaderboard[1].losses, 2);
+        // sample standard deviation 1
+        assert!((leaderboard[1].ci_high - (2.0 + Z_95 / 3.0_f64.sqrt())).abs() < 1e-9);
+        assert!(leaderboard[1].ci_low < 2.0);
+    }
+}
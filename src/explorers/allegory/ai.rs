@@ -1,4 +1,4 @@
-use common_game::components::resource::ResourceType;
+use common_game::components::resource::{BasicResourceType, ResourceType};
 use crate::explorers::BagContent;
 use crate::explorers::allegory::{knowledge::StrategyState::*, logging::emit_warning};
 use common_game::protocols::{
@@ -6,6 +6,7 @@ use common_game::protocols::{
     planet_explorer::PlanetToExplorer,
 };
 use crossbeam_channel::select;
+use rand::seq::SliceRandom;
 use common_game::protocols::orchestrator_explorer::ExplorerToOrchestrator::BagContentResponse;
 use crate::explorers::allegory::explorer::AllegoryExplorer;
 use crate::explorers::allegory::knowledge::PlanetKnowledge;
@@ -213,7 +214,10 @@ impl AllegoryExplorer {
     fn trivial_collecting(&mut self) -> Result<(), String> {
         let bag_content = BagContent::from_bag(&self.bag);
         
-        for resource in self.simple_resources_task.clone(){
+        let mut resources: Vec<(BasicResourceType, usize)> = self.simple_resources_task.clone().into_iter().collect();
+        resources.sort_unstable_by_key(|(res, _)| *res as u8);
+        resources.shuffle(&mut self.rng);
+        for resource in resources {
             // Check if we already have enough of this resource
             let owned_count = bag_content
                 .content
@@ -240,7 +244,13 @@ impl AllegoryExplorer {
     }
 
     fn trivial_crafting(&mut self) -> Result<(), String> {
-        for resource in self.task.clone() {
+        let mut tasks: Vec<(ResourceType, usize)> = self.task.clone().into_iter().collect();
+        tasks.sort_unstable_by_key(|(res, _)| match res {
+            ResourceType::Basic(basic) => (0, *basic as u8),
+            ResourceType::Complex(complex) => (1, *complex as u8),
+        });
+        tasks.shuffle(&mut self.rng);
+        for resource in tasks {
             match resource.0 {
                 ResourceType::Basic(_) => {}
                 ResourceType::Complex(complex_res) => {
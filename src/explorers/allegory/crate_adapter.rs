@@ -8,6 +8,8 @@ use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestrator, Orch
 use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
 use common_game::utils::ID;
 use crossbeam_channel::{Receiver, Sender};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 use std::collections::HashMap;
 use crate::explorers::allegory::bag::Bag;
 use crate::explorers::allegory::explorer::ExplorerMode::{Auto, Retired};
@@ -42,6 +44,7 @@ impl Explorer for AllegoryExplorer {
             knowledge: ExplorerKnowledge::default(),
             task,
             simple_resources_task: HashMap::new(),
+            rng: StdRng::from_os_rng(),
         };
         explorer.complex_to_simple_list();
         println!("{:?}", explorer.simple_resources_task);
@@ -122,4 +125,8 @@ impl Explorer for AllegoryExplorer {
         emit_info(self.id, "Concluded execution".to_string());
         Ok(())
     }
+
+    fn set_seed(&mut self, seed: u64) {
+        self.rng = StdRng::seed_from_u64(seed);
+    }
 }
@@ -10,6 +10,7 @@ use common_game::protocols::{
 };
 use common_game::utils::ID;
 use crossbeam_channel::{Receiver, Sender};
+use rand::rngs::StdRng;
 use std::collections::{HashMap, HashSet, VecDeque};
 use crate::explorers::BagContent;
 
@@ -36,6 +37,9 @@ pub struct AllegoryExplorer {
 
     pub task: HashMap<ResourceType, usize>,
     pub simple_resources_task: HashMap<BasicResourceType, usize>,
+
+    // Order of the tasks, seeded by the orchestrator so that a game can be reproduced
+    pub rng: StdRng,
 }
 
 #[allow(dead_code)]
@@ -81,7 +85,10 @@ impl AllegoryExplorer {
 
         while let Some(curr) = queue.pop_front() {
             if let Some(pk) = self.knowledge.get_planet_knowledge(curr) {
-                for &neighbor in pk.get_neighbors() {
+                // Sorted, the same galaxy is always explored in the same order
+                let mut neighbors: Vec<ID> = pk.get_neighbors().iter().copied().collect();
+                neighbors.sort_unstable();
+                for neighbor in neighbors {
                     // Check if neighbor is in targets (unexplored)
                     if targets.contains(&neighbor) {
                         // Reconstruct path to find the first step
@@ -6,6 +6,9 @@ use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestrator, Orch
 use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
 use common_game::utils::ID;
 use crossbeam_channel::{Receiver, Sender};
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rand::seq::SliceRandom;
 
 use crate::explorers::cetto::communication::{OrchestratorLoggingReceiver, OrchestratorLoggingSender,
                                              PlanetLoggingReceiver, PlanetLoggingSender};
@@ -29,7 +32,10 @@ pub struct CettoExplorer {
     bag: Bag,
 
     // Information collection
-    knowledge: ExplorerKnowledge
+    knowledge: ExplorerKnowledge,
+
+    // Picks the order of the planets and resources, seeded by the orchestrator so that a game can be reproduced
+    rng: StdRng
 }
 
 #[derive(PartialEq)]
@@ -125,10 +131,11 @@ impl Explorer for CettoExplorer {
             mode: ExplorerMode::Auto,
             rx_orchestrator: OrchestratorLoggingReceiver::new(rx_orchestrator, id, 0),
             tx_orchestrator: OrchestratorLoggingSender::new(tx_orchestrator, id, 0),
-            rx_planet: PlanetLoggingReceiver::new( rx_planet, id, 1),
+            rx_planet: PlanetLoggingReceiver::new(rx_planet, id, current_planet_id),
             tx_planets,
             bag: Bag::default(),
-            knowledge: ExplorerKnowledge::default()
+            knowledge: ExplorerKnowledge::default(),
+            rng: StdRng::from_os_rng()
         }
     }
 
@@ -151,6 +158,8 @@ impl Explorer for CettoExplorer {
             }
         }
     }
+
+    fn set_seed(&mut self, seed: u64) { self.rng = StdRng::seed_from_u64(seed); }
 }
 
 impl CettoExplorer {
@@ -271,7 +280,11 @@ impl CettoExplorer {
 
         let save_current_planet_id = self.current_planet_id;
 
-        for neighbor in self.knowledge.galaxy.connections[&self.current_planet_id].iter().copied().collect::<Vec<ID>>() {
+        let mut neighbors: Vec<ID> =
+            self.knowledge.galaxy.connections[&self.current_planet_id].iter().copied().collect();
+        neighbors.sort_unstable(); // The hash order is not reproducible, the seeded one is
+        neighbors.shuffle(&mut self.rng);
+        for neighbor in neighbors {
             if !visited.contains(&neighbor) {
                 self.move_explorer(neighbor)?;
                 self.dfs(visited)?;
@@ -313,7 +326,9 @@ impl CettoExplorer {
         }
 
         // Create complex resources if possible and needed
-        let complex = self.get_planet_complex_types()?;
+        let mut complex: Vec<ComplexResourceType> = self.get_planet_complex_types()?.into_iter().collect();
+        complex.sort_unstable_by_key(|comp| *comp as u8);
+        complex.shuffle(&mut self.rng);
         for comp in complex {
             if self.can_combine(comp, cells) {
                 self.handle_combine_resource_request(comp)?;
@@ -325,7 +340,9 @@ impl CettoExplorer {
         } // Again, to possibly lower the number of comms
 
         // Obtain basic resources if possible and needed
-        let basic = self.get_planet_basic_types()?;
+        let mut basic: Vec<BasicResourceType> = self.get_planet_basic_types()?.into_iter().collect();
+        basic.sort_unstable_by_key(|bas| *bas as u8);
+        basic.shuffle(&mut self.rng);
         for bas in basic {
             if !self.knowledge.basic_goal_completed(bas) && cells > 0 {
                 self.handle_generate_resource_request(bas)?;
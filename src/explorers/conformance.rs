@@ -0,0 +1,295 @@
+//! Protocol conformance harness for explorers. It plays the orchestrator and a planet with no resources
+//! and no charge, sends every `orchestrator_explorer` request to the explorer and checks its answers.
+//! A new explorer only needs a line in the test at the bottom to be checked.
+//!
+//! The checks run in order on the same explorer: a check that fails may leave it in a state that makes
+//! the following ones fail too, so the first failure is the one to look at.
+
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common_game::components::resource::BasicResourceType;
+use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestrator, ExplorerToOrchestratorKind as Kind,
+                                                    OrchestratorToExplorer};
+use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
+use common_game::utils::ID;
+use crossbeam_channel::{Receiver, RecvTimeoutError, Sender, unbounded};
+
+use crate::explorers::{BagContent, ExplorerBuilder};
+
+const EXPLORER_ID: ID = 1;
+const FIRST_PLANET: ID = 1;
+const SECOND_PLANET: ID = 2;
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug)]
+pub(crate) struct Check {
+    pub name: &'static str,
+    pub outcome: Result<(), String>
+}
+
+#[derive(Debug)]
+pub(crate) struct ConformanceReport {
+    pub explorer_type: String,
+    pub checks: Vec<Check>
+}
+
+impl ConformanceReport {
+    /// The names of the failed checks, in order.
+    pub fn failures(&self) -> Vec<&'static str> {
+        self.checks.iter().filter(|check| check.outcome.is_err()).map(|check| check.name).collect()
+    }
+}
+
+impl Display for ConformanceReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let passed = self.checks.len() - self.failures().len();
+        writeln!(f, "Explorer {}: {passed}/{} checks passed", self.explorer_type, self.checks.len())?;
+        for check in &self.checks {
+            match &check.outcome {
+                Ok(()) => writeln!(f, "  ok   {}", check.name)?,
+                Err(reason) => writeln!(f, "  FAIL {}: {reason}", check.name)?
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The fake orchestrator side of a running explorer.
+struct Harness {
+    thread: thread::JoinHandle<()>,
+    to_explorer: Sender<OrchestratorToExplorer>,
+    from_explorer: Receiver<ExplorerToOrchestrator<BagContent>>,
+    to_planet: Sender<ExplorerToPlanet> // given to the explorer when it moves, both planets are the same fake
+}
+
+/// Runs every check on a new explorer built by `builder`.
+pub(crate) fn check_explorer(builder: Box<dyn ExplorerBuilder>) -> ConformanceReport {
+    let explorer_type = builder.get_type_name();
+    let mut harness = Harness::start(builder);
+    let mut checks = Vec::new();
+    let mut check = |name: &'static str, outcome: Result<(), String>| checks.push(Check { name, outcome });
+
+    check("start", harness.ack(OrchestratorToExplorer::StartExplorerAI, Kind::StartExplorerAIResult));
+    check("current_planet", harness.expect_current_planet(FIRST_PLANET));
+    check(
+        "supported_resources",
+        harness.ack(OrchestratorToExplorer::SupportedResourceRequest, Kind::SupportedResourceResult)
+    );
+    check(
+        "supported_combinations",
+        harness.ack(OrchestratorToExplorer::SupportedCombinationRequest, Kind::SupportedCombinationResult)
+    );
+    check("generate_without_energy", harness.expect_failed_generation());
+    check("bag_content", harness.expect_bag_content());
+    check("stop", harness.ack(OrchestratorToExplorer::StopExplorerAI, Kind::StopExplorerAIResult));
+    check("bag_content_while_stopped", harness.expect_bag_content());
+    check("restart", harness.ack(OrchestratorToExplorer::StartExplorerAI, Kind::StartExplorerAIResult));
+    let to_planet = Some(harness.to_planet.clone());
+    check("move", harness.expect_move(to_planet, SECOND_PLANET));
+    check("current_planet_after_move", harness.expect_current_planet(SECOND_PLANET));
+    check("reset", harness.ack(OrchestratorToExplorer::ResetExplorerAI, Kind::ResetExplorerAIResult));
+    // Sent by the orchestrator when a travel is not possible, the explorer stays where it is
+    check("move_without_sender", harness.expect_move(None, SECOND_PLANET));
+    check("kill", harness.expect_kill());
+
+    ConformanceReport { explorer_type, checks }
+}
+
+impl Harness {
+    fn start(builder: Box<dyn ExplorerBuilder>) -> Self {
+        let (to_explorer, rx_orchestrator) = unbounded();
+        let (tx_orchestrator, from_explorer) = unbounded();
+        let (to_planet, rx_planet) = unbounded();
+        let (tx_explorer, rx_from_planet) = unbounded();
+        let builder = builder
+            .with_id(EXPLORER_ID)
+            .with_current_planet(FIRST_PLANET)
+            .with_orchestrator_rx(rx_orchestrator)
+            .with_orchestrator_tx(tx_orchestrator)
+            .with_current_planet_tx(to_planet.clone())
+            .with_planet_rx(rx_from_planet)
+            .with_seed(0);
+
+        thread::spawn(move || run_fake_planet(&rx_planet, &tx_explorer));
+        // Built in its own thread like in a game, explorers are not required to be Send once built
+        let thread = thread::spawn(move || match builder.build() {
+            Ok(mut explorer) =>
+                if let Err(e) = explorer.run() {
+                    log::warn!("Explorer under test terminated with error: {e}");
+                },
+            Err(e) => log::error!("Failed to build the explorer under test: {e}")
+        });
+        Harness { thread, to_explorer, from_explorer, to_planet }
+    }
+
+    fn ack(&mut self, request: OrchestratorToExplorer, expected: Kind) -> Result<(), String> {
+        self.exchange(request, expected).map(drop)
+    }
+
+    /// Sends the request and waits for the answer of the expected kind. The requests of the explorer that
+    /// may come in the meantime are served: the galaxy has no neighbours and every travel is refused.
+    fn exchange(
+        &mut self,
+        request: OrchestratorToExplorer,
+        expected: Kind
+    ) -> Result<ExplorerToOrchestrator<BagContent>, String> {
+        if self.thread.is_finished() {
+            return Err("the explorer thread terminated before the request".to_string());
+        }
+        self.to_explorer.send(request).map_err(|_| "the explorer dropped its receiver".to_string())?;
+        let deadline = Instant::now() + RESPONSE_TIMEOUT;
+        let mut travelling = false;
+        loop {
+            let message = match self.from_explorer.recv_deadline(deadline) {
+                Ok(message) => message,
+                Err(RecvTimeoutError::Timeout) => return Err(format!("no {expected:?} within {RESPONSE_TIMEOUT:?}")),
+                Err(RecvTimeoutError::Disconnected) => return Err("the explorer thread terminated".to_string())
+            };
+            if message.explorer_id() != EXPLORER_ID {
+                return Err(format!("answered with explorer ID {} instead of {EXPLORER_ID}", message.explorer_id()));
+            }
+            match message {
+                ExplorerToOrchestrator::NeighborsRequest { .. } => {
+                    let _ = self.to_explorer.send(OrchestratorToExplorer::NeighborsResponse { neighbors: vec![] });
+                }
+                ExplorerToOrchestrator::TravelToPlanetRequest { current_planet_id, .. } => {
+                    travelling = true;
+                    let _ = self.to_explorer.send(OrchestratorToExplorer::MoveToPlanet {
+                        sender_to_new_planet: None,
+                        planet_id: current_planet_id
+                    });
+                }
+                ExplorerToOrchestrator::MovedToPlanetResult { .. } if travelling => travelling = false,
+                message if Kind::from(&message) == expected => return Ok(message),
+                other => return Err(format!("expected {expected:?}, got {other:?}"))
+            }
+        }
+    }
+
+    fn expect_current_planet(&mut self, planet_id: ID) -> Result<(), String> {
+        match self.exchange(OrchestratorToExplorer::CurrentPlanetRequest, Kind::CurrentPlanetResult)? {
+            ExplorerToOrchestrator::CurrentPlanetResult { planet_id: current, .. } if current == planet_id => Ok(()),
+            other => Err(format!("expected to be on planet {planet_id}, got {other:?}"))
+        }
+    }
+
+    fn expect_failed_generation(&mut self) -> Result<(), String> {
+        let request = OrchestratorToExplorer::GenerateResourceRequest { to_generate: BasicResourceType::Carbon };
+        match self.exchange(request, Kind::GenerateResourceResponse)? {
+            ExplorerToOrchestrator::GenerateResourceResponse { generated: Err(_), .. } => Ok(()),
+            other => Err(format!("reported a generation the planet could not do: {other:?}"))
+        }
+    }
+
+    fn expect_bag_content(&mut self) -> Result<(), String> {
+        match self.exchange(OrchestratorToExplorer::BagContentRequest, Kind::BagContentResponse)? {
+            ExplorerToOrchestrator::BagContentResponse { bag_content, .. }
+                if bag_content.content.values().all(|&qty| qty == 0) =>
+                Ok(()),
+            other => Err(format!("has resources the planet never gave: {other:?}"))
+        }
+    }
+
+    fn expect_move(&mut self, sender: Option<Sender<ExplorerToPlanet>>, planet_id: ID) -> Result<(), String> {
+        let request = OrchestratorToExplorer::MoveToPlanet { sender_to_new_planet: sender, planet_id };
+        match self.exchange(request, Kind::MovedToPlanetResult)? {
+            ExplorerToOrchestrator::MovedToPlanetResult { planet_id: moved, .. } if moved == planet_id => Ok(()),
+            other => Err(format!("expected to be on planet {planet_id}, got {other:?}"))
+        }
+    }
+
+    /// The explorer must acknowledge the kill and terminate its thread.
+    fn expect_kill(&mut self) -> Result<(), String> {
+        self.exchange(OrchestratorToExplorer::KillExplorer, Kind::KillExplorerResult)?;
+        let deadline = Instant::now() + RESPONSE_TIMEOUT;
+        while !self.thread.is_finished() {
+            if Instant::now() > deadline {
+                return Err(format!("the explorer thread is still running {RESPONSE_TIMEOUT:?} after the kill"));
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        Ok(())
+    }
+}
+
+/// A planet without resources nor charged cells, answering until the explorer drops its senders.
+fn run_fake_planet(requests: &Receiver<ExplorerToPlanet>, to_explorer: &Sender<PlanetToExplorer>) {
+    while let Ok(request) = requests.recv() {
+        let response = match request {
+            ExplorerToPlanet::AvailableEnergyCellRequest { .. } =>
+                PlanetToExplorer::AvailableEnergyCellResponse { available_cells: 0 },
+            ExplorerToPlanet::SupportedResourceRequest { .. } =>
+                PlanetToExplorer::SupportedResourceResponse { resource_list: HashSet::new() },
+            ExplorerToPlanet::SupportedCombinationRequest { .. } =>
+                PlanetToExplorer::SupportedCombinationResponse { combination_list: HashSet::new() },
+            ExplorerToPlanet::GenerateResourceRequest { .. } =>
+                PlanetToExplorer::GenerateResourceResponse { resource: None },
+            // Cannot happen with an empty bag, answering would need the ingredients back
+            ExplorerToPlanet::CombineResourceRequest { .. } => {
+                log::warn!("Fake planet ignoring a combination request");
+                continue;
+            }
+        };
+        if to_explorer.send(response).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explorers::ExplorerFactory;
+
+    fn check_bundled(name: &str) -> ConformanceReport {
+        let report = check_explorer(ExplorerFactory::make_from_name(&name.to_string()));
+        println!("{report}");
+        report
+    }
+
+    #[test]
+    fn test_samufaz_conformance() {
+        // Refusing an unsolicited move without a sender terminates its thread, so the kill cannot succeed
+        assert_eq!(check_bundled("samufaz").failures(), vec!["move_without_sender", "kill"]);
+    }
+
+    #[test]
+    fn test_example_conformance() {
+        // A skeleton that never answers
+        let report = check_bundled("example");
+        assert!(report.failures().contains(&"start"));
+    }
+
+    #[test]
+    fn test_cetto_conformance() {
+        // A generation refused by the planet terminates its thread, the later checks find it gone
+        assert_eq!(check_bundled("cetto").failures(), vec![
+            "generate_without_energy",
+            "bag_content",
+            "stop",
+            "bag_content_while_stopped",
+            "restart",
+            "move",
+            "current_planet_after_move",
+            "reset",
+            "move_without_sender",
+            "kill"
+        ]);
+    }
+
+    #[test]
+    fn test_allegory_conformance() {
+        // Every request starts an AI turn that ends with an unrequested bag content, taken as the answer to
+        // the next request. Whether it lands on the second or the third request after the first turn depends
+        // on the order the explorer receives its planet and orchestrator messages in.
+        let report = check_bundled("allegory");
+        let failures = report.failures();
+        assert!(matches!(failures[0], "supported_resources" | "supported_combinations"), "{report}");
+        assert!(!failures.contains(&"start") && !failures.contains(&"current_planet"));
+        let first_failure = report.checks.iter().find_map(|check| check.outcome.as_ref().err()).unwrap();
+        assert!(first_failure.contains("got BagContentResponse"), "{first_failure}");
+    }
+}
@@ -24,6 +24,10 @@ pub trait Explorer {
         Self: Sized;
 
     fn run(&mut self) -> Result<(), String>;
+
+    /// Called once after creation. Explorers that make random choices should seed their generator
+    /// with this, so that a game can be reproduced.
+    fn set_seed(&mut self, _seed: u64) {}
 }
 
 pub(crate) trait ExplorerBuilder: Send {
@@ -46,6 +50,10 @@ pub(crate) trait ExplorerBuilder: Send {
     ) -> Box<dyn ExplorerBuilder>;
     fn with_id(self: Box<Self>, id: common_game::utils::ID) -> Box<dyn ExplorerBuilder>;
     fn with_current_planet(self: Box<Self>, planet_id: common_game::utils::ID) -> Box<dyn ExplorerBuilder>;
+    fn with_seed(self: Box<Self>, seed: u64) -> Box<dyn ExplorerBuilder>;
+    /// The name used to create this explorer from the `ExplorerFactory`
+    fn with_type_name(self: Box<Self>, type_name: &str) -> Box<dyn ExplorerBuilder>;
+    fn get_type_name(&self) -> String;
 }
 
 pub(crate) struct ExplorerBuilderImpl<T: Explorer> {
@@ -55,6 +63,8 @@ pub(crate) struct ExplorerBuilderImpl<T: Explorer> {
     tx_current_planet: Option<crossbeam_channel::Sender<ExplorerToPlanet>>,
     id: Option<common_game::utils::ID>,
     current_planet: Option<common_game::utils::ID>,
+    seed: Option<u64>,
+    type_name: Option<String>,
     _phantom: std::marker::PhantomData<T>
 }
 
@@ -68,6 +78,8 @@ impl<T: Explorer> ExplorerBuilderImpl<T> {
 
             id: None,
             current_planet: None,
+            seed: None,
+            type_name: None,
             _phantom: std::marker::PhantomData
         }
     }
@@ -93,14 +105,18 @@ impl<T: Explorer + 'static + Send> ExplorerBuilder for ExplorerBuilderImpl<T> {
         if self.tx_current_planet.is_none() {
             return Err("Current planet TX channel not set".to_string());
         }
-        Ok(Box::new(T::new(
+        let mut explorer = T::new(
             self.id.unwrap(),
             self.current_planet.unwrap(),
             self.rx_orchestrator.unwrap(),
             self.tx_orchestrator.unwrap(),
             self.tx_current_planet.unwrap(),
             self.rx_planet.unwrap()
-        )))
+        );
+        if let Some(seed) = self.seed {
+            explorer.set_seed(seed);
+        }
+        Ok(Box::new(explorer))
     }
 
     fn with_orchestrator_rx(
@@ -138,4 +154,14 @@ impl<T: Explorer + 'static + Send> ExplorerBuilder for ExplorerBuilderImpl<T> {
     fn with_current_planet(self: Box<Self>, planet_id: common_game::utils::ID) -> Box<dyn ExplorerBuilder> {
         Box::new(ExplorerBuilderImpl { current_planet: Some(planet_id), ..*self })
     }
+
+    fn with_seed(self: Box<Self>, seed: u64) -> Box<dyn ExplorerBuilder> {
+        Box::new(ExplorerBuilderImpl { seed: Some(seed), ..*self })
+    }
+
+    fn with_type_name(self: Box<Self>, type_name: &str) -> Box<dyn ExplorerBuilder> {
+        Box::new(ExplorerBuilderImpl { type_name: Some(type_name.to_string()), ..*self })
+    }
+
+    fn get_type_name(&self) -> String { self.type_name.clone().unwrap_or_else(|| "unknown".to_string()) }
 }
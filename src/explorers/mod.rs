@@ -2,12 +2,20 @@
 //! All the different explorers lives in a submodule.
 
 mod cetto;
+#[cfg(test)]
+mod conformance;
 mod example;
 mod explorer;
 mod allegory;
+mod remote;
 mod samufaz;
+mod scoring;
 
 pub(crate) use explorer::{BagContent, Explorer, ExplorerBuilder};
+pub(crate) use remote::run_remote_explorer;
+#[cfg(test)]
+pub(crate) use explorer::ExplorerBuilderImpl;
+pub(crate) use scoring::ScoringModel;
 
 pub(crate) type ExampleExplorerBuilder = explorer::ExplorerBuilderImpl<example::ExampleExplorer>;
 pub(crate) type CettoExplorerBuilder = explorer::ExplorerBuilderImpl<cetto::CettoExplorer>;
@@ -18,17 +26,29 @@ pub(crate) type SamuFazExplorerBuilder =
 pub(crate) struct ExplorerFactory;
 
 impl ExplorerFactory {
+    pub const EXPLORER_NAMES: [&str; 4] = ["example", "cetto", "allegory", "samufaz"];
+
+    /// Whether `make_from_name` knows the type, instead of falling back to `example`.
+    pub fn is_known_type(type_name: &str) -> bool {
+        type_name.starts_with("remote:") || Self::EXPLORER_NAMES.contains(&type_name.to_ascii_lowercase().as_str())
+    }
+
+    /// `remote:<address>` waits for an explorer played by another process, see `remote::protocol`.
     pub fn make_from_name(type_name: &String) -> Box<dyn ExplorerBuilder> {
+        if let Some(address) = type_name.strip_prefix("remote:") {
+            return Box::new(remote::RemoteExplorerBuilder::listen(address)).with_type_name("remote");
+        }
         #[allow(clippy::single_match_else)] // more explorers are added in personal branches
-        match type_name.to_ascii_lowercase().as_str() {
-            "example" => Box::new(ExampleExplorerBuilder::new()),
-            "cetto" => Box::new(CettoExplorerBuilder::new()),
-            "allegory" => Box::new(AllegoryExplorerBuilder::new()),
-            "samufaz" => Box::new(SamuFazExplorerBuilder::new()),
+        let (name, builder): (&str, Box<dyn ExplorerBuilder>) = match type_name.to_ascii_lowercase().as_str() {
+            "example" => ("example", Box::new(ExampleExplorerBuilder::new())),
+            "cetto" => ("cetto", Box::new(CettoExplorerBuilder::new())),
+            "allegory" => ("allegory", Box::new(AllegoryExplorerBuilder::new())),
+            "samufaz" => ("samufaz", Box::new(SamuFazExplorerBuilder::new())),
             _ => {
                 log::warn!("Explorer type '{type_name}' not recognized. Defaulting to 'example' explorer.");
-                Box::new(ExampleExplorerBuilder::new())
+                ("example", Box::new(ExampleExplorerBuilder::new()))
             }
-        }
+        };
+        builder.with_type_name(name)
     }
 }
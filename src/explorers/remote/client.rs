@@ -0,0 +1,174 @@
+//! Reference remote explorer, a starting point for explorers written in other processes (or languages).
+//! It answers every request of the orchestrator and, while its AI is running, generates the first resource
+//! supported by its planet at every turn.
+
+use std::io::{BufRead, BufReader, Write};
+
+use common_game::utils::ID;
+
+use crate::explorers::remote::connection::RemoteStream;
+use crate::explorers::remote::protocol::{FromRemote, ToRemote};
+
+pub(crate) struct RemoteClient {
+    reader: BufReader<RemoteStream>,
+    writer: RemoteStream,
+    explorer_id: ID,
+    planet_id: ID,
+    active: bool,
+    supported_resources: Vec<String> // of the current planet, asked once
+}
+
+impl RemoteClient {
+    /// Connects to a game waiting for a remote explorer and reads its `hello`.
+    pub fn connect(address: &str) -> Result<Self, String> {
+        let writer = RemoteStream::connect(address)?;
+        let reader = BufReader::new(writer.try_clone()?);
+        let mut client =
+            RemoteClient { reader, writer, explorer_id: 0, planet_id: 0, active: false, supported_resources: vec![] };
+        match client.recv()? {
+            ToRemote::Hello { explorer_id, planet_id } => {
+                client.explorer_id = explorer_id;
+                client.planet_id = planet_id;
+                Ok(client)
+            }
+            other => Err(format!("Expected hello, received {other:?}"))
+        }
+    }
+
+    pub fn send(&mut self, message: &FromRemote) -> Result<(), String> {
+        let json = serde_json::to_string(message).map_err(|e| format!("Failed to serialize {message:?}: {e}"))?;
+        writeln!(self.writer, "{json}").map_err(|e| format!("Failed to send to the game: {e}"))
+    }
+
+    pub fn recv(&mut self) -> Result<ToRemote, String> {
+        let mut line = String::new();
+        loop {
+            line.clear();
+            match self.reader.read_line(&mut line) {
+                Ok(0) => return Err("The game closed the connection".to_string()),
+                Ok(_) if line.trim().is_empty() => {}
+                Ok(_) => return serde_json::from_str(&line).map_err(|e| format!("Invalid message '{line}': {e}")),
+                Err(e) => return Err(format!("Failed to read from the game: {e}"))
+            }
+        }
+    }
+
+    /// Plays until the explorer is killed.
+    pub fn run(&mut self) -> Result<(), String> {
+        loop {
+            let message = self.recv()?;
+            let answer = match message {
+                ToRemote::StartExplorerAi => {
+                    self.active = true;
+                    FromRemote::StartExplorerAiResult
+                }
+                ToRemote::StopExplorerAi => {
+                    self.active = false;
+                    FromRemote::StopExplorerAiResult
+                }
+                ToRemote::ResetExplorerAi => {
+                    self.supported_resources.clear();
+                    FromRemote::ResetExplorerAiResult
+                }
+                ToRemote::KillExplorer => {
+                    self.send(&FromRemote::KillExplorerResult)?;
+                    return Ok(());
+                }
+                ToRemote::MoveToPlanet { planet_id, moved } => {
+                    if moved {
+                        self.planet_id = planet_id;
+                        self.supported_resources.clear();
+                    }
+                    FromRemote::MovedToPlanetResult
+                }
+                ToRemote::CurrentPlanetRequest => FromRemote::CurrentPlanetResult,
+                ToRemote::SupportedResourceRequest =>
+                    FromRemote::SupportedResourceResult { resources: self.supported_resources()? },
+                ToRemote::SupportedCombinationRequest => match self.ask(FromRemote::SupportedCombinationRequest)? {
+                    ToRemote::SupportedCombinationResponse { resources } =>
+                        FromRemote::SupportedCombinationResult { resources },
+                    _ => FromRemote::SupportedCombinationResult { resources: vec![] }
+                },
+                ToRemote::GenerateResourceRequest { resource } =>
+                    FromRemote::GenerateResourceResponse { error: self.generate(resource)? },
+                ToRemote::CombineResourceRequest { resource } =>
+                    match self.ask(FromRemote::CombineResourceRequest { resource })? {
+                        ToRemote::CombineResourceResponse { error, .. } =>
+                            FromRemote::CombineResourceResponse { error },
+                        other => FromRemote::CombineResourceResponse { error: Some(format!("{other:?}")) }
+                    },
+                ToRemote::BagContentRequest => {
+                    if self.active {
+                        self.play_turn()?;
+                    }
+                    FromRemote::BagContentResponse
+                }
+                other => {
+                    log::warn!("Remote explorer {}: unexpected {other:?}", self.explorer_id);
+                    continue;
+                }
+            };
+            self.send(&answer)?;
+        }
+    }
+
+    /// Sends a request to the current planet and waits for its answer.
+    fn ask(&mut self, request: FromRemote) -> Result<ToRemote, String> {
+        self.send(&request)?;
+        loop {
+            match self.recv()? {
+                ToRemote::Error { message } => return Err(message),
+                ToRemote::Hello { .. } => {}
+                response => return Ok(response)
+            }
+        }
+    }
+
+    fn supported_resources(&mut self) -> Result<Vec<String>, String> {
+        if self.supported_resources.is_empty()
+            && let ToRemote::SupportedResourceResponse { resources } =
+                self.ask(FromRemote::SupportedResourceRequest)?
+        {
+            self.supported_resources = resources;
+        }
+        Ok(self.supported_resources.clone())
+    }
+
+    /// Returns the reason of the failure, if any.
+    fn generate(&mut self, resource: String) -> Result<Option<String>, String> {
+        match self.ask(FromRemote::GenerateResourceRequest { resource: resource.clone() })? {
+            ToRemote::GenerateResourceResponse { resource: Some(_) } => Ok(None),
+            ToRemote::GenerateResourceResponse { resource: None } =>
+                Ok(Some(format!("Planet {} could not generate {resource}", self.planet_id))),
+            other => Ok(Some(format!("{other:?}")))
+        }
+    }
+
+    fn play_turn(&mut self) -> Result<(), String> {
+        let response = self.ask(FromRemote::AvailableEnergyCellRequest)?;
+        let ToRemote::AvailableEnergyCellResponse { available_cells } = response else {
+            return Ok(());
+        };
+        if available_cells == 0 {
+            return Ok(());
+        }
+        if let Some(resource) = self.supported_resources()?.into_iter().next()
+            && let Some(error) = self.generate(resource)?
+        {
+            log::debug!("Remote explorer {}: {error}", self.explorer_id);
+        }
+        Ok(())
+    }
+}
+
+/// Plays the reference remote explorer in a game waiting on `address`.
+pub(crate) fn run_remote_explorer(address: &str) {
+    let result = RemoteClient::connect(address).and_then(|mut client| {
+        log::info!("Connected to {address} as explorer {} on planet {}", client.explorer_id, client.planet_id);
+        client.run()
+    });
+    if let Err(e) = result {
+        log::error!("Remote explorer terminated with error: {e}");
+        std::process::exit(1);
+    }
+}
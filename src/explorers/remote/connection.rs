@@ -0,0 +1,103 @@
+//! The socket between the game and a remote explorer: TCP (`127.0.0.1:7000`) or, on unix, a Unix socket
+//! (`unix:/tmp/explorer.sock`).
+
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub(crate) enum RemoteStream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream)
+}
+
+impl RemoteStream {
+    /// Listens on the address until a remote explorer connects, or the timeout expires.
+    pub fn accept(address: &str, timeout: Duration) -> Result<Self, String> {
+        let deadline = Instant::now() + timeout;
+        #[cfg(unix)]
+        if let Some(path) = address.strip_prefix("unix:") {
+            // A socket file left by a previous game would make the bind fail
+            let _ = std::fs::remove_file(path);
+            let listener = UnixListener::bind(path).map_err(|e| format!("Failed to listen on {address}: {e}"))?;
+            listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+            let stream = wait_for_client(address, deadline, || listener.accept().map(|(stream, _)| stream))?;
+            stream.set_nonblocking(false).map_err(|e| e.to_string())?;
+            return Ok(RemoteStream::Unix(stream));
+        }
+        let listener = TcpListener::bind(address).map_err(|e| format!("Failed to listen on {address}: {e}"))?;
+        listener.set_nonblocking(true).map_err(|e| e.to_string())?;
+        let stream = wait_for_client(address, deadline, || listener.accept().map(|(stream, _)| stream))?;
+        stream.set_nonblocking(false).map_err(|e| e.to_string())?;
+        Ok(RemoteStream::Tcp(stream))
+    }
+
+    pub fn connect(address: &str) -> Result<Self, String> {
+        #[cfg(unix)]
+        if let Some(path) = address.strip_prefix("unix:") {
+            return UnixStream::connect(path)
+                .map(RemoteStream::Unix)
+                .map_err(|e| format!("Failed to connect to {address}: {e}"));
+        }
+        TcpStream::connect(address).map(RemoteStream::Tcp).map_err(|e| format!("Failed to connect to {address}: {e}"))
+    }
+
+    /// A second handle on the same socket, to read and write from different threads.
+    pub fn try_clone(&self) -> Result<Self, String> {
+        match self {
+            RemoteStream::Tcp(stream) => stream.try_clone().map(RemoteStream::Tcp),
+            #[cfg(unix)]
+            RemoteStream::Unix(stream) => stream.try_clone().map(RemoteStream::Unix)
+        }
+        .map_err(|e| format!("Failed to clone the remote explorer socket: {e}"))
+    }
+}
+
+fn wait_for_client<S>(
+    address: &str,
+    deadline: Instant,
+    mut accept: impl FnMut() -> std::io::Result<S>
+) -> Result<S, String> {
+    log::info!("Waiting for a remote explorer to connect to {address}");
+    loop {
+        match accept() {
+            Ok(stream) => return Ok(stream),
+            Err(e) if e.kind() == ErrorKind::WouldBlock && Instant::now() < deadline =>
+                thread::sleep(Duration::from_millis(50)),
+            Err(e) if e.kind() == ErrorKind::WouldBlock =>
+                return Err(format!("No remote explorer connected to {address}")),
+            Err(e) => return Err(format!("Failed to accept a remote explorer on {address}: {e}"))
+        }
+    }
+}
+
+impl Read for RemoteStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            RemoteStream::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            RemoteStream::Unix(stream) => stream.read(buf)
+        }
+    }
+}
+
+impl Write for RemoteStream {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        match self {
+            RemoteStream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            RemoteStream::Unix(stream) => stream.write(buf)
+        }
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        match self {
+            RemoteStream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            RemoteStream::Unix(stream) => stream.flush()
+        }
+    }
+}
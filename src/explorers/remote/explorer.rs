@@ -0,0 +1,528 @@
+//! The game side of a remote explorer: a `RemoteExplorer` runs in the explorer thread like any other explorer
+//! and relays the channels of the orchestrator and of the current planet to the socket. It keeps the bag,
+//! since resources cannot cross the socket, so the remote explorer only names the resources it wants.
+
+use std::collections::{HashMap, HashSet};
+use std::io::{BufRead, BufReader, Write};
+use std::thread;
+use std::time::Duration;
+
+use common_game::components::resource::{BasicResource, BasicResourceType, ComplexResource, ComplexResourceRequest,
+                                        ComplexResourceType, GenericResource, ResourceType};
+use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestrator, OrchestratorToExplorer};
+use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
+use common_game::utils::ID;
+use crossbeam_channel::{Receiver, Sender, select, unbounded};
+
+use crate::app::AppConfig;
+use crate::explorers::remote::connection::RemoteStream;
+use crate::explorers::remote::protocol::{FromRemote, ToRemote};
+use crate::explorers::{BagContent, Explorer, ExplorerBuilder};
+use crate::orchestrator::{get_recipe, resource_from_name, resource_name};
+
+/// An explorer played by another process. It relays the messages of the orchestrator and of the current
+/// planet to the socket and back, and keeps the resources of the remote explorer.
+pub struct RemoteExplorer {
+    id: ID,
+    current_planet_id: ID,
+    rx_orchestrator: Receiver<OrchestratorToExplorer>,
+    tx_orchestrator: Sender<ExplorerToOrchestrator<BagContent>>,
+    tx_planet: Sender<ExplorerToPlanet>,
+    rx_planet: Receiver<PlanetToExplorer>,
+    connection: Option<RemoteStream>,
+    bag: Bag
+}
+
+#[derive(Default)]
+struct Bag {
+    basic_resources: Vec<BasicResource>,
+    complex_resources: Vec<ComplexResource>
+}
+
+impl Bag {
+    fn count(&self, resource: ResourceType) -> usize {
+        match resource {
+            ResourceType::Basic(basic) => self.basic_resources.iter().filter(|res| res.get_type() == basic).count(),
+            ResourceType::Complex(complex) =>
+                self.complex_resources.iter().filter(|res| res.get_type() == complex).count(),
+        }
+    }
+
+    fn take_basic(&mut self, basic: BasicResourceType) -> Result<BasicResource, String> {
+        let index = self.basic_resources.iter().position(|res| res.get_type() == basic);
+        index.map(|index| self.basic_resources.remove(index)).ok_or_else(|| format!("No {basic:?} in the bag"))
+    }
+
+    fn take_complex(&mut self, complex: ComplexResourceType) -> Result<ComplexResource, String> {
+        let index = self.complex_resources.iter().position(|res| res.get_type() == complex);
+        index.map(|index| self.complex_resources.remove(index)).ok_or_else(|| format!("No {complex:?} in the bag"))
+    }
+
+    fn push_generic(&mut self, resource: GenericResource) {
+        match resource {
+            GenericResource::BasicResources(basic) => self.basic_resources.push(basic),
+            GenericResource::ComplexResources(complex) => self.complex_resources.push(complex)
+        }
+    }
+
+    fn content(&self) -> BagContent {
+        let mut content = HashMap::new();
+        for basic in &self.basic_resources {
+            *content.entry(ResourceType::Basic(basic.get_type())).or_insert(0) += 1;
+        }
+        for complex in &self.complex_resources {
+            *content.entry(ResourceType::Complex(complex.get_type())).or_insert(0) += 1;
+        }
+        BagContent { content }
+    }
+
+    /// Takes the ingredients of a complex resource, the bag is left untouched if one is missing.
+    fn combination(&mut self, complex: ComplexResourceType) -> Result<ComplexResourceRequest, String> {
+        let (first, second) = get_recipe(complex);
+        let needed = if first == second { 2 } else { 1 };
+        if self.count(first) < needed || self.count(second) < needed {
+            return Err(format!("Missing the ingredients of {complex:?}"));
+        }
+        Ok(match complex {
+            ComplexResourceType::Water => ComplexResourceRequest::Water(
+                self.take_basic(BasicResourceType::Hydrogen)?.to_hydrogen()?,
+                self.take_basic(BasicResourceType::Oxygen)?.to_oxygen()?
+            ),
+            ComplexResourceType::Diamond => ComplexResourceRequest::Diamond(
+                self.take_basic(BasicResourceType::Carbon)?.to_carbon()?,
+                self.take_basic(BasicResourceType::Carbon)?.to_carbon()?
+            ),
+            ComplexResourceType::Life => ComplexResourceRequest::Life(
+                self.take_complex(ComplexResourceType::Water)?.to_water()?,
+                self.take_basic(BasicResourceType::Carbon)?.to_carbon()?
+            ),
+            ComplexResourceType::Robot => ComplexResourceRequest::Robot(
+                self.take_basic(BasicResourceType::Silicon)?.to_silicon()?,
+                self.take_complex(ComplexResourceType::Life)?.to_life()?
+            ),
+            ComplexResourceType::Dolphin => ComplexResourceRequest::Dolphin(
+                self.take_complex(ComplexResourceType::Water)?.to_water()?,
+                self.take_complex(ComplexResourceType::Life)?.to_life()?
+            ),
+            ComplexResourceType::AIPartner => ComplexResourceRequest::AIPartner(
+                self.take_complex(ComplexResourceType::Robot)?.to_robot()?,
+                self.take_complex(ComplexResourceType::Diamond)?.to_diamond()?
+            )
+        })
+    }
+}
+
+fn basic_from_name(name: &str) -> Result<BasicResourceType, String> {
+    match resource_from_name(name) {
+        Some(ResourceType::Basic(basic)) => Ok(basic),
+        _ => Err(format!("Unknown basic resource '{name}'"))
+    }
+}
+
+fn complex_from_name(name: &str) -> Result<ComplexResourceType, String> {
+    match resource_from_name(name) {
+        Some(ResourceType::Complex(complex)) => Ok(complex),
+        _ => Err(format!("Unknown complex resource '{name}'"))
+    }
+}
+
+fn names(resources: impl IntoIterator<Item = ResourceType>) -> Vec<String> {
+    let mut names: Vec<String> = resources.into_iter().map(resource_name).collect();
+    names.sort();
+    names
+}
+
+/// Reads the messages of the remote explorer until the socket is closed. A line that cannot be parsed is
+/// passed on as an error, to be reported back.
+fn read_messages(stream: RemoteStream, messages: &Sender<Result<FromRemote, String>>) {
+    for line in BufReader::new(stream).lines() {
+        let Ok(line) = line else {
+            return;
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let message = serde_json::from_str(&line).map_err(|e| format!("Invalid message '{line}': {e}"));
+        if messages.send(message).is_err() {
+            return;
+        }
+    }
+}
+
+/// What the relay loop does after a message.
+enum Relay {
+    Continue,
+    Stop
+}
+
+impl Explorer for RemoteExplorer {
+    fn new(
+        id: ID,
+        current_planet_id: ID,
+        rx_orchestrator: Receiver<OrchestratorToExplorer>,
+        tx_orchestrator: Sender<ExplorerToOrchestrator<BagContent>>,
+        tx_first_planet: Sender<ExplorerToPlanet>,
+        rx_planet: Receiver<PlanetToExplorer>
+    ) -> Self {
+        RemoteExplorer {
+            id,
+            current_planet_id,
+            rx_orchestrator,
+            tx_orchestrator,
+            tx_planet: tx_first_planet,
+            rx_planet,
+            connection: None,
+            bag: Bag::default()
+        }
+    }
+
+    fn run(&mut self) -> Result<(), String> {
+        let mut writer = self.connection.take().ok_or("The remote explorer is not connected")?;
+        let reader = writer.try_clone()?;
+        let (tx_messages, messages) = unbounded();
+        thread::spawn(move || read_messages(reader, &tx_messages));
+
+        send(&mut writer, &ToRemote::Hello { explorer_id: self.id, planet_id: self.current_planet_id })?;
+        let (rx_orchestrator, rx_planet) = (self.rx_orchestrator.clone(), self.rx_planet.clone());
+        loop {
+            let relay = select! {
+                recv(rx_orchestrator) -> message => match message {
+                    Ok(message) => self.relay_orchestrator(message, &mut writer)?,
+                    Err(_) => Relay::Stop // removed from the game
+                },
+                recv(rx_planet) -> message => match message {
+                    Ok(message) => self.relay_planet(message, &mut writer)?,
+                    Err(_) => Relay::Stop
+                },
+                recv(messages) -> message => match message {
+                    Ok(Ok(message)) => self.relay_remote(message, &mut writer)?,
+                    Ok(Err(e)) => {
+                        send(&mut writer, &ToRemote::Error { message: e })?;
+                        Relay::Continue
+                    }
+                    Err(_) => return Err(format!("Remote explorer {} disconnected", self.id))
+                }
+            };
+            if let Relay::Stop = relay {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn send(writer: &mut RemoteStream, message: &ToRemote) -> Result<(), String> {
+    let json = serde_json::to_string(message).map_err(|e| format!("Failed to serialize {message:?}: {e}"))?;
+    writeln!(writer, "{json}").map_err(|e| format!("Failed to send to the remote explorer: {e}"))
+}
+
+impl RemoteExplorer {
+    fn relay_orchestrator(
+        &mut self,
+        message: OrchestratorToExplorer,
+        writer: &mut RemoteStream
+    ) -> Result<Relay, String> {
+        let message = match message {
+            OrchestratorToExplorer::StartExplorerAI => ToRemote::StartExplorerAi,
+            OrchestratorToExplorer::StopExplorerAI => ToRemote::StopExplorerAi,
+            OrchestratorToExplorer::ResetExplorerAI => ToRemote::ResetExplorerAi,
+            OrchestratorToExplorer::KillExplorer => ToRemote::KillExplorer,
+            OrchestratorToExplorer::MoveToPlanet { sender_to_new_planet, planet_id } => {
+                let moved = sender_to_new_planet.is_some();
+                if let Some(sender) = sender_to_new_planet {
+                    self.tx_planet = sender;
+                    self.current_planet_id = planet_id;
+                }
+                ToRemote::MoveToPlanet { planet_id, moved }
+            }
+            OrchestratorToExplorer::CurrentPlanetRequest => ToRemote::CurrentPlanetRequest,
+            OrchestratorToExplorer::SupportedResourceRequest => ToRemote::SupportedResourceRequest,
+            OrchestratorToExplorer::SupportedCombinationRequest => ToRemote::SupportedCombinationRequest,
+            OrchestratorToExplorer::GenerateResourceRequest { to_generate } =>
+                ToRemote::GenerateResourceRequest { resource: resource_name(ResourceType::Basic(to_generate)) },
+            OrchestratorToExplorer::CombineResourceRequest { to_generate } =>
+                ToRemote::CombineResourceRequest { resource: resource_name(ResourceType::Complex(to_generate)) },
+            OrchestratorToExplorer::BagContentRequest => ToRemote::BagContentRequest,
+            OrchestratorToExplorer::NeighborsResponse { neighbors } => ToRemote::NeighborsResponse { neighbors }
+        };
+        send(writer, &message)?;
+        Ok(Relay::Continue)
+    }
+
+    fn relay_planet(&mut self, message: PlanetToExplorer, writer: &mut RemoteStream) -> Result<Relay, String> {
+        let message = match message {
+            PlanetToExplorer::SupportedResourceResponse { resource_list } => ToRemote::SupportedResourceResponse {
+                resources: names(resource_list.into_iter().map(ResourceType::Basic))
+            },
+            PlanetToExplorer::SupportedCombinationResponse { combination_list } =>
+                ToRemote::SupportedCombinationResponse {
+                    resources: names(combination_list.into_iter().map(ResourceType::Complex))
+                },
+            PlanetToExplorer::AvailableEnergyCellResponse { available_cells } =>
+                ToRemote::AvailableEnergyCellResponse { available_cells },
+            PlanetToExplorer::GenerateResourceResponse { resource } => {
+                let name = resource.as_ref().map(|res| resource_name(ResourceType::Basic(res.get_type())));
+                if let Some(resource) = resource {
+                    self.bag.basic_resources.push(resource);
+                }
+                ToRemote::GenerateResourceResponse { resource: name }
+            }
+            PlanetToExplorer::CombineResourceResponse { complex_response } => match complex_response {
+                Ok(complex) => {
+                    let name = resource_name(ResourceType::Complex(complex.get_type()));
+                    self.bag.complex_resources.push(complex);
+                    ToRemote::CombineResourceResponse { resource: Some(name), error: None }
+                }
+                Err((error, first, second)) => {
+                    self.bag.push_generic(first);
+                    self.bag.push_generic(second);
+                    ToRemote::CombineResourceResponse { resource: None, error: Some(error) }
+                }
+            },
+            PlanetToExplorer::Stopped => ToRemote::PlanetStopped
+        };
+        send(writer, &message)?;
+        Ok(Relay::Continue)
+    }
+
+    fn relay_remote(&mut self, message: FromRemote, writer: &mut RemoteStream) -> Result<Relay, String> {
+        let explorer_id = self.id;
+        let current_planet_id = self.current_planet_id;
+        let to_orchestrator = match message {
+            FromRemote::StartExplorerAiResult => ExplorerToOrchestrator::StartExplorerAIResult { explorer_id },
+            FromRemote::StopExplorerAiResult => ExplorerToOrchestrator::StopExplorerAIResult { explorer_id },
+            FromRemote::ResetExplorerAiResult => ExplorerToOrchestrator::ResetExplorerAIResult { explorer_id },
+            FromRemote::KillExplorerResult => {
+                self.send_to_orchestrator(ExplorerToOrchestrator::KillExplorerResult { explorer_id })?;
+                return Ok(Relay::Stop);
+            }
+            FromRemote::MovedToPlanetResult =>
+                ExplorerToOrchestrator::MovedToPlanetResult { explorer_id, planet_id: current_planet_id },
+            FromRemote::CurrentPlanetResult =>
+                ExplorerToOrchestrator::CurrentPlanetResult { explorer_id, planet_id: current_planet_id },
+            FromRemote::SupportedResourceResult { resources } => {
+                match resources.iter().map(|name| basic_from_name(name)).collect::<Result<HashSet<_>, _>>() {
+                    Ok(supported_resources) =>
+                        ExplorerToOrchestrator::SupportedResourceResult { explorer_id, supported_resources },
+                    Err(e) => return self.reject(e, writer)
+                }
+            }
+            FromRemote::SupportedCombinationResult { resources } => {
+                match resources.iter().map(|name| complex_from_name(name)).collect::<Result<HashSet<_>, _>>() {
+                    Ok(combination_list) =>
+                        ExplorerToOrchestrator::SupportedCombinationResult { explorer_id, combination_list },
+                    Err(e) => return self.reject(e, writer)
+                }
+            }
+            FromRemote::GenerateResourceResponse { error } =>
+                ExplorerToOrchestrator::GenerateResourceResponse { explorer_id, generated: error.map_or(Ok(()), Err) },
+            FromRemote::CombineResourceResponse { error } =>
+                ExplorerToOrchestrator::CombineResourceResponse { explorer_id, generated: error.map_or(Ok(()), Err) },
+            FromRemote::BagContentResponse =>
+                ExplorerToOrchestrator::BagContentResponse { explorer_id, bag_content: self.bag.content() },
+            FromRemote::NeighborsRequest => ExplorerToOrchestrator::NeighborsRequest { explorer_id, current_planet_id },
+            FromRemote::TravelToPlanetRequest { dst_planet_id } =>
+                ExplorerToOrchestrator::TravelToPlanetRequest { explorer_id, current_planet_id, dst_planet_id },
+            // To the current planet
+            FromRemote::AvailableEnergyCellRequest =>
+                return self.relay_to_planet(ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id }, writer),
+            FromRemote::SupportedResourceRequest =>
+                return self.relay_to_planet(ExplorerToPlanet::SupportedResourceRequest { explorer_id }, writer),
+            FromRemote::SupportedCombinationRequest =>
+                return self.relay_to_planet(ExplorerToPlanet::SupportedCombinationRequest { explorer_id }, writer),
+            FromRemote::GenerateResourceRequest { resource } => return self.relay_generation(&resource, writer),
+            FromRemote::CombineResourceRequest { resource } => return self.relay_combination(&resource, writer)
+        };
+        self.send_to_orchestrator(to_orchestrator)?;
+        Ok(Relay::Continue)
+    }
+
+    fn relay_generation(&self, resource: &str, writer: &mut RemoteStream) -> Result<Relay, String> {
+        match basic_from_name(resource) {
+            Ok(resource) => {
+                let request = ExplorerToPlanet::GenerateResourceRequest { explorer_id: self.id, resource };
+                self.relay_to_planet(request, writer)
+            }
+            Err(e) => self.reject(e, writer)
+        }
+    }
+
+    /// A combination missing its ingredients is answered as the planet would, without wasting its energy.
+    fn relay_combination(&mut self, resource: &str, writer: &mut RemoteStream) -> Result<Relay, String> {
+        match complex_from_name(resource).and_then(|complex| self.bag.combination(complex)) {
+            Ok(msg) => {
+                let request = ExplorerToPlanet::CombineResourceRequest { explorer_id: self.id, msg };
+                self.relay_to_planet(request, writer)
+            }
+            Err(e) => {
+                send(writer, &ToRemote::CombineResourceResponse { resource: None, error: Some(e) })?;
+                Ok(Relay::Continue)
+            }
+        }
+    }
+
+    fn relay_to_planet(&self, request: ExplorerToPlanet, writer: &mut RemoteStream) -> Result<Relay, String> {
+        if self.tx_planet.send(request).is_err() {
+            send(writer, &ToRemote::PlanetStopped)?;
+        }
+        Ok(Relay::Continue)
+    }
+
+    fn send_to_orchestrator(&self, message: ExplorerToOrchestrator<BagContent>) -> Result<(), String> {
+        self.tx_orchestrator.send(message).map_err(|e| format!("Failed to send to the orchestrator: {e}"))
+    }
+
+    /// Reports a message that cannot be relayed back to the remote explorer.
+    fn reject(&self, error: String, writer: &mut RemoteStream) -> Result<Relay, String> {
+        log::warn!("Remote explorer {}: {error}", self.id);
+        send(writer, &ToRemote::Error { message: error })?;
+        Ok(Relay::Continue)
+    }
+}
+
+/// Builds a `RemoteExplorer` on a connection accepted when the builder is created, so that the game does
+/// not start before the remote explorer is there.
+pub(crate) struct RemoteExplorerBuilder {
+    connection: Result<RemoteStream, String>,
+    rx_orchestrator: Option<Receiver<OrchestratorToExplorer>>,
+    tx_orchestrator: Option<Sender<ExplorerToOrchestrator<BagContent>>>,
+    rx_planet: Option<Receiver<PlanetToExplorer>>,
+    tx_current_planet: Option<Sender<ExplorerToPlanet>>,
+    id: Option<ID>,
+    current_planet: Option<ID>,
+    type_name: Option<String>
+}
+
+impl RemoteExplorerBuilder {
+    /// Waits up to `remote_explorer_wait_seconds` for the remote explorer to connect to `address`.
+    pub fn listen(address: &str) -> Self {
+        let timeout = Duration::from_secs(AppConfig::get().remote_explorer_wait_seconds);
+        let connection = RemoteStream::accept(address, timeout);
+        match &connection {
+            Ok(_) => log::info!("Remote explorer connected to {address}"),
+            Err(e) => log::error!("{e}")
+        }
+        RemoteExplorerBuilder {
+            connection,
+            rx_orchestrator: None,
+            tx_orchestrator: None,
+            rx_planet: None,
+            tx_current_planet: None,
+            id: None,
+            current_planet: None,
+            type_name: None
+        }
+    }
+}
+
+impl ExplorerBuilder for RemoteExplorerBuilder {
+    fn build(self: Box<Self>) -> Result<Box<dyn Explorer>, String> {
+        let connection = self.connection?;
+        let mut explorer = RemoteExplorer::new(
+            self.id.ok_or("Explorer ID not set")?,
+            self.current_planet.ok_or("Current planet ID not set")?,
+            self.rx_orchestrator.ok_or("Orchestrator RX channel not set")?,
+            self.tx_orchestrator.ok_or("Orchestrator TX channel not set")?,
+            self.tx_current_planet.ok_or("Current planet TX channel not set")?,
+            self.rx_planet.ok_or("Planet RX channel not set")?
+        );
+        explorer.connection = Some(connection);
+        Ok(Box::new(explorer))
+    }
+
+    fn with_orchestrator_rx(self: Box<Self>, rx: Receiver<OrchestratorToExplorer>) -> Box<dyn ExplorerBuilder> {
+        Box::new(RemoteExplorerBuilder { rx_orchestrator: Some(rx), ..*self })
+    }
+
+    fn with_orchestrator_tx(
+        self: Box<Self>,
+        tx: Sender<ExplorerToOrchestrator<BagContent>>
+    ) -> Box<dyn ExplorerBuilder> {
+        Box::new(RemoteExplorerBuilder { tx_orchestrator: Some(tx), ..*self })
+    }
+
+    fn with_planet_rx(self: Box<Self>, rx: Receiver<PlanetToExplorer>) -> Box<dyn ExplorerBuilder> {
+        Box::new(RemoteExplorerBuilder { rx_planet: Some(rx), ..*self })
+    }
+
+    fn with_current_planet_tx(self: Box<Self>, tx: Sender<ExplorerToPlanet>) -> Box<dyn ExplorerBuilder> {
+        Box::new(RemoteExplorerBuilder { tx_current_planet: Some(tx), ..*self })
+    }
+
+    fn with_id(self: Box<Self>, id: ID) -> Box<dyn ExplorerBuilder> {
+        Box::new(RemoteExplorerBuilder { id: Some(id), ..*self })
+    }
+
+    fn with_current_planet(self: Box<Self>, planet_id: ID) -> Box<dyn ExplorerBuilder> {
+        Box::new(RemoteExplorerBuilder { current_planet: Some(planet_id), ..*self })
+    }
+
+    /// The randomness of a remote explorer is its own business
+    fn with_seed(self: Box<Self>, _seed: u64) -> Box<dyn ExplorerBuilder> { self }
+
+    fn with_type_name(self: Box<Self>, type_name: &str) -> Box<dyn ExplorerBuilder> {
+        Box::new(RemoteExplorerBuilder { type_name: Some(type_name.to_string()), ..*self })
+    }
+
+    fn get_type_name(&self) -> String { self.type_name.clone().unwrap_or_else(|| "unknown".to_string()) }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+
+    use super::*;
+    use crate::explorers::ExplorerFactory;
+    use crate::explorers::remote::client::RemoteClient;
+    use crate::orchestrator::{ManualActionResult, Orchestrator, OrchestratorManualAction, OrchestratorMode};
+
+    #[test]
+    fn test_remote_explorer_loopback() {
+        let port = TcpListener::bind("127.0.0.1:0").unwrap().local_addr().unwrap().port();
+        let address = format!("127.0.0.1:{port}");
+        let client_address = address.clone();
+        let client = thread::spawn(move || {
+            let mut client = loop {
+                match RemoteClient::connect(&client_address) {
+                    Ok(client) => break client,
+                    Err(_) => thread::sleep(Duration::from_millis(20))
+                }
+            };
+            client.run()
+        });
+
+        let explorer = ExplorerFactory::make_from_name(&format!("remote:{address}"));
+        assert_eq!(explorer.get_type_name(), "remote");
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 3, vec![explorer], 25).unwrap();
+        orchestrator.manual_init().unwrap();
+        let explorer_id = orchestrator.get_explorer_ids()[0];
+
+        let Ok(ManualActionResult::ExplorerPlanet { planet_id }) =
+            orchestrator.execute(OrchestratorManualAction::QueryExplorerPlanet { explorer_id })
+        else {
+            panic!("The remote explorer did not tell its planet");
+        };
+        assert_eq!(orchestrator.get_explorer_current_planet(explorer_id), Some(planet_id));
+        let Ok(ManualActionResult::ExplorerResources(resources)) =
+            orchestrator.execute(OrchestratorManualAction::QueryExplorerResources { explorer_id })
+        else {
+            panic!("The remote explorer did not list the resources of its planet");
+        };
+
+        // The resource travels as a name but stays in the game, in the bag kept by the proxy
+        let resource = *resources.iter().next().unwrap();
+        orchestrator.execute(OrchestratorManualAction::SendSunray { planet_id }).unwrap();
+        let generated = orchestrator.execute(OrchestratorManualAction::GenerateBasic { explorer_id, resource });
+        // Stopped, the client does not play a turn of its own when asked for the bag
+        orchestrator.execute(OrchestratorManualAction::StopExplorer { explorer_id }).unwrap();
+        let Ok(ManualActionResult::ExplorerBag(bag)) =
+            orchestrator.execute(OrchestratorManualAction::QueryExplorerBag { explorer_id })
+        else {
+            panic!("The remote explorer did not send its bag");
+        };
+        let expected = usize::from(generated.is_ok());
+        assert_eq!(bag.content.get(&ResourceType::Basic(resource)).copied().unwrap_or(0), expected);
+
+        orchestrator.execute(OrchestratorManualAction::KillExplorer { explorer_id }).unwrap();
+        assert_eq!(client.join().unwrap(), Ok(()));
+    }
+}
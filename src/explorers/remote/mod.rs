@@ -0,0 +1,11 @@
+//! Explorers played by another process over a TCP or Unix socket. The game side is a `RemoteExplorer`
+//! relaying the channels to the socket, the wire format is described in `protocol`, and `client` is a
+//! reference implementation of the other side (`--remote-explorer <address>`).
+
+mod client;
+mod connection;
+mod explorer;
+mod protocol;
+
+pub(crate) use client::run_remote_explorer;
+pub(crate) use explorer::RemoteExplorerBuilder;
@@ -0,0 +1,120 @@
+//! Wire format of the remote explorers: newline-delimited JSON, one message per line in both directions,
+//! tagged by `type`. Resources are named as in the config (e.g. `"Carbon"`, `"AIPartner"`).
+//!
+//! The game opens the connection with `hello`, then forwards what the orchestrator and the current planet
+//! send to the explorer ([`ToRemote`]). The explorer answers every orchestrator request with the matching
+//! result and talks to its current planet with the planet requests ([`FromRemote`]):
+//! ```text
+//! game -> {"type":"hello","explorer_id":8,"planet_id":1}
+//! game -> {"type":"start_explorer_ai"}
+//! remote <- {"type":"start_explorer_ai_result"}
+//! game -> {"type":"bag_content_request"}
+//! remote <- {"type":"generate_resource_request","resource":"Carbon"}
+//! game -> {"type":"generate_resource_response","resource":"Carbon"}
+//! remote <- {"type":"bag_content_response"}
+//! ```
+//! The resources never leave the game: it keeps the bag of the explorer, so `bag_content_response` has no
+//! content and a combination only names the complex resource, the game takes the ingredients from the bag.
+//! A message the game cannot understand is answered with `error` and otherwise ignored.
+
+use common_game::utils::ID;
+use serde::{Deserialize, Serialize};
+
+/// From the game to the remote explorer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ToRemote {
+    /// First message of the connection
+    Hello { explorer_id: ID, planet_id: ID },
+    /// Something sent by the remote explorer was not understood
+    Error { message: String },
+
+    // From the orchestrator
+    StartExplorerAi,
+    StopExplorerAi,
+    ResetExplorerAi,
+    /// Answer with the result, then the connection is closed
+    KillExplorer,
+    /// `moved` is false if the orchestrator refused the travel, the explorer stays where it is
+    MoveToPlanet { planet_id: ID, moved: bool },
+    CurrentPlanetRequest,
+    SupportedResourceRequest,
+    SupportedCombinationRequest,
+    GenerateResourceRequest { resource: String },
+    CombineResourceRequest { resource: String },
+    /// The explorer plays its turn, then answers
+    BagContentRequest,
+    NeighborsResponse { neighbors: Vec<ID> },
+
+    // From the current planet
+    SupportedResourceResponse { resources: Vec<String> },
+    SupportedCombinationResponse { resources: Vec<String> },
+    AvailableEnergyCellResponse { available_cells: u32 },
+    /// `resource` is missing if the planet could not generate it
+    GenerateResourceResponse {
+        #[serde(default)]
+        resource: Option<String>
+    },
+    /// Either the new resource or the reason of the failure, the ingredients go back to the bag
+    CombineResourceResponse {
+        #[serde(default)]
+        resource: Option<String>,
+        #[serde(default)]
+        error: Option<String>
+    },
+    /// The current planet stopped and does not answer anymore
+    PlanetStopped
+}
+
+/// From the remote explorer to the game. The game fills in the explorer and planet ids it knows.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum FromRemote {
+    // To the orchestrator
+    StartExplorerAiResult,
+    StopExplorerAiResult,
+    ResetExplorerAiResult,
+    KillExplorerResult,
+    MovedToPlanetResult,
+    CurrentPlanetResult,
+    SupportedResourceResult { resources: Vec<String> },
+    SupportedCombinationResult { resources: Vec<String> },
+    /// `error` is missing if the resource is in the bag
+    GenerateResourceResponse {
+        #[serde(default)]
+        error: Option<String>
+    },
+    CombineResourceResponse {
+        #[serde(default)]
+        error: Option<String>
+    },
+    BagContentResponse,
+    NeighborsRequest,
+    TravelToPlanetRequest { dst_planet_id: ID },
+
+    // To the current planet
+    AvailableEnergyCellRequest,
+    SupportedResourceRequest,
+    SupportedCombinationRequest,
+    GenerateResourceRequest { resource: String },
+    CombineResourceRequest { resource: String }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_message_format() {
+        let hello = serde_json::to_string(&ToRemote::Hello { explorer_id: 8, planet_id: 1 }).unwrap();
+        assert_eq!(hello, r#"{"type":"hello","explorer_id":8,"planet_id":1}"#);
+        assert_eq!(serde_json::to_string(&ToRemote::StartExplorerAi).unwrap(), r#"{"type":"start_explorer_ai"}"#);
+
+        let generate: FromRemote = serde_json::from_str(r#"{"type":"generate_resource_response"}"#).unwrap();
+        assert_eq!(generate, FromRemote::GenerateResourceResponse { error: None });
+        let travel = r#"{"type":"travel_to_planet_request","dst_planet_id":3}"#;
+        let travel: FromRemote = serde_json::from_str(travel).unwrap();
+        assert_eq!(travel, FromRemote::TravelToPlanetRequest { dst_planet_id: 3 });
+        assert!(serde_json::from_str::<FromRemote>(r#"{"type":"teleport"}"#).is_err());
+    }
+}
@@ -5,6 +5,8 @@ use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer
 use common_game::utils::ID;
 use crossbeam_channel::{Receiver, Sender};
 use log::info;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
 
 use super::{Bag, GalaxyKnowledge, OrchestratorCommunicator, OrchestratorLoggingReceiver, OrchestratorLoggingSender,
             PlanetLoggingReceiver, PlanetLoggingSender, PlanetsCommunicator, ProbabilityEstimator,
@@ -18,7 +20,9 @@ pub(super) struct ExplorerState {
     pub knowledge: Option<GalaxyKnowledge>,
     pub current_planet: ID,
     pub asteroid_probability_estimator: ProbabilityEstimator,
-    pub sunray_probability_estimator: ProbabilityEstimator
+    pub sunray_probability_estimator: ProbabilityEstimator,
+    // Breaks the ties between equally good planets, seeded by the orchestrator so that a game can be reproduced
+    pub rng: StdRng
 }
 
 pub struct SamuFazExplorer {
@@ -48,7 +52,8 @@ impl Explorer for SamuFazExplorer {
                 knowledge: None,
                 current_planet,
                 asteroid_probability_estimator: ProbabilityEstimator::new(),
-                sunray_probability_estimator: ProbabilityEstimator::new()
+                sunray_probability_estimator: ProbabilityEstimator::new(),
+                rng: StdRng::from_os_rng()
             },
             orchestrator_communicator: OrchestratorCommunicator::new(
                 OrchestratorLoggingSender::new(tx_orchestrator, id, 0), // Orchestrator has ID 0
@@ -57,7 +62,7 @@ impl Explorer for SamuFazExplorer {
             ),
             planets_communicator: PlanetsCommunicator::new(
                 HashMap::from([(current_planet, PlanetLoggingSender::new(tx_current_planet, id, current_planet))]),
-                PlanetLoggingReceiver::new(rx_planet, id, 1), // First planet has ID 1
+                PlanetLoggingReceiver::new(rx_planet, id, current_planet),
                 id
             )
         }
@@ -72,6 +77,8 @@ impl Explorer for SamuFazExplorer {
         info!("Explorer {} terminating gracefully.", self.id);
         Ok(())
     }
+
+    fn set_seed(&mut self, seed: u64) { self.state.rng = StdRng::seed_from_u64(seed); }
 }
 
 impl SamuFazExplorer {
@@ -92,7 +99,8 @@ impl SamuFazExplorer {
                     knowledge: None,
                     current_planet: self.state.current_planet,
                     asteroid_probability_estimator: ProbabilityEstimator::new(),
-                    sunray_probability_estimator: ProbabilityEstimator::new()
+                    sunray_probability_estimator: ProbabilityEstimator::new(),
+                    rng: self.state.rng.clone()
                 };
                 self.orchestrator_communicator.send_reset_ack()?;
             }
@@ -2,6 +2,7 @@ use std::collections::{HashSet, VecDeque};
 
 use common_game::components::resource::{BasicResourceType, ComplexResourceType};
 use common_game::utils::ID;
+use rand::seq::IndexedRandom;
 
 use super::{ExplorerState, GalaxyKnowledge, GlobalPlanner, LocalPlanner, LocalTask, OrchestratorCommunicator,
             PlanetsCommunicator, get_resource_request};
@@ -58,15 +59,16 @@ impl<'a> RoundExecutor<'a> {
 
     /// Prefer planets with many connection with explored ones (to explore more on clusters)
     /// Simple BFS
-    fn get_best_nearest_unexplored_planet(&self, explored: &HashSet<ID>) -> Option<ID> {
-        self.find_nearest_planet(
+    fn get_best_nearest_unexplored_planet(&mut self, explored: &HashSet<ID>) -> Option<ID> {
+        let candidates = self.find_nearest_planets(
             |pid| !explored.contains(&pid),
             |pid| {
                 self.new_galaxy
                     .get_planet_neighbours(*pid) // Count connections with explored planets
                     .map_or(0, |neighbors| neighbors.iter().filter(|n| explored.contains(n)).count()) // If no neighbors, 0 connections
             }
-        )
+        );
+        self.pick(candidates)
     }
 
     fn inspect_current_planet(&mut self) -> Result<(), String> {
@@ -93,15 +95,18 @@ impl<'a> RoundExecutor<'a> {
     }
 
     fn goto_safest_place(&mut self) -> Result<(), String> {
-        let safest = self
-            .new_galaxy
-            .get_planet_ids()
+        let planet_ids = self.new_galaxy.get_planet_ids();
+        let best = planet_ids
             .iter()
-            .map(|pid| (pid, self.new_galaxy.get_planet_reliability(*pid)))
-            .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap()) // Unwrap safe since reliability is a f32 constant
-            .map(|(pid, _)| *pid)
+            .map(|pid| self.new_galaxy.get_planet_reliability(*pid))
+            .max_by(f32::total_cmp)
             .ok_or("No planets found in galaxy")?;
+        let safest: Vec<ID> = planet_ids
+            .into_iter()
+            .filter(|pid| self.new_galaxy.get_planet_reliability(*pid).total_cmp(&best).is_eq())
+            .collect();
 
+        let safest = self.pick(safest).ok_or("No planets found in galaxy")?;
         self.goto_planet(safest)
     }
 
@@ -130,13 +135,14 @@ impl<'a> RoundExecutor<'a> {
     }
 
     fn generate_resource(&mut self, resource: BasicResourceType) -> Result<bool, String> {
-        let dest = self.find_nearest_planet(
+        let candidates = self.find_nearest_planets(
             |planet_id| {
                 self.new_galaxy.produces_basic_resource(planet_id, resource)
                     && self.new_galaxy.get_n_charged_cells(planet_id) > 0
             },
             |planet_id| self.new_galaxy.get_n_charged_cells(*planet_id)
         ); // Prefer planets with more energy cells
+        let dest = self.pick(candidates);
 
         if dest.is_none() {
             return Ok(false); // No planet can produce the resource
@@ -156,13 +162,14 @@ impl<'a> RoundExecutor<'a> {
     }
 
     fn produce_resource(&mut self, resource: ComplexResourceType) -> Result<bool, String> {
-        let dest = self.find_nearest_planet(
+        let candidates = self.find_nearest_planets(
             |planet_id| {
                 self.new_galaxy.supports_combination_rule(planet_id, resource)
                     && self.new_galaxy.get_n_charged_cells(planet_id) > 0
             },
             |planet_id| self.new_galaxy.get_n_charged_cells(*planet_id)
         ); // Prefer planets with more energy cells
+        let dest = self.pick(candidates);
 
         if dest.is_none() {
             return Ok(false); // No planet can produce the resource
@@ -194,13 +201,13 @@ impl<'a> RoundExecutor<'a> {
         }
     }
 
-    /// BFS to find nearest planet satisfying predicate, then select the best one according to discriminant
+    /// BFS to find nearest planets satisfying predicate, then keep the best ones according to discriminant
     /// (max by)
-    fn find_nearest_planet<B: Ord>(
+    fn find_nearest_planets<B: Ord>(
         &self,
         predicate: impl Fn(ID) -> bool,
         discriminant: impl Fn(&ID) -> B
-    ) -> Option<ID> {
+    ) -> Vec<ID> {
         let mut candidates: Vec<ID> = Vec::new();
         let mut best_distance = i32::MAX;
 
@@ -232,7 +239,18 @@ impl<'a> RoundExecutor<'a> {
             }
         }
 
-        candidates.into_iter().max_by_key(discriminant)
+        let Some(best) = candidates.iter().map(&discriminant).max() else {
+            return candidates;
+        };
+        candidates.retain(|id| discriminant(id) == best);
+        candidates
+    }
+
+    /// Chooses among equally good planets with the seeded generator, the order of the candidates depends on
+    /// the hashing of the galaxy knowledge so it's sorted first
+    fn pick(&mut self, mut candidates: Vec<ID>) -> Option<ID> {
+        candidates.sort_unstable();
+        candidates.choose(&mut self.state.rng).copied()
     }
 
     /// Evaluate the risk of using energy on a planet
@@ -278,7 +296,9 @@ impl<'a> RoundExecutor<'a> {
             if !visited.contains(&node)
                 && let Some(neighbours) = self.new_galaxy.get_planet_neighbours(node)
             {
-                for &neighbor in neighbours {
+                let mut neighbours: Vec<ID> = neighbours.iter().copied().collect();
+                neighbours.sort_unstable(); // the same shortest path for the same galaxy
+                for neighbor in neighbours {
                     let mut new_path = path.clone();
                     new_path.push(neighbor);
                     queue.push_back(new_path);
@@ -0,0 +1,73 @@
+use std::collections::BTreeMap;
+
+use serde::Deserialize;
+
+use crate::explorers::BagContent;
+use crate::orchestrator::resource_name;
+
+/// Points given to an explorer at the end of a game. Every resource in the bag is worth its weight
+/// (resources without a weight are worth nothing) and dying before the end of the game costs
+/// `death_penalty` points.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct ScoringModel {
+    pub weights: BTreeMap<String, f64>, // resource name (e.g. "Dolphin") -> points
+    pub death_penalty: f64
+}
+
+impl Default for ScoringModel {
+    /// Complex resources are worth more the more basic resources and combinations they need.
+    fn default() -> Self {
+        let weights = [
+            ("Carbon", 1.0),
+            ("Silicon", 1.0),
+            ("Oxygen", 1.0),
+            ("Hydrogen", 1.0),
+            ("Water", 3.0),
+            ("Diamond", 3.0),
+            ("Life", 5.0),
+            ("Robot", 8.0),
+            ("Dolphin", 12.0),
+            ("AIPartner", 15.0)
+        ];
+        ScoringModel {
+            weights: weights.into_iter().map(|(name, points)| (name.to_string(), points)).collect(),
+            death_penalty: 10.0
+        }
+    }
+}
+
+impl ScoringModel {
+    #[allow(clippy::cast_precision_loss)] // quantities are small
+    pub fn score(&self, bag: &BagContent, died: bool) -> f64 {
+        let points: f64 = bag
+            .content
+            .iter()
+            .map(|(&resource, &qty)| self.weights.get(&resource_name(resource)).copied().unwrap_or(0.0) * qty as f64)
+            .sum();
+        if died { points - self.death_penalty } else { points }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_game::components::resource::{BasicResourceType, ComplexResourceType, ResourceType};
+
+    use super::*;
+
+    #[test]
+    fn test_score() {
+        let model = ScoringModel::default();
+        let bag = BagContent {
+            content: [
+                (ResourceType::Basic(BasicResourceType::Carbon), 2),
+                (ResourceType::Complex(ComplexResourceType::Dolphin), 1)
+            ]
+            .into_iter()
+            .collect()
+        };
+        assert_eq!(model.score(&bag, false), 14.0);
+        assert_eq!(model.score(&bag, true), 4.0);
+        assert_eq!(model.score(&BagContent::default(), false), 0.0);
+    }
+}
@@ -1,39 +1,77 @@
 use common_game::components::resource::{BasicResourceType, ComplexResourceType};
 use common_game::utils::ID;
 
-use crate::gui::types::OrchestratorEvent;
+use crate::gui::recorder::EventRecorder;
+use crate::gui::types::{GameEvent, OrchestratorEvent, SupervisionEvent};
 
 pub struct GuiEventBuffer {
-    buffer: Vec<OrchestratorEvent>
+    buffer: Vec<GameEvent>,
+    time: u32, // time step of the events pushed now
+    recorder: Option<EventRecorder>
 }
 
 impl GuiEventBuffer {
-    pub fn new() -> Self { GuiEventBuffer { buffer: Vec::new() } }
+    pub fn new() -> Self { GuiEventBuffer { buffer: Vec::new(), time: 0, recorder: None } }
 
-    pub fn sunray_sent(&mut self, planet_id: ID) { self.buffer.push(OrchestratorEvent::SunraySent { planet_id }); }
+    pub fn sunray_sent(&mut self, planet_id: ID) { self.push(OrchestratorEvent::SunraySent { planet_id }); }
 
-    pub fn sunray_received(&mut self, planet_id: ID) {
-        self.buffer.push(OrchestratorEvent::SunrayReceived { planet_id });
-    }
+    pub fn sunray_received(&mut self, planet_id: ID) { self.push(OrchestratorEvent::SunrayReceived { planet_id }); }
 
-    pub fn planet_destroyed(&mut self, planet_id: ID) {
-        self.buffer.push(OrchestratorEvent::PlanetDestroyed { planet_id });
-    }
+    pub fn planet_destroyed(&mut self, planet_id: ID) { self.push(OrchestratorEvent::PlanetDestroyed { planet_id }); }
 
-    pub fn asteroid_sent(&mut self, planet_id: ID) { self.buffer.push(OrchestratorEvent::AsteroidSent { planet_id }); }
+    pub fn asteroid_sent(&mut self, planet_id: ID) { self.push(OrchestratorEvent::AsteroidSent { planet_id }); }
 
     pub fn explorer_moved(&mut self, explorer_id: ID, destination: ID) {
-        self.buffer.push(OrchestratorEvent::ExplorerMoved { explorer_id, destination });
+        self.push(OrchestratorEvent::ExplorerMoved { explorer_id, destination });
     }
 
     pub fn basic_resource_generated(&mut self, explorer_id: ID, resource: BasicResourceType) {
-        self.buffer.push(OrchestratorEvent::BasicResourceGenerated { explorer_id, resource });
+        self.push(OrchestratorEvent::BasicResourceGenerated { explorer_id, resource });
     }
 
     pub fn complex_resource_generated(&mut self, explorer_id: ID, resource: ComplexResourceType) {
-        self.buffer.push(OrchestratorEvent::ComplexResourceGenerated { explorer_id, resource });
+        self.push(OrchestratorEvent::ComplexResourceGenerated { explorer_id, resource });
+    }
+
+    pub fn planet_failed(&mut self, planet_id: ID, reason: String) {
+        self.push(SupervisionEvent::PlanetFailed { planet_id, reason });
+    }
+
+    pub fn explorer_restarted(&mut self, explorer_id: ID, reason: String) {
+        self.push(SupervisionEvent::ExplorerRestarted { explorer_id, reason });
+    }
+
+    pub fn explorer_evicted(&mut self, explorer_id: ID, reason: String) {
+        self.push(SupervisionEvent::ExplorerEvicted { explorer_id, reason });
+    }
+
+    /// Adds an event without recording it, used to feed a replayed game to the GUI.
+    pub fn replay(&mut self, event: GameEvent) { self.buffer.push(event); }
+
+    fn push(&mut self, event: impl Into<GameEvent>) {
+        let event = event.into();
+        if let Some(recorder) = &mut self.recorder {
+            recorder.record(self.time, &event);
+        }
+        self.buffer.push(event);
+    }
+
+    pub fn set_time(&mut self, time: u32) { self.time = time; }
+
+    /// From now on every event is also written to the recorder.
+    pub fn set_recorder(&mut self, recorder: EventRecorder) { self.recorder = Some(recorder); }
+
+    /// The events for the GUI, the supervision ones are dropped.
+    pub fn drain_events(&mut self) -> Vec<OrchestratorEvent> {
+        self.drain_game_events()
+            .into_iter()
+            .filter_map(|event| match event {
+                GameEvent::Orchestrator(event) => Some(event),
+                GameEvent::Supervision(_) => None
+            })
+            .collect()
     }
 
-    pub fn drain_events(&mut self) -> Vec<OrchestratorEvent> { std::mem::take(&mut self.buffer) }
-    pub fn has_events(&self) -> bool { !self.buffer.is_empty() }
+    pub fn drain_game_events(&mut self) -> Vec<GameEvent> { std::mem::take(&mut self.buffer) }
+    pub fn has_events(&self) -> bool { self.buffer.iter().any(|event| matches!(event, GameEvent::Orchestrator(_))) }
 }
@@ -22,8 +22,13 @@ pub(self) mod game;
 pub(self) mod utils;
 
 mod event_buffer;
+mod recorder;
+mod replay;
 mod routines;
 mod types;
 
 pub(crate) use event_buffer::GuiEventBuffer;
+pub(crate) use recorder::EventRecorder;
+pub(crate) use replay::GameReplay;
 pub(crate) use routines::run_gui;
+pub(crate) use types::{GameEvent, OrchestratorEvent, SupervisionEvent, deserialize_basic, deserialize_complex};
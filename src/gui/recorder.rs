@@ -0,0 +1,100 @@
+//! Recording of a whole game to a line-delimited JSON file.
+//! The first line holds the galaxy at the start of the recording, every other line an orchestrator
+//! event with the time step it occurred in:
+//! ```text
+//! {"kind":"start","snapshot":{"time":0,"planets":[...],"edges":[...],"explorers":[...]}}
+//! {"kind":"event","time":0,"event":{"type":"sunray_sent","planet_id":3}}
+//! ```
+
+use std::fs::File;
+use std::io::{LineWriter, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::gui::types::GameEvent;
+use crate::orchestrator::GameSnapshot;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum RecordLine {
+    Start { snapshot: GameSnapshot },
+    Event { time: u32, event: GameEvent }
+}
+
+pub(crate) struct EventRecorder {
+    path: String,
+    writer: LineWriter<File> // flushed at every line, a crash does not lose the recorded events
+}
+
+impl EventRecorder {
+    pub fn create(path: &str, start: GameSnapshot) -> Result<Self, String> {
+        let file = File::create(path).map_err(|e| format!("Failed to create recording {path}: {e}"))?;
+        let mut recorder = EventRecorder { path: path.to_string(), writer: LineWriter::new(file) };
+        recorder.write(&RecordLine::Start { snapshot: start })?;
+        Ok(recorder)
+    }
+
+    /// Recording errors are logged and do not stop the game.
+    pub fn record(&mut self, time: u32, event: &GameEvent) {
+        if let Err(e) = self.write(&RecordLine::Event { time, event: event.clone() }) {
+            log::error!("{e}");
+        }
+    }
+
+    fn write(&mut self, line: &RecordLine) -> Result<(), String> {
+        let json = serde_json::to_string(line).map_err(|e| format!("Failed to serialize event: {e}"))?;
+        writeln!(self.writer, "{json}").map_err(|e| format!("Failed to write to recording {}: {e}", self.path))
+    }
+}
+
+pub(crate) fn read_recording(path: &str) -> Result<Vec<RecordLine>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read recording {path}: {e}"))?;
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line).map_err(|e| format!("Failed to parse line {} of recording {path}: {e}", i + 1))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use common_game::components::resource::ComplexResourceType;
+
+    use super::*;
+    use crate::gui::types::{OrchestratorEvent, SupervisionEvent};
+
+    #[test]
+    fn test_event_json_format() {
+        let line = RecordLine::Event {
+            time: 3,
+            event: OrchestratorEvent::ComplexResourceGenerated { explorer_id: 8, resource: ComplexResourceType::Water }
+                .into()
+        };
+        let json = serde_json::to_string(&line).unwrap();
+        assert_eq!(
+            json,
+            concat!(
+                r#"{"kind":"event","time":3,"#,
+                r#""event":{"type":"complex_resource_generated","explorer_id":8,"resource":"Water"}}"#
+            )
+        );
+        assert_eq!(serde_json::from_str::<RecordLine>(&json).unwrap(), line);
+    }
+
+    #[test]
+    fn test_supervision_event_json_format() {
+        let line = RecordLine::Event {
+            time: 4,
+            event: SupervisionEvent::ExplorerEvicted { explorer_id: 9, reason: "panicked".to_string() }.into()
+        };
+        let json = serde_json::to_string(&line).unwrap();
+        assert_eq!(
+            json,
+            r#"{"kind":"event","time":4,"event":{"type":"explorer_evicted","explorer_id":9,"reason":"panicked"}}"#
+        );
+        assert_eq!(serde_json::from_str::<RecordLine>(&json).unwrap(), line);
+    }
+}
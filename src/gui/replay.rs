@@ -0,0 +1,222 @@
+//! Replay of a recorded game (see `recorder`). No planet or explorer thread is started: the state shown
+//! to the GUI is rebuilt from the events, so what the events do not describe is approximated (e.g. the
+//! rockets built by the planets and, in auto mode, the explorers bags).
+//! A replay is only created by `Orchestrator::from_config` and is headless only: the GUI builds its own
+//! orchestrator, so `--replay` is refused together with `show_gui`. The supervision events are replayed too,
+//! but `drain_events` leaves them out of the GUI.
+
+use std::collections::{BTreeMap, VecDeque};
+
+use common_game::components::resource::ResourceType;
+use common_game::utils::ID;
+
+use crate::explorers::BagContent;
+use crate::gui::recorder::{RecordLine, read_recording};
+use crate::gui::types::{ExplorerInfo, ExplorerInfoMap, GameEvent, OrchestratorEvent, PlanetInfo, PlanetInfoMap, Status,
+                        SupervisionEvent};
+use crate::orchestrator::{GameSnapshot, get_recipe, resource_from_name};
+
+pub(crate) struct GameReplay {
+    time: u32,
+    events: VecDeque<(u32, GameEvent)>,
+    planets: BTreeMap<ID, PlanetInfo>,
+    explorers: BTreeMap<ID, ExplorerInfo>,
+    edges: Vec<(ID, ID)>
+}
+
+impl GameReplay {
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        let mut lines = read_recording(path)?.into_iter();
+        let Some(RecordLine::Start { snapshot }) = lines.next() else {
+            return Err(format!("Recording {path} does not start with the galaxy description"));
+        };
+        let events = lines
+            .map(|line| match line {
+                RecordLine::Event { time, event } => Ok((time, event)),
+                RecordLine::Start { .. } => Err(format!("Recording {path} contains more than one start line"))
+            })
+            .collect::<Result<_, String>>()?;
+        Ok(Self::new(snapshot, events))
+    }
+
+    fn new(snapshot: GameSnapshot, events: VecDeque<(u32, GameEvent)>) -> Self {
+        let planets = snapshot
+            .planets
+            .into_iter()
+            .map(|planet| {
+                (planet.id, PlanetInfo {
+                    status: Status::Running,
+                    energy_cells: planet.energy_cells,
+                    charged_cells_count: planet.charged_cells_count,
+                    rocket: planet.has_rocket,
+                    name: planet.planet_type
+                })
+            })
+            .collect();
+        let explorers = snapshot
+            .explorers
+            .into_iter()
+            .map(|explorer| {
+                let content = explorer
+                    .bag
+                    .iter()
+                    .filter_map(|(name, &qty)| Some((resource_from_name(name)?, qty)))
+                    .collect();
+                (explorer.id, ExplorerInfo {
+                    status: Status::Running,
+                    current_planet_id: explorer.planet,
+                    bag: BagContent { content }
+                })
+            })
+            .collect();
+        GameReplay { time: snapshot.time, events, planets, explorers, edges: snapshot.edges }
+    }
+
+    pub fn get_time(&self) -> u32 { self.time }
+
+    pub fn is_over(&self) -> bool { self.events.is_empty() }
+
+    /// Applies the events of the current time step and returns them.
+    pub fn step(&mut self) -> Vec<GameEvent> {
+        let mut step_events = Vec::new();
+        while self.events.front().is_some_and(|(time, _)| *time <= self.time) {
+            let (_, event) = self.events.pop_front().unwrap(); // checked above
+            self.apply(&event);
+            step_events.push(event);
+        }
+        self.time += 1;
+        step_events
+    }
+
+    fn apply(&mut self, event: &GameEvent) {
+        let event = match event {
+            GameEvent::Orchestrator(event) => event,
+            GameEvent::Supervision(SupervisionEvent::ExplorerEvicted { explorer_id, .. }) => {
+                if let Some(explorer) = self.explorers.get_mut(explorer_id) {
+                    explorer.status = Status::Dead;
+                }
+                return;
+            }
+            GameEvent::Supervision(_) => return
+        };
+        match *event {
+            OrchestratorEvent::SunrayReceived { planet_id } =>
+                if let Some(planet) = self.planets.get_mut(&planet_id)
+                    && let Some(cell) = planet.energy_cells.iter_mut().find(|cell| !**cell)
+                {
+                    *cell = true;
+                    planet.charged_cells_count += 1;
+                },
+            OrchestratorEvent::AsteroidSent { planet_id } =>
+                if let Some(planet) = self.planets.get_mut(&planet_id) {
+                    planet.rocket = false; // either used or the planet is destroyed
+                },
+            OrchestratorEvent::PlanetDestroyed { planet_id } => self.destroy_planet(planet_id),
+            OrchestratorEvent::ExplorerMoved { explorer_id, destination } =>
+                if let Some(explorer) = self.explorers.get_mut(&explorer_id) {
+                    explorer.current_planet_id = destination;
+                },
+            OrchestratorEvent::BasicResourceGenerated { explorer_id, resource } =>
+                self.resource_generated(explorer_id, ResourceType::Basic(resource)),
+            OrchestratorEvent::ComplexResourceGenerated { explorer_id, resource } => {
+                let (a, b) = get_recipe(resource);
+                if let Some(explorer) = self.explorers.get_mut(&explorer_id) {
+                    for ingredient in [a, b] {
+                        explorer.bag.content.entry(ingredient).and_modify(|qty| *qty = qty.saturating_sub(1));
+                    }
+                }
+                self.resource_generated(explorer_id, ResourceType::Complex(resource));
+            }
+            OrchestratorEvent::SunraySent { .. } => {}
+        }
+    }
+
+    fn destroy_planet(&mut self, planet_id: ID) {
+        if let Some(planet) = self.planets.get_mut(&planet_id) {
+            planet.status = Status::Dead;
+            planet.energy_cells.clear();
+            planet.charged_cells_count = 0;
+            planet.rocket = false;
+        }
+        self.edges.retain(|&(a, b)| a != planet_id && b != planet_id);
+        for explorer in self.explorers.values_mut().filter(|e| e.current_planet_id == planet_id) {
+            explorer.status = Status::Dead;
+        }
+    }
+
+    /// Adds the resource to the explorer bag and discharges a cell of its planet.
+    fn resource_generated(&mut self, explorer_id: ID, resource: ResourceType) {
+        let Some(explorer) = self.explorers.get_mut(&explorer_id) else {
+            return;
+        };
+        *explorer.bag.content.entry(resource).or_default() += 1;
+        if let Some(planet) = self.planets.get_mut(&explorer.current_planet_id)
+            && let Some(cell) = planet.energy_cells.iter_mut().find(|cell| **cell)
+        {
+            *cell = false;
+            planet.charged_cells_count -= 1;
+        }
+    }
+
+    pub fn get_topology(&self) -> Vec<(ID, ID)> { self.edges.clone() }
+
+    pub fn get_alive_planets(&self) -> Vec<ID> {
+        self.planets.iter().filter(|(_, planet)| planet.status != Status::Dead).map(|(&id, _)| id).collect()
+    }
+
+    pub fn get_planets_info(&self) -> PlanetInfoMap { PlanetInfoMap { map: self.planets.clone() } }
+
+    pub fn get_explorer_states(&self) -> ExplorerInfoMap {
+        let map = self
+            .explorers
+            .iter()
+            .map(|(&id, explorer)| {
+                (id, ExplorerInfo {
+                    status: explorer.status,
+                    current_planet_id: explorer.current_planet_id,
+                    bag: explorer.bag.clone()
+                })
+            })
+            .collect();
+        ExplorerInfoMap { map }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gui::EventRecorder;
+    use crate::orchestrator::{Orchestrator, OrchestratorMode};
+
+    #[test]
+    fn test_replay_recorded_game() {
+        let path = std::env::temp_dir().join("rusty_crab_recording_test.jsonl");
+        let path = path.to_str().unwrap();
+
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, 7, vec![], 5).unwrap();
+        orchestrator.manual_init().unwrap();
+        let recorder = EventRecorder::create(path, orchestrator.snapshot().unwrap()).unwrap();
+        orchestrator.get_gui_events_buffer().set_recorder(recorder);
+        for _ in 0..40 {
+            if orchestrator.is_game_over() {
+                break;
+            }
+            orchestrator.manual_step().unwrap();
+        }
+        let played = orchestrator.get_gui_events_buffer().drain_game_events();
+
+        let mut replay = GameReplay::from_file(path).unwrap();
+        let mut replayed = Vec::new();
+        while !replay.is_over() {
+            replayed.extend(replay.step());
+        }
+
+        assert_eq!(replayed, played);
+        assert_eq!(replay.get_alive_planets(), orchestrator.get_alive_planets());
+        let mut topology = orchestrator.get_topology();
+        topology.sort_unstable();
+        let mut replay_topology = replay.get_topology();
+        replay_topology.sort_unstable();
+        assert_eq!(replay_topology, topology);
+    }
+}
@@ -1,26 +1,29 @@
 use std::collections::BTreeMap;
 
-use common_game::components::resource::{BasicResourceType, ComplexResourceType};
+use common_game::components::resource::{BasicResourceType, ComplexResourceType, ResourceType};
 use common_game::utils::ID;
+use serde::de::Error;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
-use crate::app::AppConfig;
 use crate::explorers::BagContent;
-use crate::orchestrator::{Orchestrator, PLANET_ORDER, PlanetType};
+use crate::orchestrator::{Orchestrator, PlanetType, resource_from_name, resource_name};
 
 // Functions to bridge the orchestrator state with the GUI resources
 impl Orchestrator {
     pub fn get_planets_info(&self) -> PlanetInfoMap {
+        if let Some(replay) = self.get_replay() {
+            return replay.get_planets_info();
+        }
         let mut map = BTreeMap::new();
-        // Check all the IDs, this usually is not reliable but the GUI can only have n planets from the config
-        for id in 1..=AppConfig::get().number_of_planets {
-            match self.get_planet_state(id as ID) {
+        for (&id, &name) in self.get_planet_types() {
+            match self.get_planet_state(id) {
                 Some(Ok(state)) => {
                     map.insert(id, PlanetInfo {
                         status: Status::Running,
                         energy_cells: state.energy_cells,
                         charged_cells_count: state.charged_cells_count,
                         rocket: state.has_rocket,
-                        name: PLANET_ORDER[(id as usize - 1) % PLANET_ORDER.len()]
+                        name
                     });
                 }
                 Some(Err(e)) => {
@@ -33,7 +36,7 @@ impl Orchestrator {
                         energy_cells: vec![],
                         charged_cells_count: 0,
                         rocket: false,
-                        name: PLANET_ORDER[0]
+                        name
                     });
                 }
             }
@@ -42,11 +45,12 @@ impl Orchestrator {
         PlanetInfoMap { map }
     }
 
-    #[allow(clippy::cast_possible_truncation)] // We will never have that many planets
     pub fn get_explorer_states(&self) -> ExplorerInfoMap {
-        let cfg = AppConfig::get();
+        if let Some(replay) = self.get_replay() {
+            return replay.get_explorer_states();
+        }
         let mut map = BTreeMap::new();
-        for id in (cfg.number_of_planets + 1)..=(cfg.number_of_planets + cfg.explorers.len() as u32) {
+        for id in self.get_explorer_ids() {
             match self.get_explorer_current_planet(id) {
                 Some(current_planet_id) => {
                     let bag = self.get_explorer_bag(id).cloned().unwrap_or_default();
@@ -85,7 +89,7 @@ pub struct PlanetInfo {
 
 #[derive(Clone)]
 pub struct PlanetInfoMap {
-    map: BTreeMap<u32, PlanetInfo>
+    pub(super) map: BTreeMap<u32, PlanetInfo>
 }
 
 impl PlanetInfoMap {
@@ -106,10 +110,12 @@ pub struct ExplorerInfo {
 
 #[derive(Debug)]
 pub struct ExplorerInfoMap {
-    map: BTreeMap<u32, ExplorerInfo>
+    pub(super) map: BTreeMap<u32, ExplorerInfo>
 }
 
 impl ExplorerInfoMap {
+    pub fn iter(&self) -> impl Iterator<Item = (&u32, &ExplorerInfo)> { self.map.iter() }
+
     #[allow(clippy::trivially_copy_pass_by_ref)] // defined in gui, not out api
     pub fn get(&self, id: &u32) -> Option<&ExplorerInfo> { self.map.get(id) }
 
@@ -117,14 +123,80 @@ impl ExplorerInfoMap {
     pub fn get_current_planet(&self, id: &u32) -> u32 { self.map.get(id).unwrap().current_planet_id }
 }
 
+/// The events drawn by the GUI. The `omc-gui` code matches on them exhaustively, so a new kind of event
+/// goes in `SupervisionEvent` unless the GUI is updated with it.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub enum OrchestratorEvent {
     PlanetDestroyed { planet_id: u32 },
     SunraySent { planet_id: u32 },
     SunrayReceived { planet_id: u32 },
     AsteroidSent { planet_id: u32 },
     ExplorerMoved { explorer_id: u32, destination: u32 },
-    BasicResourceGenerated { explorer_id: u32, resource: BasicResourceType },
-    ComplexResourceGenerated { explorer_id: u32, resource: ComplexResourceType }
+    BasicResourceGenerated {
+        explorer_id: u32,
+        #[serde(serialize_with = "serialize_basic", deserialize_with = "deserialize_basic")]
+        resource: BasicResourceType
+    },
+    ComplexResourceGenerated {
+        explorer_id: u32,
+        #[serde(serialize_with = "serialize_complex", deserialize_with = "deserialize_complex")]
+        resource: ComplexResourceType
+    }
+}
+
+/// The faults handled by the orchestrator supervision, not drawn by the GUI but recorded and served by the API.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SupervisionEvent {
+    /// The planet crashed or stopped answering, it is destroyed right after
+    PlanetFailed { planet_id: u32, reason: String },
+    /// The explorer misbehaved and its AI was reset
+    ExplorerRestarted { explorer_id: u32, reason: String },
+    /// The explorer misbehaved too many times, or its thread terminated, and was removed from the game
+    ExplorerEvicted { explorer_id: u32, reason: String }
+}
+
+/// Every event of the game, in the order they happened. Both kinds are tagged by `type`, so the JSON of a
+/// `GameEvent` is the one of the event it wraps.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum GameEvent {
+    Orchestrator(OrchestratorEvent),
+    Supervision(SupervisionEvent)
+}
+
+impl From<OrchestratorEvent> for GameEvent {
+    fn from(event: OrchestratorEvent) -> Self { GameEvent::Orchestrator(event) }
+}
+
+impl From<SupervisionEvent> for GameEvent {
+    fn from(event: SupervisionEvent) -> Self { GameEvent::Supervision(event) }
+}
+
+// Resources are saved by name, as in the game snapshots
+fn serialize_basic<S: Serializer>(resource: &BasicResourceType, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&resource_name(ResourceType::Basic(*resource)))
+}
+
+fn serialize_complex<S: Serializer>(resource: &ComplexResourceType, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&resource_name(ResourceType::Complex(*resource)))
+}
+
+pub(crate) fn deserialize_basic<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BasicResourceType, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    match resource_from_name(&name) {
+        Some(ResourceType::Basic(resource)) => Ok(resource),
+        _ => Err(D::Error::custom(format!("Unknown basic resource {name}")))
+    }
+}
+
+pub(crate) fn deserialize_complex<'de, D: Deserializer<'de>>(deserializer: D) -> Result<ComplexResourceType, D::Error> {
+    let name = String::deserialize(deserializer)?;
+    match resource_from_name(&name) {
+        Some(ResourceType::Complex(resource)) => Ok(resource),
+        _ => Err(D::Error::custom(format!("Unknown complex resource {name}")))
+    }
 }
 
 pub fn get_planet_basic_resources(planet_type: PlanetType) -> Vec<BasicResourceType> {
@@ -5,7 +5,6 @@ mod orchestrator;
 
 use orchestrator::{Orchestrator, OrchestratorMode};
 
-use crate::explorers::ExplorerFactory;
 use crate::gui::run_gui;
 
 fn init() {
@@ -26,23 +25,48 @@ fn main() {
 
     let config = app::AppConfig::get();
 
+    if let Some(address) = &config.remote_explorer {
+        explorers::run_remote_explorer(address);
+        return;
+    }
+
     if config.show_gui {
         run_gui();
         return;
     }
 
-    let explorers = config.explorers.iter().map(ExplorerFactory::make_from_name).collect();
+    if let Some(runs) = config.runs {
+        app::run_batch(runs);
+        return;
+    }
+
+    if let Some(path) = &config.scenario {
+        app::run_scenario(path);
+        return;
+    }
 
-    let mut orchestrator = Orchestrator::new(
-        OrchestratorMode::Auto,
-        config.number_of_planets,
-        explorers // No explorers implemented yet
-    )
-    .unwrap_or_else(|e| {
+    if let Some(port) = config.serve {
+        app::run_api(port);
+        return;
+    }
+
+    if config.repl {
+        app::run_repl();
+        return;
+    }
+
+    if let Some(seeds) = config.tournament {
+        app::run_tournament(seeds);
+        return;
+    }
+
+    let mode = if config.real_time { OrchestratorMode::RealTime } else { OrchestratorMode::Auto };
+    let mut orchestrator = Orchestrator::from_config(mode).unwrap_or_else(|e| {
         log::error!("Failed to create orchestrator: {e}");
         panic!("Failed to create orchestrator: {e}");
     });
-    orchestrator.run().unwrap_or_else(|e| {
-        log::error!("Orchestrator terminated with error: {e}");
-    });
+    match orchestrator.run() {
+        Ok(result) => print!("{result}"),
+        Err(e) => log::error!("Orchestrator terminated with error: {e}")
+    }
 }
@@ -1,9 +1,12 @@
 use std::collections::{HashMap, VecDeque};
+use std::time::Instant;
 
 use common_game::utils::ID;
+use crossbeam_channel::RecvTimeoutError;
 
 use super::logging_channel::{ActorMarker, ExplorerMarker, LoggingReceiver, PlanetMarker};
 use crate::app::AppConfig;
+use crate::orchestrator::OrchestratorError;
 
 /// This wrapper around a channel receiver divides the stream per sender (planet and explorer IDs).
 pub(crate) struct ChannelDemultiplexer<A: ActorMarker> {
@@ -14,8 +17,14 @@ pub(crate) struct ChannelDemultiplexer<A: ActorMarker> {
 impl<A: ActorMarker> ChannelDemultiplexer<A> {
     pub fn new(receiver: LoggingReceiver<A>) -> Self { Self { receiver, buffers: HashMap::new() } }
 
-    pub fn recv_from(&mut self, id: ID) -> Result<A::RecvMsg, String> {
+    pub fn recv_from(&mut self, id: ID) -> Result<A::RecvMsg, OrchestratorError> {
         let timeout = std::time::Duration::from_millis(AppConfig::get().max_wait_time_ms);
+        self.recv_from_before(id, Instant::now() + timeout)
+    }
+
+    /// Like `recv_from`, but with a deadline instead of a timeout, so that it can be shared by the answers
+    /// to many requests sent together.
+    pub fn recv_from_before(&mut self, id: ID, deadline: Instant) -> Result<A::RecvMsg, OrchestratorError> {
         // Check if we have buffered messages for this ID
         if let Some(buffer) = self.buffers.get_mut(&id)
             && let Some(msg) = buffer.pop_front()
@@ -24,12 +33,8 @@ impl<A: ActorMarker> ChannelDemultiplexer<A> {
         }
 
         // Keep receiving until we find a message from the desired ID or timeout
-        let start_time = std::time::Instant::now();
-        while start_time.elapsed() < timeout {
-            let msg = self
-                .receiver
-                .recv_timeout(timeout)
-                .map_err(|e| format!("Error waiting for message from ID {id}: {e}"))?;
+        while let Some(remaining) = deadline.checked_duration_since(Instant::now()) {
+            let msg = self.receiver.recv_timeout(remaining).map_err(|e| Self::recv_error(&e, Some(id)))?;
             let msg_id = A::get_id(&msg);
 
             if msg_id == id {
@@ -39,14 +44,34 @@ impl<A: ActorMarker> ChannelDemultiplexer<A> {
             // Buffer the message for later
             self.buffers.entry(msg_id).or_default().push_back(msg);
         }
-        Err(format!("Timeout waiting for message from ID {id}"))
+        Err(OrchestratorError::Timeout { actor: A::actor(), id: Some(id) })
     }
 
     /// Doesn't require mut, just receives the next available message from any sender.
-    pub fn recv_any(&self) -> Result<A::RecvMsg, String> {
+    pub fn recv_any(&self) -> Result<A::RecvMsg, OrchestratorError> {
         let timeout = std::time::Duration::from_millis(AppConfig::get().max_wait_time_ms);
 
-        self.receiver.recv_timeout(timeout).map_err(|e| format!("Error waiting for message: {e}"))
+        self.receiver.recv_timeout(timeout).map_err(|e| Self::recv_error(&e, None))
+    }
+
+    /// Receives the next message from any sender, the buffered ones first. `None` if the deadline passes.
+    pub fn recv_any_before(&mut self, deadline: Instant) -> Result<Option<A::RecvMsg>, OrchestratorError> {
+        if let Some(msg) = self.buffers.values_mut().find_map(VecDeque::pop_front) {
+            return Ok(Some(msg));
+        }
+        match self.receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok(msg) => Ok(Some(msg)),
+            Err(RecvTimeoutError::Timeout) => Ok(None),
+            Err(e) => Err(Self::recv_error(&e, None))
+        }
+    }
+
+    fn recv_error(error: &RecvTimeoutError, id: Option<ID>) -> OrchestratorError {
+        match error {
+            RecvTimeoutError::Timeout => OrchestratorError::Timeout { actor: A::actor(), id },
+            // The receiver is shared by all the actors of this kind, so no one can send anymore
+            RecvTimeoutError::Disconnected => OrchestratorError::ChannelClosed { actor: A::actor(), id: None }
+        }
     }
 }
 
@@ -100,11 +125,36 @@ mod tests {
         assert_eq!(received.explorer_id(), 2);
     }
 
+    #[test]
+    fn receive_before_shared_deadline() {
+        let (tx, mut mux) = make_mux();
+        tx.send(msg(3)).unwrap();
+        tx.send(msg(1)).unwrap();
+
+        let deadline = Instant::now() + std::time::Duration::from_millis(50);
+        assert_eq!(mux.recv_from_before(1, deadline).unwrap().explorer_id(), 1);
+        assert_eq!(mux.recv_from_before(3, deadline).unwrap().explorer_id(), 3);
+        let result = mux.recv_from_before(2, deadline);
+        assert_eq!(result.unwrap_err(), OrchestratorError::Timeout { actor: ExplorerMarker::actor(), id: Some(2) });
+    }
+
+    #[test]
+    fn receive_any_buffered_first() {
+        let (tx, mut mux) = make_mux();
+        tx.send(msg(2)).unwrap();
+        tx.send(msg(1)).unwrap();
+        mux.recv_from(1).unwrap(); // buffers the message of 2
+
+        let deadline = Instant::now() + std::time::Duration::from_millis(50);
+        assert_eq!(mux.recv_any_before(deadline).unwrap().unwrap().explorer_id(), 2);
+        assert!(mux.recv_any_before(deadline).unwrap().is_none());
+    }
+
     #[test]
     fn receive_missing_id() {
         let (tx, mut mux) = make_mux();
 
         let result = mux.recv_from(67);
-        assert!(result.is_err());
+        assert_eq!(result.unwrap_err(), OrchestratorError::Timeout { actor: ExplorerMarker::actor(), id: Some(67) });
     }
 }
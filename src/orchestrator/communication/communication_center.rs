@@ -1,8 +1,11 @@
 use std::collections::HashMap;
+use std::time::{Duration, Instant};
 
 use common_game::utils::ID;
 
+use crate::app::AppConfig;
 use crate::orchestrator::communication::channel_demultiplexer::ChannelDemultiplexer;
+use crate::orchestrator::OrchestratorError;
 use crate::orchestrator::communication::logging_channel::{ActorMarker, ExplorerMarker, LoggingSender, PlanetMarker};
 
 /// Like a control tower, this struct provides utilities and logic handling for communication
@@ -18,50 +21,84 @@ impl<A: ActorMarker> CommunicationCenter<A> {
 
     pub fn remove(&mut self, id: ID) { self.tx.remove(&id); }
 
-    pub fn send_to(&self, id: ID, msg: A::SendMsg) -> Result<(), String> {
-        self.tx[&id].send(msg, id).map_err(|e| e.to_string())
+    pub fn send_to(&self, id: ID, msg: A::SendMsg) -> Result<(), OrchestratorError> {
+        let tx = self.tx.get(&id).ok_or(OrchestratorError::unknown(A::actor(), id))?;
+        tx.send(msg, id).map_err(|_| OrchestratorError::ChannelClosed { actor: A::actor(), id: Some(id) })
     }
 
     #[allow(clippy::needless_pass_by_value)] // msg kind il less than 8 bytes so passing by value is fine
-    pub fn req_ack(&mut self, id: ID, msg: A::SendMsg, expected: A::RecvMsgKind) -> Result<A::RecvMsg, String> {
+    pub fn req_ack(
+        &mut self,
+        id: ID,
+        msg: A::SendMsg,
+        expected: A::RecvMsgKind
+    ) -> Result<A::RecvMsg, OrchestratorError> {
         self.send_to(id, msg)?;
-        self.recv_from(id).map(|res| {
-            if A::RecvMsgKind::from(&res) == expected {
-                Ok(res)
-            } else {
-                Err(format!("Expected {} {id} to respond with {expected:?}, but got {res:?}", A::get_name()))
-            }
-        })? // Flatten the Result<Result<...>>
+        let res = self.recv_from(id)?;
+        Self::expect_kind(id, &expected, res)
+    }
+
+    /// Like `req_ack` for many actors at once: every request is sent before waiting for the answers, so that
+    /// the actors work in parallel and a slow one does not delay the others. The answers share a single
+    /// deadline and are returned in the order of the requests.
+    #[allow(clippy::needless_pass_by_value)] // msg kind il less than 8 bytes so passing by value is fine
+    pub fn broadcast_req_ack(
+        &mut self,
+        requests: Vec<(ID, A::SendMsg)>,
+        expected: A::RecvMsgKind
+    ) -> Vec<(ID, Result<A::RecvMsg, OrchestratorError>)> {
+        let deadline = Instant::now() + Duration::from_millis(AppConfig::get().max_wait_time_ms);
+        let sent: Vec<(ID, Result<(), OrchestratorError>)> =
+            requests.into_iter().map(|(id, msg)| (id, self.send_to(id, msg))).collect();
+        sent.into_iter()
+            .map(|(id, sent)| {
+                let res = sent
+                    .and_then(|()| self.rx.recv_from_before(id, deadline))
+                    .and_then(|res| Self::expect_kind(id, &expected, res));
+                (id, res)
+            })
+            .collect()
     }
 
     /// Same as `req_ack` but doesn't require &mut self. Doesn't buffer messages.
     /// May lead to lost messages if another actor sends a message while waiting for the response.
     #[allow(clippy::needless_pass_by_value)] // msg kind il less than 8 bytes so passing by value is fine
-    pub fn riskier_req_ack(&self, id: ID, msg: A::SendMsg, expected: A::RecvMsgKind) -> Result<A::RecvMsg, String> {
+    pub fn riskier_req_ack(
+        &self,
+        id: ID,
+        msg: A::SendMsg,
+        expected: A::RecvMsgKind
+    ) -> Result<A::RecvMsg, OrchestratorError> {
         self.send_to(id, msg)?;
-        match self.rx.recv_any() {
-            Ok(res) => {
-                if A::get_id(&res) != id {
-                    return Err(format!(
-                        "Expected response from Þ{} {id}, but got message from {} {}: {res:?}",
-                        A::get_name(),
-                        A::get_name(),
-                        A::get_id(&res)
-                    ));
-                }
-                if A::RecvMsgKind::from(&res) != expected {
-                    return Err(format!(
-                        "Expected {} {id} to respond with {expected:?}, but got {res:?}",
-                        A::get_name()
-                    ));
-                }
-                Ok(res)
-            }
-            Err(e) => Err(format!("Error receiving response from {} {id}: {e}", A::get_name()))
+        let res = self.rx.recv_any().map_err(|e| match e {
+            OrchestratorError::Timeout { actor, .. } => OrchestratorError::Timeout { actor, id: Some(id) },
+            other => other
+        })?;
+        // A message from another actor is unexpected as well
+        if A::get_id(&res) != id || A::RecvMsgKind::from(&res) != expected {
+            return Err(Self::unexpected(id, &expected, &res));
         }
+        Ok(res)
+    }
+
+    pub fn recv_from(&mut self, id: ID) -> Result<A::RecvMsg, OrchestratorError> { self.rx.recv_from(id) }
+
+    pub fn recv_any_before(&mut self, deadline: Instant) -> Result<Option<A::RecvMsg>, OrchestratorError> {
+        self.rx.recv_any_before(deadline)
+    }
+
+    fn expect_kind(id: ID, expected: &A::RecvMsgKind, res: A::RecvMsg) -> Result<A::RecvMsg, OrchestratorError> {
+        if A::RecvMsgKind::from(&res) == *expected { Ok(res) } else { Err(Self::unexpected(id, expected, &res)) }
     }
 
-    pub fn recv_from(&mut self, id: ID) -> Result<A::RecvMsg, String> { self.rx.recv_from(id) }
+    fn unexpected(id: ID, expected: &A::RecvMsgKind, got: &A::RecvMsg) -> OrchestratorError {
+        OrchestratorError::UnexpectedMessage {
+            actor: A::actor(),
+            id,
+            expected: format!("{expected:?}"),
+            got: format!("{got:?}")
+        }
+    }
 }
 
 pub(crate) type PlanetCommunicationCenter = CommunicationCenter<PlanetMarker>;
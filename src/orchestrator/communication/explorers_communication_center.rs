@@ -4,6 +4,7 @@ use common_game::utils::ID;
 use crossbeam_channel::Sender;
 
 use crate::orchestrator::communication::ExplorerCommunicationCenter;
+use crate::orchestrator::{Actor, OrchestratorError};
 
 impl ExplorerCommunicationCenter {
     pub fn notify_explorer_successful_movement(
@@ -11,7 +12,7 @@ impl ExplorerCommunicationCenter {
         explorer_id: ID,
         planet_id: ID,
         new_sender: Sender<ExplorerToPlanet>
-    ) -> Result<(), String> {
+    ) -> Result<(), OrchestratorError> {
         let new_planet_id = self
             .req_ack(
                 explorer_id,
@@ -23,10 +24,11 @@ impl ExplorerCommunicationCenter {
             .1; // Unwrap is safe due to expected kind
 
         if new_planet_id != planet_id {
-            return Err(format!(
-                "Explorer {explorer_id} moved to planet {new_planet_id}, but was expected to move to planet \
-                 {planet_id}"
-            ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Explorer,
+                id: explorer_id,
+                reason: format!("moved to planet {new_planet_id}, but was expected to move to planet {planet_id}")
+            });
         }
         Ok(())
     }
@@ -15,6 +15,7 @@ use common_game::utils::ID;
 use crossbeam_channel::{Receiver, Sender};
 
 use crate::explorers::BagContent;
+use crate::orchestrator::Actor;
 
 const ORCHESTRATOR_PARTICIPANT: Option<Participant> = Some(Participant { actor_type: Orchestrator, id: 0 });
 
@@ -30,7 +31,7 @@ pub trait ActorMarker {
     fn event_type_recv() -> EventType;
     fn actor_type() -> common_game::logging::ActorType;
     fn get_id(msg: &Self::RecvMsg) -> ID;
-    fn get_name() -> &'static str;
+    fn actor() -> Actor;
 }
 
 impl ActorMarker for ExplorerMarker {
@@ -41,7 +42,7 @@ impl ActorMarker for ExplorerMarker {
     fn event_type_recv() -> EventType { MessageExplorerToOrchestrator }
     fn actor_type() -> common_game::logging::ActorType { Explorer }
     fn get_id(msg: &Self::RecvMsg) -> ID { msg.explorer_id() }
-    fn get_name() -> &'static str { "explorer" }
+    fn actor() -> Actor { Actor::Explorer }
 }
 
 impl ActorMarker for PlanetMarker {
@@ -52,7 +53,7 @@ impl ActorMarker for PlanetMarker {
     fn event_type_recv() -> EventType { MessagePlanetToOrchestrator }
     fn actor_type() -> common_game::logging::ActorType { Planet }
     fn get_id(msg: &Self::RecvMsg) -> ID { msg.planet_id() }
-    fn get_name() -> &'static str { "planet" }
+    fn actor() -> Actor { Actor::Planet }
 }
 
 pub struct LoggingSender<A: ActorMarker> {
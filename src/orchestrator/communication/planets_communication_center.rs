@@ -4,6 +4,7 @@ use common_game::utils::ID;
 use crossbeam_channel::Sender;
 
 use crate::orchestrator::communication::PlanetCommunicationCenter;
+use crate::orchestrator::{Actor, OrchestratorError};
 
 impl PlanetCommunicationCenter {
     pub(crate) fn notify_planet_incoming_explorer(
@@ -11,7 +12,7 @@ impl PlanetCommunicationCenter {
         explorer_id: ID,
         dst_planet_id: ID,
         new_sender: Sender<PlanetToExplorer>
-    ) -> Result<(), String> {
+    ) -> Result<(), OrchestratorError> {
         let (_, accepted_explorer_id, res) = self
             .req_ack(
                 dst_planet_id,
@@ -21,23 +22,32 @@ impl PlanetCommunicationCenter {
             .into_incoming_explorer_response()
             .unwrap(); // Unwrap is safe due to expected kind
 
-        if res.is_err() {
-            return Err(format!(
-                "Planet {dst_planet_id} failed to accept incoming explorer {explorer_id}: {}",
-                res.err().unwrap()
-            ));
+        if let Err(e) = res {
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Planet,
+                id: dst_planet_id,
+                reason: format!("failed to accept incoming explorer {explorer_id}: {e}")
+            });
         }
 
         if accepted_explorer_id != explorer_id {
-            return Err(format!(
-                "Planet {dst_planet_id} accepted incoming explorer {accepted_explorer_id}, but was expected to accept \
-                 explorer {explorer_id}"
-            ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Planet,
+                id: dst_planet_id,
+                reason: format!(
+                    "accepted incoming explorer {accepted_explorer_id}, but was expected to accept explorer \
+                     {explorer_id}"
+                )
+            });
         }
         Ok(())
     }
 
-    pub(crate) fn notify_planet_explorer_left(&mut self, explorer_id: ID, current_planet_id: ID) -> Result<(), String> {
+    pub(crate) fn notify_planet_explorer_left(
+        &mut self,
+        explorer_id: ID,
+        current_planet_id: ID
+    ) -> Result<(), OrchestratorError> {
         let (_, left_explorer_id, res) = self
             .req_ack(
                 current_planet_id,
@@ -47,18 +57,22 @@ impl PlanetCommunicationCenter {
             .into_outgoing_explorer_response()
             .unwrap(); // Unwrap is safe due to expected kind
 
-        if res.is_err() {
-            return Err(format!(
-                "Planet {current_planet_id} failed to confirm outgoing explorer {explorer_id}: {}",
-                res.err().unwrap()
-            ));
+        if let Err(e) = res {
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Planet,
+                id: current_planet_id,
+                reason: format!("failed to confirm outgoing explorer {explorer_id}: {e}")
+            });
         }
 
         if left_explorer_id != explorer_id {
-            return Err(format!(
-                "Planet {current_planet_id} confirmed outgoing explorer {left_explorer_id}, but was expected to \
-                 confirm explorer {explorer_id}"
-            ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Planet,
+                id: current_planet_id,
+                reason: format!(
+                    "confirmed outgoing explorer {left_explorer_id}, but was expected to confirm explorer {explorer_id}"
+                )
+            });
         }
         Ok(())
     }
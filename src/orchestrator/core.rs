@@ -1,18 +1,24 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::thread;
+use std::time::{Duration, Instant};
 
 use common_game::components::planet::{DummyPlanetState, Planet};
 use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestratorKind, OrchestratorToExplorer};
 use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetToOrchestratorKind};
 use common_game::utils::ID;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
-use crate::explorers::{BagContent, ExplorerBuilder};
-use crate::gui::GuiEventBuffer;
+use crate::app::AppConfig;
+use crate::explorers::{BagContent, ExplorerBuilder, ExplorerFactory};
+use crate::gui::{EventRecorder, GameReplay, GuiEventBuffer};
 use crate::orchestrator::communication::{ExplorerCommunicationCenter, PlanetCommunicationCenter};
 use crate::orchestrator::state::OrchestratorManualAction;
-use crate::orchestrator::{ExplorerChannelDemultiplexer, ExplorerHandle, ExplorerLoggingReceiver,
-                          ExplorerLoggingSender, GalaxyBuilder, OrchestratorState, OrchestratorUpdateFactory,
-                          PlanetChannelDemultiplexer, PlanetHandle, PlanetLoggingReceiver, PlanetLoggingSender};
+use crate::orchestrator::{EndCondition, EndReason, ExplorerChannelDemultiplexer, ExplorerHandle,
+                          ExplorerLoggingReceiver, ExplorerLoggingSender, GalaxyBuilder, GalaxyLayout, GameResult,
+                          GameSnapshot, HistoryAction, ManualActionResult, ManualHistory, OrchestratorError,
+                          OrchestratorState, OrchestratorStats, OrchestratorUpdateFactory, PlanetChannelDemultiplexer,
+                          PlanetHandle, PlanetLoggingReceiver, PlanetLoggingSender, PlanetType};
 
 /// The Orchestrator is the main entity that manages the game.
 /// It's responsible for managing the communication and threads (IPC)
@@ -23,27 +29,119 @@ pub(crate) struct Orchestrator {
 
     state: OrchestratorState,
 
-    manual_commands: Vec<OrchestratorManualAction>
+    manual_commands: Vec<OrchestratorManualAction>,
+    initialized: bool, // planets and explorers AI started
+    started_at: Option<Instant>, // wall-clock start of the game, set by `manual_init`
+
+    end_condition: EndCondition,
+
+    // Manual actions that can be undone
+    history: ManualHistory,
+
+    // Recorded game shown instead of a real one, no planet or explorer is started
+    replay: Option<GameReplay>
 }
 
 #[allow(dead_code)] // only one at a time is used
 #[derive(Clone, Copy)]
 pub(crate) enum OrchestratorMode {
     Auto,
-    Manual
+    Manual,
+    /// Every step lasts `real_time_tick_ms`, explorers act without waiting for each other
+    RealTime
 }
 
 impl Orchestrator {
+    /// Creates the game selected by the config and command line: a replay, a resumed snapshot, a layout
+    /// file or a generated galaxy, in this order of precedence.
+    pub fn from_config(mode: OrchestratorMode) -> Result<Self, OrchestratorError> {
+        let config = AppConfig::get();
+        if let Some(recording) = &config.replay {
+            Self::from_recording(recording)
+        } else if let Some(snapshot_file) = &config.resume {
+            Self::from_snapshot(mode, &GameSnapshot::load(snapshot_file).map_err(OrchestratorError::Io)?)
+        } else if let Some(galaxy_file) = &config.galaxy_file {
+            Self::from_layout_file(mode, galaxy_file)
+        } else {
+            let explorers = config.explorers.iter().map(ExplorerFactory::make_from_name).collect();
+            Self::new(mode, config.number_of_planets, explorers)
+        }
+    }
+
+    /// Creates an orchestrator seeded from the config, or with a random seed if none is set.
     pub fn new(
         mode: OrchestratorMode,
         n_planets: u32,
         explorer_builders: Vec<Box<dyn ExplorerBuilder>>
-    ) -> Result<Self, String> {
-        let initial_galaxy = GalaxyBuilder::new()
-            .with_fully_connected_topology()
+    ) -> Result<Self, OrchestratorError> {
+        let seed = AppConfig::get().seed.unwrap_or_else(rand::random);
+        Self::new_with_seed(mode, n_planets, explorer_builders, seed)
+    }
+
+    /// Two orchestrators created with the same seed, number of planets and explorers play the same game.
+    pub fn new_with_seed(
+        mode: OrchestratorMode,
+        n_planets: u32,
+        explorer_builders: Vec<Box<dyn ExplorerBuilder>>,
+        seed: u64
+    ) -> Result<Self, OrchestratorError> {
+        let mut rng = Self::make_rng(seed);
+        let galaxy_builder = GalaxyBuilder::new()
+            .with_seed(rng.random())
+            .with_topology(AppConfig::get().topology)
             .with_n_planets(n_planets)
             .with_explorers(explorer_builders)
-            .build()?;
+            .with_explorer_placement(AppConfig::get().explorer_placement.clone())
+            .with_planet_mix(AppConfig::get().planet_mix.clone())
+            .with_planet_options(AppConfig::get().planet_options.clone());
+        Self::from_galaxy_builder(mode, galaxy_builder, rng)
+    }
+
+    /// Creates an orchestrator with the galaxy described in a layout file (see `GalaxyLayout`).
+    pub fn from_layout_file(mode: OrchestratorMode, path: &str) -> Result<Self, OrchestratorError> {
+        Self::from_layout(mode, GalaxyLayout::from_file(path).map_err(OrchestratorError::Config)?)
+    }
+
+    pub fn from_layout(mode: OrchestratorMode, layout: GalaxyLayout) -> Result<Self, OrchestratorError> {
+        Self::from_layout_with_seed(mode, layout, AppConfig::get().seed.unwrap_or_else(rand::random))
+    }
+
+    pub fn from_layout_with_seed(
+        mode: OrchestratorMode,
+        layout: GalaxyLayout,
+        seed: u64
+    ) -> Result<Self, OrchestratorError> {
+        let mut rng = Self::make_rng(seed);
+        let galaxy_builder = GalaxyBuilder::new()
+            .with_seed(rng.random())
+            .with_layout(layout)
+            .with_planet_options(AppConfig::get().planet_options.clone());
+        Self::from_galaxy_builder(mode, galaxy_builder, rng)
+    }
+
+    /// Creates an orchestrator that replays a game recorded with the `record_file` option.
+    pub fn from_recording(path: &str) -> Result<Self, OrchestratorError> {
+        let mut orchestrator = Self::new_with_seed(OrchestratorMode::Manual, 0, vec![], 0)?;
+        orchestrator.replay = Some(GameReplay::from_file(path).map_err(OrchestratorError::Io)?);
+        Ok(orchestrator)
+    }
+
+    fn make_rng(seed: u64) -> StdRng {
+        log::info!("Creating orchestrator with seed {seed}");
+        StdRng::seed_from_u64(seed)
+    }
+
+    fn from_galaxy_builder(
+        mode: OrchestratorMode,
+        galaxy_builder: GalaxyBuilder,
+        rng: StdRng
+    ) -> Result<Self, OrchestratorError> {
+        let initial_galaxy = galaxy_builder.build().map_err(OrchestratorError::Config)?;
+        let scheduler = AppConfig::get().scheduler.build().map_err(OrchestratorError::Config)?;
+        let end_condition = AppConfig::get().end_condition.clone();
+        end_condition.validate().map_err(OrchestratorError::Config)?;
+        let explorer_types =
+            initial_galaxy.explorer_inits.iter().map(|(&id, init)| (id, init.type_name.clone())).collect();
 
         let (planet_handles, planet_senders) = initial_galaxy
             .planet_inits
@@ -77,9 +175,16 @@ impl Orchestrator {
         Ok(Orchestrator {
             mode,
             manual_commands: vec![],
+            initialized: false,
+            started_at: None,
+            end_condition,
+            history: ManualHistory::new(AppConfig::get().undo_history),
+            replay: None,
             state: OrchestratorState {
                 time: 0,
                 galaxy: initial_galaxy.galaxy,
+                planet_types: initial_galaxy.planet_types,
+                explorer_types,
                 planets: planet_handles,
                 explorers: explorer_handles,
                 planets_communication_center: PlanetCommunicationCenter::new(
@@ -95,50 +200,191 @@ impl Orchestrator {
                     ))
                 ),
                 gui_events_buffer: GuiEventBuffer::new(),
-                explorer_bags: HashMap::new()
+                stats: OrchestratorStats::new(),
+                explorer_bags: HashMap::new(),
+                explorer_strikes: HashMap::new(),
+                explorers_busy: HashSet::new(),
+                dead_explorers: HashSet::new(),
+                scheduler,
+                rng
             }
         })
     }
 
-    pub fn run(&mut self) -> Result<(), String> {
+    /// Plays the game until the end condition is met.
+    pub fn run(&mut self) -> Result<GameResult, OrchestratorError> {
         self.manual_init()?;
 
-        while !self.is_game_over() {
+        let reasons = loop {
+            if let Some(reasons) = self.end_reasons() {
+                break reasons;
+            }
             self.manual_step()?;
+            self.save_checkpoint_if_due();
+        };
+        let result = GameResult { reasons, turns: self.state.time, standings: self.state.standings() };
+        log::info!("Game over after {} turns: {:?}", result.turns, result.reasons);
+        if let Some(path) = &AppConfig::get().stats_file {
+            match self.state.stats.save_csv(path) {
+                Ok(()) => log::info!("Saved the game statistics to {path}"),
+                Err(e) => log::error!("{e}")
+            }
         }
-        Ok(())
+        Ok(result)
     }
 
-    pub fn manual_init(&mut self) -> Result<(), String> {
+    /// Starts the planets and explorers AI, does nothing if they are already started.
+    pub fn manual_init(&mut self) -> Result<(), OrchestratorError> {
+        if self.initialized {
+            return Ok(());
+        }
         self.send_planet_ai_start()?;
         self.notify_planet_explorer_channel()?;
         self.send_explorer_ai_start()?;
+        self.initialized = true;
+        self.started_at = Some(Instant::now());
+        if self.replay.is_none()
+            && let Some(path) = &AppConfig::get().record_file
+        {
+            let recorder = EventRecorder::create(path, self.snapshot()?).map_err(OrchestratorError::Io)?;
+            self.state.gui_events_buffer.set_recorder(recorder);
+            log::info!("Recording the game events to {path}");
+        }
         Ok(())
     }
 
-    pub fn manual_step(&mut self) -> Result<(), String> {
+    pub fn manual_step(&mut self) -> Result<(), OrchestratorError> {
+        if let Some(replay) = &mut self.replay {
+            for event in replay.step() {
+                log::info!("Replaying {event:?}");
+                self.state.gui_events_buffer.replay(event);
+            }
+            self.state.time = replay.get_time();
+            log::info!("--- Time step {} replayed ---", self.state.time);
+            return Ok(());
+        }
+        let recording = self.checkpoint_if_recording()?;
         OrchestratorUpdateFactory::get_strategy(self.mode, &mut self.state).update()?;
+        self.state.end_turn_stats();
         self.state.time += 1;
+        self.state.gui_events_buffer.set_time(self.state.time);
         log::info!("--- Time step {} completed ---", self.state.time);
+        if recording {
+            self.history.record(HistoryAction::Step);
+        }
         Ok(())
     }
 
-    pub fn is_game_over(&self) -> bool { self.state.galaxy.get_planets().is_empty() }
+    pub fn is_game_over(&self) -> bool { self.end_reasons().is_some() }
+
+    /// Why the game is over, `None` if it is not. See `EndCondition`.
+    pub fn end_reasons(&self) -> Option<Vec<EndReason>> {
+        if let Some(replay) = &self.replay {
+            return replay.is_over().then(|| vec![EndReason::ReplayOver]);
+        }
+        let elapsed = self.started_at.map_or(Duration::ZERO, |start| start.elapsed());
+        self.end_condition.evaluate(&self.state, elapsed).or_else(|| {
+            EndCondition::AllPlanetsDestroyed.evaluate(&self.state, elapsed) // nothing can happen anymore
+        })
+    }
+
+    /// Replaces the end condition of the config.
+    pub fn set_end_condition(&mut self, end_condition: EndCondition) { self.end_condition = end_condition; }
+
+    /// The statistics of the turns played so far.
+    #[allow(dead_code)] // used in tests
+    pub fn stats(&self) -> &OrchestratorStats { &self.state.stats }
 
     pub fn get_gui_events_buffer(&mut self) -> &mut GuiEventBuffer { &mut self.state.gui_events_buffer }
 
-    pub fn get_topology(&self) -> Vec<(ID, ID)> { self.state.galaxy.get_topology() }
+    pub fn get_topology(&self) -> Vec<(ID, ID)> {
+        match &self.replay {
+            Some(replay) => replay.get_topology(),
+            None => self.state.galaxy.get_topology()
+        }
+    }
+
+    pub(crate) fn get_replay(&self) -> Option<&GameReplay> { self.replay.as_ref() }
 
-    pub fn process_commands(&mut self) -> Result<(), String> {
-        let mut strategy = OrchestratorUpdateFactory::get_strategy(self.mode, &mut self.state);
-        for command in self.manual_commands.drain(..) {
-            strategy.process_command(command)?;
+    /// Runs the scheduled actions in order, stops at the first failure.
+    pub fn process_commands(&mut self) -> Result<Vec<ManualActionResult>, OrchestratorError> {
+        let commands = std::mem::take(&mut self.manual_commands);
+        commands.into_iter().map(|command| self.execute(command)).collect()
+    }
+
+    /// Runs a manual action right away and returns what it did, the scheduled actions are left alone.
+    pub fn execute(&mut self, action: OrchestratorManualAction) -> Result<ManualActionResult, OrchestratorError> {
+        let recording = !action.is_query() && self.checkpoint_if_recording()?;
+        let recorded = recording.then(|| action.clone());
+        let result = if let OrchestratorManualAction::AdvanceTurns { turns } = action {
+            self.advance_turns(turns)
+        } else {
+            OrchestratorUpdateFactory::get_strategy(self.mode, &mut self.state).process_command(action)
+        };
+        if let Some(action) = recorded
+            && result.is_ok()
+        {
+            self.history.record(HistoryAction::Action(action));
         }
-        Ok(())
+        result
+    }
+
+    /// Whether the next manual action or step goes in the history, taking a checkpoint if it is due.
+    fn checkpoint_if_recording(&mut self) -> Result<bool, OrchestratorError> {
+        if !matches!(self.mode, OrchestratorMode::Manual) || !self.history.is_enabled() || self.replay.is_some() {
+            return Ok(false);
+        }
+        if self.history.needs_checkpoint() {
+            let snapshot = self.snapshot()?;
+            self.history.checkpoint(snapshot);
+        }
+        Ok(true)
+    }
+
+    pub(super) fn history_mut(&mut self) -> &mut ManualHistory { &mut self.history }
+
+    /// Takes the place of a game rebuilt by an undo, keeping the settings and the history of this one. The
+    /// planets and explorers of this game are killed.
+    pub(super) fn replace_game(&mut self, mut rebuilt: Orchestrator) {
+        rebuilt.mode = self.mode;
+        rebuilt.end_condition = self.end_condition.clone();
+        rebuilt.started_at = self.started_at;
+        rebuilt.history = std::mem::take(&mut self.history);
+        let mut replaced = std::mem::replace(self, rebuilt);
+        for planet_id in replaced.state.galaxy.get_planets() {
+            replaced.state.handle_planet_destroyed(planet_id);
+        }
+    }
+
+    fn advance_turns(&mut self, turns: u32) -> Result<ManualActionResult, OrchestratorError> {
+        let mode = std::mem::replace(&mut self.mode, OrchestratorMode::Auto);
+        let mut played = 0;
+        let result = loop {
+            if played == turns || self.is_game_over() {
+                break Ok(ManualActionResult::TurnsAdvanced { turns: played });
+            }
+            if let Err(e) = self.manual_step() {
+                break Err(e);
+            }
+            played += 1;
+        };
+        self.mode = mode;
+        result
     }
 
     pub fn schedule_manual_action(&mut self, action: OrchestratorManualAction) { self.manual_commands.push(action); }
 
+    pub fn get_mode(&self) -> OrchestratorMode { self.mode }
+
+    pub fn set_mode(&mut self, mode: OrchestratorMode) { self.mode = mode; }
+
+    pub fn get_time(&self) -> u32 { self.state.time }
+
+    pub(super) fn set_time(&mut self, time: u32) {
+        self.state.time = time;
+        self.state.gui_events_buffer.set_time(time);
+    }
+
     #[allow(dead_code)] // implemented for future gui integrations
     pub fn set_mode_auto(&mut self) { self.mode = OrchestratorMode::Auto; }
 
@@ -165,7 +411,7 @@ impl Orchestrator {
         })
     }
 
-    fn send_planet_ai_start(&mut self) -> Result<(), String> {
+    fn send_planet_ai_start(&mut self) -> Result<(), OrchestratorError> {
         for planet_id in self.state.galaxy.get_planets() {
             self.state.planets_communication_center.req_ack(
                 planet_id,
@@ -176,12 +422,16 @@ impl Orchestrator {
         Ok(())
     }
 
-    fn notify_planet_explorer_channel(&mut self) -> Result<(), String> {
-        for (explorer_id, explorer_handle) in &self.state.explorers {
+    /// Each explorer is announced to its own starting planet, which can differ between explorers.
+    fn notify_planet_explorer_channel(&mut self) -> Result<(), OrchestratorError> {
+        let mut explorer_ids: Vec<ID> = self.state.explorers.keys().copied().collect();
+        explorer_ids.sort_unstable(); // deterministic order for seeded games
+        for explorer_id in explorer_ids {
+            let explorer_handle = &self.state.explorers[&explorer_id];
             let current_planet_id = explorer_handle.current_planet;
             let new_sender = explorer_handle.tx_planet.clone();
             self.state.planets_communication_center.notify_planet_incoming_explorer(
-                *explorer_id,
+                explorer_id,
                 current_planet_id,
                 new_sender
             )?;
@@ -189,10 +439,12 @@ impl Orchestrator {
         Ok(())
     }
 
-    fn send_explorer_ai_start(&mut self) -> Result<(), String> {
-        for explorer_id in self.state.explorers.keys() {
+    fn send_explorer_ai_start(&mut self) -> Result<(), OrchestratorError> {
+        let mut explorer_ids: Vec<ID> = self.state.explorers.keys().copied().collect();
+        explorer_ids.sort_unstable(); // deterministic order for seeded games
+        for explorer_id in explorer_ids {
             self.state.explorers_communication_center.req_ack(
-                *explorer_id,
+                explorer_id,
                 OrchestratorToExplorer::StartExplorerAI,
                 ExplorerToOrchestratorKind::StartExplorerAIResult
             )?;
@@ -201,7 +453,7 @@ impl Orchestrator {
     }
 
     /// Get the state of a planet by its ID (to be used only in non-concurrent contexts)
-    pub fn get_planet_state(&self, planet_id: ID) -> Option<Result<DummyPlanetState, String>> {
+    pub fn get_planet_state(&self, planet_id: ID) -> Option<Result<DummyPlanetState, OrchestratorError>> {
         if !self.state.planets.contains_key(&planet_id) {
             return None;
         }
@@ -220,6 +472,21 @@ impl Orchestrator {
 
     pub fn get_alive_planets(&self) -> Vec<ID> { self.state.galaxy.get_planets() }
 
+    /// All the planets of the game with their type, including the destroyed ones.
+    pub fn get_planet_types(&self) -> &HashMap<ID, PlanetType> { &self.state.planet_types }
+
+    /// All the explorers of the game sorted by ID, including the dead ones.
+    pub fn get_explorer_ids(&self) -> Vec<ID> {
+        let mut ids: Vec<ID> = self.state.explorer_types.keys().copied().collect();
+        ids.sort_unstable();
+        ids
+    }
+
+    /// The factory name of an explorer (e.g. "samufaz"), also for dead ones.
+    pub fn get_explorer_type(&self, explorer_id: ID) -> Option<&str> {
+        self.state.explorer_types.get(&explorer_id).map(String::as_str)
+    }
+
     pub fn get_explorer_bag(&self, explorer_id: ID) -> Option<&BagContent> {
         self.state.explorer_bags.get(&explorer_id)
     }
@@ -236,6 +503,7 @@ impl Default for Orchestrator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gui::{GameEvent, OrchestratorEvent, SupervisionEvent};
 
     #[test]
     fn test_empty_galaxy_create() {
@@ -248,4 +516,194 @@ mod tests {
         let orchestrator = Orchestrator::new(OrchestratorMode::Auto, 0, vec![]).unwrap();
         assert!(orchestrator.is_game_over());
     }
+
+    #[test]
+    fn test_end_conditions() {
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 3, vec![], 1).unwrap();
+        let turns = EndCondition::MaxTurns { turns: 1 };
+        let explorers_dead = EndCondition::AllExplorersDead;
+
+        orchestrator.set_end_condition(EndCondition::All { conditions: vec![turns.clone(), explorers_dead.clone()] });
+        assert_eq!(orchestrator.end_reasons(), None);
+        orchestrator.set_end_condition(EndCondition::Any { conditions: vec![turns, explorers_dead] });
+        assert_eq!(orchestrator.end_reasons(), Some(vec![EndReason::AllExplorersDead]));
+        orchestrator.set_end_condition(EndCondition::WallClock { seconds: 3600.0 });
+        assert!(!orchestrator.is_game_over());
+    }
+
+    #[test]
+    fn test_run_until_max_turns() {
+        let explorers = vec![ExplorerFactory::make_from_name(&"samufaz".to_string())];
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, 5, explorers, 4).unwrap();
+        orchestrator.set_end_condition(EndCondition::MaxTurns { turns: 2 });
+
+        let result = orchestrator.run().unwrap();
+        assert!(result.turns <= 2);
+        assert!(!result.reasons.is_empty());
+        assert_eq!(result.standings.len(), 1);
+        assert_eq!(result.standings[0].explorer_type, "samufaz");
+    }
+
+    #[test]
+    fn test_no_explorer_teleported() {
+        let explorers = (0..3).map(|_| ExplorerFactory::make_from_name(&"samufaz".to_string())).collect();
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, 8, explorers, 6).unwrap();
+        let edges: HashSet<(ID, ID)> = orchestrator.get_topology().into_iter().collect();
+        orchestrator.manual_init().unwrap();
+        for _ in 0..20 {
+            if orchestrator.is_game_over() {
+                break;
+            }
+            orchestrator.manual_step().unwrap();
+        }
+
+        let stats = orchestrator.stats();
+        assert_eq!(stats.turns().len(), orchestrator.get_time() as usize);
+        for step in stats.moves() {
+            assert!(
+                edges.contains(&(step.from, step.to)) || edges.contains(&(step.to, step.from)),
+                "Explorer {} teleported from planet {} to planet {}",
+                step.explorer_id,
+                step.from,
+                step.to
+            );
+        }
+    }
+
+    fn play_seeded_game(seed: u64, explorers: &[&str]) -> Vec<GameEvent> {
+        let explorers = explorers.iter().map(|name| ExplorerFactory::make_from_name(&(*name).to_string())).collect();
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, 7, explorers, seed).unwrap();
+        orchestrator.manual_init().unwrap();
+        for _ in 0..30 {
+            if orchestrator.is_game_over() {
+                break;
+            }
+            orchestrator.manual_step().unwrap();
+        }
+        orchestrator.get_gui_events_buffer().drain_game_events()
+    }
+
+    #[test]
+    fn test_unknown_planet_error() {
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 3, vec![], 1).unwrap();
+        orchestrator.manual_init().unwrap();
+        orchestrator.schedule_manual_action(OrchestratorManualAction::SendSunray { planet_id: 42 });
+        assert_eq!(orchestrator.process_commands().err(), Some(OrchestratorError::UnknownPlanet(42)));
+    }
+
+    #[test]
+    fn test_same_seed_same_game() {
+        let first = play_seeded_game(42, &[]);
+        let second = play_seeded_game(42, &[]);
+        assert!(!first.is_empty());
+        assert_eq!(first, second);
+    }
+
+    /// With a single explorer the turns are sequential, so its moves and resources depend only on the seed
+    #[test]
+    fn test_same_seed_same_explorer_decisions() {
+        for explorer in ["samufaz", "cetto"] {
+            let first = play_seeded_game(42, &[explorer]);
+            let second = play_seeded_game(42, &[explorer]);
+            let moved =
+                |event: &GameEvent| matches!(event, GameEvent::Orchestrator(OrchestratorEvent::ExplorerMoved { .. }));
+            assert!(first.iter().any(moved));
+            assert_eq!(first, second, "{explorer} played differently with the same seed");
+        }
+    }
+
+    /// Sunrays and asteroids are dispatched to all the planets at once, so a turn should take about as long
+    /// as the slowest planet instead of the sum of all of them.
+    #[test]
+    #[ignore = "benchmark, run with cargo test --release -- --ignored --nocapture bench_"]
+    fn bench_turn_with_many_planets() {
+        const PLANETS: u32 = 120;
+        const TURNS: u32 = 20;
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, PLANETS, vec![], 7).unwrap();
+        orchestrator.manual_init().unwrap();
+
+        let start = std::time::Instant::now();
+        for _ in 0..TURNS {
+            orchestrator.manual_step().unwrap();
+        }
+        let elapsed = start.elapsed();
+        println!("{PLANETS} planets: {:?} per turn ({TURNS} turns in {elapsed:?})", elapsed / TURNS);
+        assert_eq!(orchestrator.get_time(), TURNS);
+    }
+
+    #[test]
+    fn test_real_time_steps_last_a_tick() {
+        let explorers = vec![ExplorerFactory::make_from_name(&"samufaz".to_string())];
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::RealTime, 5, explorers, 2).unwrap();
+        orchestrator.manual_init().unwrap();
+
+        let start = std::time::Instant::now();
+        for _ in 0..3 {
+            orchestrator.manual_step().unwrap();
+        }
+        let tick = std::time::Duration::from_millis(AppConfig::get().real_time_tick_ms);
+        assert!(start.elapsed() >= 3 * tick);
+        assert_eq!(orchestrator.get_time(), 3);
+    }
+
+    #[test]
+    fn test_failed_planet_evacuates_explorers() {
+        let explorers = vec![ExplorerFactory::make_from_name(&"samufaz".to_string())];
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 3, explorers, 1).unwrap();
+        orchestrator.manual_init().unwrap();
+        let explorer_id = orchestrator.get_explorer_ids()[0];
+        let failed = orchestrator.get_explorer_current_planet(explorer_id).unwrap();
+
+        orchestrator.state.handle_planet_failure(failed, "stopped answering");
+
+        assert!(!orchestrator.get_alive_planets().contains(&failed));
+        let evacuated_to = orchestrator.get_explorer_current_planet(explorer_id).unwrap();
+        assert_ne!(evacuated_to, failed);
+        let events = orchestrator.get_gui_events_buffer().drain_game_events();
+        let expected: Vec<GameEvent> = vec![
+            SupervisionEvent::PlanetFailed { planet_id: failed, reason: "stopped answering".to_string() }.into(),
+            OrchestratorEvent::ExplorerMoved { explorer_id, destination: evacuated_to }.into(),
+            OrchestratorEvent::PlanetDestroyed { planet_id: failed }.into()
+        ];
+        assert_eq!(events, expected);
+    }
+
+    #[test]
+    fn test_manual_commands() {
+        let explorers = vec![ExplorerFactory::make_from_name(&"samufaz".to_string())];
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 3, explorers, 1).unwrap();
+        orchestrator.manual_init().unwrap();
+        let explorer_id = orchestrator.get_explorer_ids()[0];
+        let current = orchestrator.get_explorer_current_planet(explorer_id).unwrap();
+        let other = *orchestrator.get_alive_planets().iter().find(|&&id| id != current).unwrap();
+
+        let result = orchestrator.execute(OrchestratorManualAction::QueryExplorerPlanet { explorer_id });
+        assert!(matches!(result, Ok(ManualActionResult::ExplorerPlanet { planet_id }) if planet_id == current));
+        let result = orchestrator.execute(OrchestratorManualAction::QueryPlanetState { planet_id: current });
+        assert!(matches!(result, Ok(ManualActionResult::PlanetState { .. })));
+
+        // Without the edge the explorer cannot move, then it can again
+        let result = orchestrator.execute(OrchestratorManualAction::CutEdge { from: current, to: other });
+        assert!(matches!(result, Ok(ManualActionResult::Topology { changed: true })));
+        let move_there = || OrchestratorManualAction::MoveExplorer { explorer_id, destination_planet_id: other };
+        assert!(matches!(orchestrator.execute(move_there()), Err(OrchestratorError::InvalidMove { .. })));
+        let result = orchestrator.execute(OrchestratorManualAction::AddEdge { from: current, to: other });
+        assert!(matches!(result, Ok(ManualActionResult::Topology { changed: true })));
+        let result = orchestrator.execute(OrchestratorManualAction::AddEdge { from: other, to: current });
+        assert!(matches!(result, Ok(ManualActionResult::Topology { changed: false })));
+        assert!(orchestrator.execute(move_there()).is_ok());
+
+        orchestrator.set_end_condition(EndCondition::MaxTurns { turns: 100 });
+        let result = orchestrator.execute(OrchestratorManualAction::AdvanceTurns { turns: 2 });
+        assert!(matches!(result, Ok(ManualActionResult::TurnsAdvanced { turns: 2 })));
+        assert_eq!(orchestrator.get_time(), 2);
+        assert!(matches!(orchestrator.get_mode(), OrchestratorMode::Manual));
+
+        if orchestrator.get_explorer_current_planet(explorer_id).is_some() {
+            assert!(orchestrator.execute(OrchestratorManualAction::KillExplorer { explorer_id }).is_ok());
+            assert_eq!(orchestrator.get_explorer_current_planet(explorer_id), None);
+        }
+        let result = orchestrator.execute(OrchestratorManualAction::QueryExplorerBag { explorer_id });
+        assert_eq!(result.err(), Some(OrchestratorError::UnknownExplorer(explorer_id)));
+    }
 }
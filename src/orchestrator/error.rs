@@ -0,0 +1,113 @@
+use std::fmt::{Display, Formatter};
+
+use common_game::utils::ID;
+
+/// The kind of actor the orchestrator talks to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum Actor {
+    Planet,
+    Explorer
+}
+
+/// Everything that can go wrong while running a game. The variants carry the actor involved, so that
+/// the caller can decide how to recover (e.g. kill an unresponsive planet but stop on a bad config).
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) enum OrchestratorError {
+    /// No message arrived before the deadline, `id` is `None` when waiting for any actor
+    Timeout { actor: Actor, id: Option<ID> },
+    /// The actor answered with a message that the protocol does not allow here
+    UnexpectedMessage { actor: Actor, id: ID, expected: String, got: String },
+    UnknownPlanet(ID),
+    UnknownExplorer(ID),
+    /// The other side of the channel was dropped, usually the actor thread terminated
+    ChannelClosed { actor: Actor, id: Option<ID> },
+    /// The destination is not linked to the planet of the explorer
+    InvalidMove { explorer_id: ID, from: ID, to: ID },
+    /// The actor answered, but refused the request or did something different from it
+    Rejected { actor: Actor, id: ID, reason: String },
+    /// Invalid configuration, galaxy layout or saved game
+    Config(String),
+    /// Failure reading or writing a file (snapshots, recordings)
+    Io(String)
+}
+
+impl OrchestratorError {
+    pub fn unknown(actor: Actor, id: ID) -> Self {
+        match actor {
+            Actor::Planet => OrchestratorError::UnknownPlanet(id),
+            Actor::Explorer => OrchestratorError::UnknownExplorer(id)
+        }
+    }
+
+    /// The planet or explorer that caused the error, if any.
+    pub fn actor(&self) -> Option<(Actor, ID)> {
+        match *self {
+            OrchestratorError::Timeout { actor, id: Some(id) }
+            | OrchestratorError::ChannelClosed { actor, id: Some(id) }
+            | OrchestratorError::UnexpectedMessage { actor, id, .. }
+            | OrchestratorError::Rejected { actor, id, .. } => Some((actor, id)),
+            OrchestratorError::UnknownPlanet(id) => Some((Actor::Planet, id)),
+            OrchestratorError::UnknownExplorer(id) | OrchestratorError::InvalidMove { explorer_id: id, .. } =>
+                Some((Actor::Explorer, id)),
+            _ => None
+        }
+    }
+}
+
+impl Display for Actor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Actor::Planet => write!(f, "planet"),
+            Actor::Explorer => write!(f, "explorer")
+        }
+    }
+}
+
+impl Display for OrchestratorError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OrchestratorError::Timeout { actor, id: Some(id) } => write!(f, "Timeout waiting for {actor} {id}"),
+            OrchestratorError::Timeout { actor, id: None } => write!(f, "Timeout waiting for any {actor}"),
+            OrchestratorError::UnexpectedMessage { actor, id, expected, got } =>
+                write!(f, "Expected {actor} {id} to respond with {expected}, but got {got}"),
+            OrchestratorError::UnknownPlanet(id) => write!(f, "Planet with ID: {id} does not exist"),
+            OrchestratorError::UnknownExplorer(id) => write!(f, "Explorer with ID: {id} does not exist"),
+            OrchestratorError::ChannelClosed { actor, id: Some(id) } =>
+                write!(f, "Channel with {actor} {id} is closed"),
+            OrchestratorError::ChannelClosed { actor, id: None } => write!(f, "Channel from the {actor}s is closed"),
+            OrchestratorError::InvalidMove { explorer_id, from, to } => write!(
+                f,
+                "Explorer {explorer_id} cannot move from planet {from} to planet {to}, they are not directly linked"
+            ),
+            OrchestratorError::Rejected { actor, id, reason } => write!(f, "Request to {actor} {id} failed: {reason}"),
+            OrchestratorError::Config(reason) => write!(f, "Invalid configuration: {reason}"),
+            OrchestratorError::Io(reason) => write!(f, "{reason}")
+        }
+    }
+}
+
+impl std::error::Error for OrchestratorError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            OrchestratorError::Timeout { actor: Actor::Planet, id: Some(3) }.to_string(),
+            "Timeout waiting for planet 3"
+        );
+        assert_eq!(
+            OrchestratorError::InvalidMove { explorer_id: 8, from: 1, to: 2 }.to_string(),
+            "Explorer 8 cannot move from planet 1 to planet 2, they are not directly linked"
+        );
+    }
+
+    #[test]
+    fn test_actor() {
+        assert_eq!(OrchestratorError::unknown(Actor::Explorer, 4).actor(), Some((Actor::Explorer, 4)));
+        assert_eq!(OrchestratorError::Timeout { actor: Actor::Planet, id: None }.actor(), None);
+        assert_eq!(OrchestratorError::Config("bad".to_string()).actor(), None);
+    }
+}
@@ -1,13 +1,52 @@
-use std::collections::{HashMap, HashSet};
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet, VecDeque};
 
 use common_game::utils::ID;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use serde::Deserialize;
 
 /// Galaxy topology container, only manages the connections between planets.
 pub(crate) struct Galaxy {
     connections: HashMap<ID, HashSet<ID>>
 }
 
+/// The shapes a galaxy can be built with, selectable from the config with e.g.
+/// `topology = { type = "small_world", neighbours = 4, rewire_probability = 0.2 }`.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum GalaxyTopology {
+    FullyConnected,
+    Circular,
+    /// Erdős–Rényi graph over a random spanning tree, so it's always connected
+    Random { edge_probability: f64 },
+    /// Rectangular lattice, square if the number of columns is not given
+    Grid {
+        #[serde(default)]
+        columns: Option<usize>
+    },
+    /// The first planet is connected to all the others
+    Star,
+    /// Watts–Strogatz ring where only the non-adjacent links are rewired, so it stays connected
+    SmallWorld { neighbours: usize, rewire_probability: f64 },
+    /// Fully connected clusters ("galaxy arms") joined in a ring by a single link
+    Clustered { clusters: usize }
+}
+
 impl Galaxy {
+    pub fn make(topology: GalaxyTopology, ids: &[ID], rng: &mut impl Rng) -> Result<Self, String> {
+        match topology {
+            GalaxyTopology::FullyConnected => Self::make_fully_connected(ids),
+            GalaxyTopology::Circular => Self::make_circular(ids),
+            GalaxyTopology::Random { edge_probability } => Self::make_random(ids, edge_probability, rng),
+            GalaxyTopology::Grid { columns } => Self::make_grid(ids, columns),
+            GalaxyTopology::Star => Self::make_star(ids),
+            GalaxyTopology::SmallWorld { neighbours, rewire_probability } =>
+                Self::make_small_world(ids, neighbours, rewire_probability, rng),
+            GalaxyTopology::Clustered { clusters } => Self::make_clustered(ids, clusters, rng)
+        }
+    }
+
     pub fn make_fully_connected(ids: &[ID]) -> Result<Self, String> {
         let mut connections: HashMap<ID, HashSet<ID>> = HashMap::new();
 
@@ -46,7 +85,223 @@ impl Galaxy {
         Ok(Galaxy { connections })
     }
 
-    pub fn get_planets(&self) -> Vec<ID> { self.connections.keys().copied().collect() }
+    pub fn make_random(ids: &[ID], edge_probability: f64, rng: &mut impl Rng) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&edge_probability) {
+            return Err(format!("Edge probability must be between 0 and 1, got {edge_probability}"));
+        }
+        let mut galaxy = Galaxy::make_disconnected(ids)?;
+
+        // Random spanning tree: every planet links to one of the planets placed before it
+        let mut shuffled = ids.to_vec();
+        shuffled.shuffle(rng);
+        for i in 1..shuffled.len() {
+            let parent = shuffled[rng.random_range(0..i)];
+            galaxy.add_link(shuffled[i], parent);
+        }
+
+        for (i, &a) in ids.iter().enumerate() {
+            for &b in &ids[i + 1..] {
+                if rng.random_bool(edge_probability) {
+                    galaxy.add_link(a, b);
+                }
+            }
+        }
+        Ok(galaxy)
+    }
+
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss, clippy::cast_precision_loss)] // small numbers
+    pub fn make_grid(ids: &[ID], columns: Option<usize>) -> Result<Self, String> {
+        let columns = columns.unwrap_or_else(|| (ids.len() as f64).sqrt().ceil() as usize).max(1);
+        let mut galaxy = Galaxy::make_disconnected(ids)?;
+
+        // Planets are placed row by row, each one links to the right and bottom ones
+        for i in 0..ids.len() {
+            if (i + 1) % columns != 0 && i + 1 < ids.len() {
+                galaxy.add_link(ids[i], ids[i + 1]);
+            }
+            if i + columns < ids.len() {
+                galaxy.add_link(ids[i], ids[i + columns]);
+            }
+        }
+        Ok(galaxy)
+    }
+
+    pub fn make_star(ids: &[ID]) -> Result<Self, String> {
+        let mut galaxy = Galaxy::make_disconnected(ids)?;
+        if let Some((&center, others)) = ids.split_first() {
+            for &id in others {
+                galaxy.add_link(center, id);
+            }
+        }
+        Ok(galaxy)
+    }
+
+    pub fn make_small_world(
+        ids: &[ID],
+        neighbours: usize,
+        rewire_probability: f64,
+        rng: &mut impl Rng
+    ) -> Result<Self, String> {
+        if !(0.0..=1.0).contains(&rewire_probability) {
+            return Err(format!("Rewire probability must be between 0 and 1, got {rewire_probability}"));
+        }
+        let mut galaxy = Galaxy::make_circular(ids)?;
+        let n = ids.len();
+
+        // Ring lattice, every planet links to the `neighbours / 2` closest planets on each side
+        for distance in 2..=(neighbours / 2).min(n.saturating_sub(1) / 2) {
+            for i in 0..n {
+                let (a, b) = (ids[i], ids[(i + distance) % n]);
+                if !rng.random_bool(rewire_probability) {
+                    galaxy.add_link(a, b);
+                    continue;
+                }
+                // Rewire to a random planet that is not already linked
+                let candidates: Vec<ID> =
+                    ids.iter().copied().filter(|&c| c != a && !galaxy.are_planets_connected(a, c)).collect();
+                if candidates.is_empty() {
+                    galaxy.add_link(a, b);
+                } else {
+                    galaxy.add_link(a, candidates[rng.random_range(0..candidates.len())]);
+                }
+            }
+        }
+        Ok(galaxy)
+    }
+
+    pub fn make_clustered(ids: &[ID], clusters: usize, rng: &mut impl Rng) -> Result<Self, String> {
+        if clusters == 0 {
+            return Err("Cannot build a clustered galaxy with zero clusters".to_string());
+        }
+        let mut galaxy = Galaxy::make_disconnected(ids)?;
+        let cluster_size = ids.len().div_ceil(clusters).max(1);
+        let arms: Vec<&[ID]> = ids.chunks(cluster_size).collect();
+
+        for arm in &arms {
+            for (i, &a) in arm.iter().enumerate() {
+                for &b in &arm[i + 1..] {
+                    galaxy.add_link(a, b);
+                }
+            }
+        }
+
+        // Bridge each arm to the next one, closing the ring when there are more than two
+        let n_bridges = if arms.len() > 2 { arms.len() } else { arms.len().saturating_sub(1) };
+        for i in 0..n_bridges {
+            let (from, to) = (arms[i], arms[(i + 1) % arms.len()]);
+            galaxy.add_link(from[rng.random_range(0..from.len())], to[rng.random_range(0..to.len())]);
+        }
+        Ok(galaxy)
+    }
+
+    pub fn make_from_edges(ids: &[ID], edges: &[(ID, ID)]) -> Result<Self, String> {
+        let mut galaxy = Galaxy::make_disconnected(ids)?;
+        for &(a, b) in edges {
+            if !galaxy.connections.contains_key(&a) || !galaxy.connections.contains_key(&b) {
+                return Err(format!("Edge ({a}, {b}) references a planet that does not exist"));
+            }
+            galaxy.add_link(a, b);
+        }
+        Ok(galaxy)
+    }
+
+    fn make_disconnected(ids: &[ID]) -> Result<Self, String> {
+        let connections: HashMap<ID, HashSet<ID>> = ids.iter().map(|&id| (id, HashSet::new())).collect();
+        if connections.len() != ids.len() {
+            return Err("Duplicate planet IDs found".to_string());
+        }
+        Ok(Galaxy { connections })
+    }
+
+    /// Links two planets, returns false if they were already linked.
+    pub fn add_link(&mut self, a: ID, b: ID) -> bool {
+        if a == b {
+            return false;
+        }
+        self.connections.entry(a).or_default().insert(b);
+        self.connections.entry(b).or_default().insert(a)
+    }
+
+    /// Returns false if the planets were not linked.
+    pub fn remove_link(&mut self, a: ID, b: ID) -> bool {
+        let removed = self.connections.get_mut(&a).is_some_and(|neighbours| neighbours.remove(&b));
+        if let Some(neighbours) = self.connections.get_mut(&b) {
+            neighbours.remove(&a);
+        }
+        removed
+    }
+
+    /// True if every planet can be reached from every other one.
+    #[allow(dead_code)] // used in tests
+    pub fn is_connected(&self) -> bool {
+        let Some(&start) = self.connections.keys().next() else {
+            return true;
+        };
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(id) = queue.pop_front() {
+            for &neighbour in &self.connections[&id] {
+                if visited.insert(neighbour) {
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        visited.len() == self.connections.len()
+    }
+
+    /// Number of hops from `from` to every planet reachable from it.
+    pub fn get_distances(&self, from: ID) -> HashMap<ID, usize> {
+        let mut distances = HashMap::new();
+        if !self.connections.contains_key(&from) {
+            return distances;
+        }
+        distances.insert(from, 0);
+        let mut queue = VecDeque::from([from]);
+        while let Some(id) = queue.pop_front() {
+            let distance = distances[&id] + 1;
+            for &neighbour in &self.connections[&id] {
+                if !distances.contains_key(&neighbour) {
+                    distances.insert(neighbour, distance);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        distances
+    }
+
+    /// Picks `n` planets starting from `first`, each one as far as possible from the ones already picked
+    /// (farthest-point heuristic). Unreachable planets count as infinitely far, ties go to the lowest ID.
+    pub fn get_spread_planets(&self, n: usize, first: ID) -> Vec<ID> {
+        let planets = self.get_planets();
+        if planets.is_empty() || n == 0 {
+            return vec![];
+        }
+        let first = if self.connections.contains_key(&first) { first } else { planets[0] };
+
+        let mut picked = vec![first];
+        // Distance of every planet from the closest picked one
+        let mut min_distances = self.get_distances(first);
+        while picked.len() < n {
+            let next = planets
+                .iter()
+                .copied()
+                .max_by_key(|id| (min_distances.get(id).copied().unwrap_or(usize::MAX), Reverse(*id)))
+                .unwrap(); // planets is not empty
+            picked.push(next);
+            for (id, distance) in self.get_distances(next) {
+                let min_distance = min_distances.entry(id).or_insert(usize::MAX);
+                *min_distance = (*min_distance).min(distance);
+            }
+        }
+        picked
+    }
+
+    /// Returns the alive planets sorted by ID, so that iterating over them is deterministic.
+    pub fn get_planets(&self) -> Vec<ID> {
+        let mut planets: Vec<ID> = self.connections.keys().copied().collect();
+        planets.sort_unstable();
+        planets
+    }
 
     pub fn are_planets_connected(&self, a: ID, b: ID) -> bool {
         if let Some(neighbors) = self.connections.get(&a) { neighbors.contains(&b) } else { false }
@@ -59,12 +314,11 @@ impl Galaxy {
         }
     }
 
+    /// Sorted by ID, like `get_planets`.
     pub fn get_planet_neighbours(&self, planet_id: ID) -> Vec<ID> {
-        if let Some(neighbors) = self.connections.get(&planet_id) {
-            neighbors.iter().copied().collect()
-        } else {
-            vec![]
-        }
+        let mut neighbors: Vec<ID> = self.connections.get(&planet_id).into_iter().flatten().copied().collect();
+        neighbors.sort_unstable();
+        neighbors
     }
 
     pub fn get_topology(&self) -> Vec<(ID, ID)> {
@@ -79,6 +333,9 @@ impl Galaxy {
 #[allow(clippy::wildcard_imports)] // It's just tests
 #[cfg(test)]
 mod test {
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
     use super::*;
 
     #[allow(dead_code)] // used in some tests
@@ -139,4 +396,112 @@ mod test {
             assert!(!galaxy.are_planets_connected(planet, planet_to_remove));
         }
     }
+
+    fn get_many_ids() -> Vec<ID> { (1..=30).collect() }
+
+    fn degrees(galaxy: &Galaxy) -> Vec<usize> {
+        galaxy.get_planets().iter().map(|&id| galaxy.get_planet_neighbours(id).len()).collect()
+    }
+
+    #[test]
+    fn test_random_is_connected() {
+        let mut rng = StdRng::seed_from_u64(1);
+        for p in [0.0, 0.05, 0.5] {
+            let galaxy = Galaxy::make_random(&get_many_ids(), p, &mut rng).unwrap();
+            assert!(galaxy.is_connected());
+        }
+    }
+
+    #[test]
+    fn test_random_spanning_tree_only() {
+        // With no extra edges the galaxy is a tree: n - 1 links
+        let galaxy = Galaxy::make_random(&get_many_ids(), 0.0, &mut StdRng::seed_from_u64(2)).unwrap();
+        assert_eq!(galaxy.get_topology().len(), get_many_ids().len() - 1);
+    }
+
+    #[test]
+    fn test_random_invalid_probability() {
+        assert!(Galaxy::make_random(&get_dummy_ids(), 1.5, &mut StdRng::seed_from_u64(0)).is_err());
+    }
+
+    #[test]
+    fn test_grid_degrees() {
+        // 3x3 grid: corners have 2 links, sides 3 and the center 4
+        let galaxy = Galaxy::make_grid(&(1..=9).collect::<Vec<ID>>(), None).unwrap();
+        assert!(galaxy.is_connected());
+        assert_eq!(galaxy.get_planet_neighbours(1).len(), 2);
+        assert_eq!(galaxy.get_planet_neighbours(2).len(), 3);
+        assert_eq!(galaxy.get_planet_neighbours(5).len(), 4);
+        assert!(galaxy.are_planets_connected(3, 6));
+        assert!(!galaxy.are_planets_connected(3, 4));
+    }
+
+    #[test]
+    fn test_star_degrees() {
+        let galaxy = Galaxy::make_star(&get_dummy_ids()).unwrap();
+        assert!(galaxy.is_connected());
+        assert_eq!(galaxy.get_planet_neighbours(1).len(), 4);
+        for planet in 2..=5 {
+            assert_eq!(galaxy.get_planet_neighbours(planet), vec![1]);
+        }
+    }
+
+    #[test]
+    fn test_small_world_without_rewiring_is_regular() {
+        let galaxy = Galaxy::make_small_world(&get_many_ids(), 4, 0.0, &mut StdRng::seed_from_u64(3)).unwrap();
+        assert!(galaxy.is_connected());
+        assert!(degrees(&galaxy).iter().all(|&d| d == 4));
+    }
+
+    #[test]
+    fn test_small_world_rewired_keeps_edges() {
+        let galaxy = Galaxy::make_small_world(&get_many_ids(), 6, 0.3, &mut StdRng::seed_from_u64(4)).unwrap();
+        assert!(galaxy.is_connected());
+        // Rewiring can only merge links, never add new ones
+        assert!(galaxy.get_topology().len() > get_many_ids().len());
+        assert!(galaxy.get_topology().len() <= get_many_ids().len() * 3);
+    }
+
+    #[test]
+    fn test_clustered() {
+        let galaxy = Galaxy::make_clustered(&get_many_ids(), 3, &mut StdRng::seed_from_u64(5)).unwrap();
+        assert!(galaxy.is_connected());
+        // Every planet is linked at least to the other 9 of its cluster
+        assert!(degrees(&galaxy).iter().all(|&d| d >= 9));
+        assert!(Galaxy::make_clustered(&get_many_ids(), 0, &mut StdRng::seed_from_u64(5)).is_err());
+    }
+
+    #[test]
+    fn test_from_edges() {
+        let galaxy = Galaxy::make_from_edges(&get_dummy_ids(), &[(1, 2), (2, 3), (5, 4)]).unwrap();
+        assert!(galaxy.are_planets_connected(4, 5));
+        assert!(!galaxy.are_planets_connected(1, 3));
+        assert!(Galaxy::make_from_edges(&get_dummy_ids(), &[(1, 6)]).is_err());
+    }
+
+    #[test]
+    fn test_distances() {
+        let galaxy = Galaxy::make_from_edges(&get_dummy_ids(), &[(1, 2), (2, 3), (3, 4)]).unwrap();
+        let distances = galaxy.get_distances(1);
+        assert_eq!(distances[&4], 3);
+        assert!(!distances.contains_key(&5));
+    }
+
+    #[test]
+    fn test_spread_planets() {
+        let line = Galaxy::make_from_edges(&get_dummy_ids(), &[(1, 2), (2, 3), (3, 4), (4, 5)]).unwrap();
+        assert_eq!(line.get_spread_planets(3, 1), vec![1, 5, 3]);
+
+        let ring = Galaxy::make_circular(&(1..=6).collect::<Vec<ID>>()).unwrap();
+        assert_eq!(ring.get_spread_planets(2, 1), vec![1, 4]);
+
+        // More explorers than planets: planets are reused
+        assert_eq!(ring.get_spread_planets(8, 1).len(), 8);
+    }
+
+    #[test]
+    fn test_disconnected_detection() {
+        let galaxy = Galaxy::make_disconnected(&get_dummy_ids()).unwrap();
+        assert!(!galaxy.is_connected());
+    }
 }
@@ -0,0 +1,191 @@
+//! When a game ends, selectable from the config with e.g. `end_condition = { type = "any", conditions =
+//! [{ type = "max_turns", turns = 500 }, { type = "all_explorers_dead" }] }`. A game always ends when every
+//! planet is destroyed, since nothing can happen anymore.
+
+use std::collections::BTreeMap;
+use std::fmt::{Display, Formatter};
+use std::time::Duration;
+
+use common_game::utils::ID;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppConfig;
+use crate::orchestrator::{OrchestratorState, resource_from_name, resource_name};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum EndCondition {
+    AllPlanetsDestroyed,
+    /// After this many time steps
+    MaxTurns { turns: u32 },
+    /// Every explorer of the game is dead (also true for a game without explorers)
+    AllExplorersDead,
+    /// An explorer holds at least these resources, by name (e.g. `bag = { AIPartner = 3 }`)
+    TargetBag { bag: BTreeMap<String, usize> },
+    /// Wall-clock time since the game started
+    WallClock { seconds: f64 },
+    /// At least one of the conditions is met
+    Any { conditions: Vec<EndCondition> },
+    /// All the conditions are met
+    All { conditions: Vec<EndCondition> }
+}
+
+/// Why a game ended, one for every condition met.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum EndReason {
+    AllPlanetsDestroyed,
+    MaxTurns { turns: u32 },
+    AllExplorersDead,
+    TargetBag { explorer_id: ID },
+    WallClock { seconds: f64 },
+    /// The recorded game shown with `--replay` is over
+    ReplayOver
+}
+
+/// The outcome of a game, returned by `Orchestrator::run`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct GameResult {
+    pub reasons: Vec<EndReason>,
+    pub turns: u32,
+    /// Best score first
+    pub standings: Vec<Standing>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub(crate) struct Standing {
+    pub explorer_id: ID,
+    pub explorer_type: String,
+    pub died: bool, // killed while other planets were still alive
+    pub score: f64, // see `ScoringModel`
+    pub bag: BTreeMap<String, usize>
+}
+
+impl EndCondition {
+    /// Checks that the target bags name known resources.
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            EndCondition::TargetBag { bag } => match bag.keys().find(|name| resource_from_name(name).is_none()) {
+                Some(name) => Err(format!("Unknown resource '{name}' in the target bag end condition")),
+                None => Ok(())
+            },
+            EndCondition::Any { conditions } | EndCondition::All { conditions } =>
+                conditions.iter().try_for_each(EndCondition::validate),
+            _ => Ok(())
+        }
+    }
+
+    /// The reasons to end the game now, `None` if the condition is not met.
+    pub fn evaluate(&self, state: &OrchestratorState, elapsed: Duration) -> Option<Vec<EndReason>> {
+        match self {
+            EndCondition::AllPlanetsDestroyed =>
+                state.galaxy.get_planets().is_empty().then(|| vec![EndReason::AllPlanetsDestroyed]),
+            EndCondition::MaxTurns { turns } =>
+                (state.time >= *turns).then(|| vec![EndReason::MaxTurns { turns: *turns }]),
+            EndCondition::AllExplorersDead => state.explorers.is_empty().then(|| vec![EndReason::AllExplorersDead]),
+            EndCondition::TargetBag { bag } =>
+                state.explorer_reaching(bag).map(|explorer_id| vec![EndReason::TargetBag { explorer_id }]),
+            EndCondition::WallClock { seconds } =>
+                (elapsed.as_secs_f64() >= *seconds).then(|| vec![EndReason::WallClock { seconds: *seconds }]),
+            EndCondition::Any { conditions } => {
+                let reasons: Vec<EndReason> =
+                    conditions.iter().filter_map(|condition| condition.evaluate(state, elapsed)).flatten().collect();
+                (!reasons.is_empty()).then_some(reasons)
+            }
+            EndCondition::All { conditions } => conditions
+                .iter()
+                .map(|condition| condition.evaluate(state, elapsed))
+                .collect::<Option<Vec<Vec<EndReason>>>>()
+                .map(|reasons| reasons.into_iter().flatten().collect())
+        }
+    }
+}
+
+impl OrchestratorState {
+    /// The first explorer, by ID, holding at least the resources of the target bag.
+    fn explorer_reaching(&self, target: &BTreeMap<String, usize>) -> Option<ID> {
+        let mut explorers: Vec<ID> = self.explorer_bags.keys().copied().collect();
+        explorers.sort_unstable();
+        explorers.into_iter().find(|explorer_id| {
+            let bag = &self.explorer_bags[explorer_id];
+            target.iter().all(|(name, &quantity)| {
+                resource_from_name(name)
+                    .is_some_and(|resource| bag.content.get(&resource).copied().unwrap_or(0) >= quantity)
+            })
+        })
+    }
+
+    /// The explorers sorted by score, best first, ties broken by ID.
+    pub fn standings(&self) -> Vec<Standing> {
+        let scoring = &AppConfig::get().scoring;
+        let mut standings: Vec<Standing> = self
+            .explorer_types
+            .iter()
+            .map(|(&explorer_id, explorer_type)| {
+                let died = self.dead_explorers.contains(&explorer_id);
+                let bag = self.explorer_bags.get(&explorer_id).cloned().unwrap_or_default();
+                Standing {
+                    explorer_id,
+                    explorer_type: explorer_type.clone(),
+                    died,
+                    score: scoring.score(&bag, died),
+                    bag: bag
+                        .content
+                        .into_iter()
+                        .filter(|(_, qty)| *qty > 0)
+                        .map(|(resource, qty)| (resource_name(resource), qty))
+                        .collect()
+                }
+            })
+            .collect();
+        standings.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.explorer_id.cmp(&b.explorer_id)));
+        standings
+    }
+}
+
+impl Display for EndReason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            EndReason::AllPlanetsDestroyed => write!(f, "all the planets were destroyed"),
+            EndReason::MaxTurns { turns } => write!(f, "{turns} turns were played"),
+            EndReason::AllExplorersDead => write!(f, "all the explorers are dead"),
+            EndReason::TargetBag { explorer_id } => write!(f, "explorer {explorer_id} reached the target bag"),
+            EndReason::WallClock { seconds } => write!(f, "the game lasted {seconds} seconds"),
+            EndReason::ReplayOver => write!(f, "the replay is over")
+        }
+    }
+}
+
+impl Display for GameResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let reasons: Vec<String> = self.reasons.iter().map(ToString::to_string).collect();
+        writeln!(f, "Game over after {} turns: {}", self.turns, reasons.join(", "))?;
+        for (rank, standing) in self.standings.iter().enumerate() {
+            let status = if standing.died { ", died" } else { "" };
+            writeln!(
+                f,
+                "{:>3}. explorer {} ({}): {:.1} points{status}",
+                rank + 1,
+                standing.explorer_id,
+                standing.explorer_type,
+                standing.score
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unknown_target_resource() {
+        let condition = EndCondition::Any {
+            conditions: vec![EndCondition::TargetBag { bag: BTreeMap::from([("Unobtainium".to_string(), 1)]) }]
+        };
+        assert!(condition.validate().is_err());
+        let condition = EndCondition::TargetBag { bag: BTreeMap::from([("AIPartner".to_string(), 2)]) };
+        assert!(condition.validate().is_ok());
+    }
+}
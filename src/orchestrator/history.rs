@@ -0,0 +1,182 @@
+//! Undo and redo of manual actions. The history is a list of checkpoints, each one a snapshot of the game
+//! followed by the manual actions and steps played after it: undoing rebuilds the game from the last
+//! checkpoint and plays its actions again, except the undone one. Like resuming a snapshot this is best
+//! effort, the explorers AI and the turns played in auto mode may go differently and the statistics start
+//! over from the checkpoint.
+
+use std::collections::VecDeque;
+
+use crate::orchestrator::{GameSnapshot, Orchestrator, OrchestratorError, OrchestratorManualAction, OrchestratorMode};
+
+/// Actions recorded after a checkpoint before taking a new one: fewer snapshots, but longer undos
+const CHECKPOINT_INTERVAL: usize = 10;
+
+#[derive(Debug, Clone)]
+pub(crate) enum HistoryAction {
+    Action(OrchestratorManualAction),
+    /// A time step played in manual mode
+    Step
+}
+
+#[derive(Debug)]
+struct Checkpoint {
+    snapshot: GameSnapshot,
+    actions: Vec<HistoryAction>
+}
+
+/// The undoable actions of a manual game, about `capacity` of them (the oldest are dropped a checkpoint at a
+/// time). A zero capacity disables the history.
+#[derive(Debug, Default)]
+pub(crate) struct ManualHistory {
+    capacity: usize,
+    checkpoints: VecDeque<Checkpoint>,
+    undone: Vec<HistoryAction> // last undone first out
+}
+
+impl ManualHistory {
+    pub fn new(capacity: usize) -> Self { Self { capacity, ..Self::default() } }
+
+    pub fn is_enabled(&self) -> bool { self.capacity > 0 }
+
+    pub fn needs_checkpoint(&self) -> bool {
+        self.checkpoints.back().is_none_or(|checkpoint| checkpoint.actions.len() >= CHECKPOINT_INTERVAL)
+    }
+
+    pub fn checkpoint(&mut self, snapshot: GameSnapshot) {
+        self.checkpoints.push_back(Checkpoint { snapshot, actions: vec![] });
+    }
+
+    /// Records an action played after the last checkpoint, the undone actions cannot be redone anymore.
+    pub fn record(&mut self, action: HistoryAction) {
+        self.undone.clear();
+        self.push(action);
+    }
+
+    pub fn len(&self) -> usize { self.checkpoints.iter().map(|checkpoint| checkpoint.actions.len()).sum() }
+
+    fn push(&mut self, action: HistoryAction) {
+        let Some(checkpoint) = self.checkpoints.back_mut() else {
+            return;
+        };
+        checkpoint.actions.push(action);
+        while self.len() > self.capacity && self.checkpoints.len() > 1 {
+            self.checkpoints.pop_front();
+        }
+    }
+
+    /// Removes the last action, the checkpoints left without actions are dropped too.
+    fn pop(&mut self) -> Option<HistoryAction> {
+        loop {
+            let checkpoint = self.checkpoints.back_mut()?;
+            if let Some(action) = checkpoint.actions.pop() {
+                self.undone.push(action.clone());
+                return Some(action);
+            }
+            self.checkpoints.pop_back();
+        }
+    }
+}
+
+impl Orchestrator {
+    /// Takes back the last manual action or step, returns `None` if there is nothing to undo.
+    pub fn undo(&mut self) -> Result<Option<HistoryAction>, OrchestratorError> {
+        let Some(action) = self.history_mut().pop() else {
+            return Ok(None);
+        };
+        self.rebuild()?;
+        Ok(Some(action))
+    }
+
+    /// Plays again the last undone action, returns `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Result<Option<HistoryAction>, OrchestratorError> {
+        let Some(action) = self.history_mut().undone.pop() else {
+            return Ok(None);
+        };
+        // Recording the action would forget the other undone ones
+        let undone = std::mem::take(&mut self.history_mut().undone);
+        let result = self.play(action.clone());
+        self.history_mut().undone = undone;
+        result.map(|()| Some(action))
+    }
+
+    fn play(&mut self, action: HistoryAction) -> Result<(), OrchestratorError> {
+        match action {
+            HistoryAction::Action(action) => self.execute(action).map(|_| ()),
+            HistoryAction::Step => self.manual_step()
+        }
+    }
+
+    fn rebuild(&mut self) -> Result<(), OrchestratorError> {
+        let Some(checkpoint) = self.history_mut().checkpoints.back() else {
+            return Ok(());
+        };
+        let actions = checkpoint.actions.clone();
+        let mut rebuilt = Orchestrator::from_snapshot(OrchestratorMode::Manual, &checkpoint.snapshot)?;
+        *rebuilt.history_mut() = ManualHistory::default();
+        for action in actions {
+            let description = format!("{action:?}");
+            if let Err(e) = rebuilt.play(action) {
+                log::warn!("Replayed action {description} failed: {e}");
+            }
+        }
+        self.replace_game(rebuilt);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn empty_snapshot() -> GameSnapshot { GameSnapshot { time: 0, planets: vec![], edges: vec![], explorers: vec![] } }
+
+    #[test]
+    fn test_history_bounds() {
+        let mut history = ManualHistory::new(15);
+        for _ in 0..40 {
+            if history.needs_checkpoint() {
+                history.checkpoint(empty_snapshot());
+            }
+            history.record(HistoryAction::Step);
+        }
+        // Two checkpoints of 10 actions are too many, the oldest is dropped
+        assert_eq!(history.len(), 10);
+        assert_eq!(history.checkpoints.len(), 1);
+
+        for _ in 0..10 {
+            assert!(history.pop().is_some());
+        }
+        assert!(history.pop().is_none());
+        assert_eq!(history.undone.len(), 10);
+        history.record(HistoryAction::Step);
+        assert!(history.undone.is_empty());
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 4, vec![], 21).unwrap();
+        orchestrator.manual_init().unwrap();
+        *orchestrator.history_mut() = ManualHistory::new(50);
+        let planet_id = orchestrator.get_alive_planets()[0];
+
+        orchestrator.execute(OrchestratorManualAction::SendSunray { planet_id }).unwrap();
+        orchestrator.manual_step().unwrap();
+        let edges = orchestrator.get_topology().len();
+        orchestrator.execute(OrchestratorManualAction::SendAsteroid { planet_id }).unwrap();
+        let survived = orchestrator.get_alive_planets().contains(&planet_id);
+
+        let undone = orchestrator.undo().unwrap();
+        assert!(matches!(undone, Some(HistoryAction::Action(OrchestratorManualAction::SendAsteroid { .. }))));
+        assert!(orchestrator.get_alive_planets().contains(&planet_id));
+        assert_eq!(orchestrator.get_topology().len(), edges);
+        assert_eq!(orchestrator.get_time(), 1);
+
+        assert!(matches!(orchestrator.undo(), Ok(Some(HistoryAction::Step))));
+        assert_eq!(orchestrator.get_time(), 0);
+
+        assert!(matches!(orchestrator.redo(), Ok(Some(HistoryAction::Step))));
+        assert!(orchestrator.redo().unwrap().is_some());
+        assert_eq!(orchestrator.get_alive_planets().contains(&planet_id), survived);
+        assert!(orchestrator.redo().unwrap().is_none());
+    }
+}
@@ -1,4 +1,4 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 
 use common_game::components::planet::Planet;
 use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestrator, OrchestratorToExplorer};
@@ -6,18 +6,28 @@ use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetTo
 use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
 use common_game::utils::ID;
 use crossbeam_channel::{Receiver, Sender, unbounded};
+use rand::distr::Distribution;
+use rand::distr::weighted::WeightedIndex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Deserialize;
 
 use crate::app::AppConfig;
-use crate::explorers::{BagContent, ExplorerBuilder};
-use crate::orchestrator::{Galaxy, PlanetFactory, PlanetType};
+use crate::explorers::{BagContent, ExplorerBuilder, ExplorerFactory};
+use crate::orchestrator::{Galaxy, GalaxyLayout, GalaxyTopology, PlanetFactory, PlanetOptions, PlanetType,
+                          resource_from_name};
 
 /// This struct creates and initializes all the galaxy entities, with the help of the corresponding
 /// factories/builders.
 pub(crate) struct GalaxyBuilder {
-    fully_connected: bool,
-    circular: bool,
+    topologies: Vec<GalaxyTopology>, // only one is allowed, kept as a list to report conflicts
     n_planets: u32,
     explorers: Vec<Box<dyn ExplorerBuilder>>,
+    layout: Option<GalaxyLayout>,
+    explorer_placement: ExplorerPlacement,
+    planet_mix: PlanetMix,
+    planet_options: PlanetOptions,
+    rng: StdRng,
     explorer_to_orchestrator:
         (Sender<ExplorerToOrchestrator<BagContent>>, Receiver<ExplorerToOrchestrator<BagContent>>),
     planet_to_orchestrator: (Sender<PlanetToOrchestrator>, Receiver<PlanetToOrchestrator>)
@@ -33,6 +43,34 @@ pub const PLANET_ORDER: [PlanetType; 7] = [
     PlanetType::RustEze
 ];
 
+/// Where the explorers start the game, selectable from the config with e.g. `{ type = "spread" }`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum ExplorerPlacement {
+    /// Everyone starts on the configured `initial_planet_id`
+    Fixed,
+    /// The starting planet of each explorer, in order
+    Explicit { planets: Vec<ID> },
+    Random,
+    /// As far as possible from each other, starting from `initial_planet_id`
+    Spread
+}
+
+/// Which planet types make the galaxy, selectable from the config with e.g. `{ type = "weighted", weights =
+/// { rust_eze = 2, carbonium = 1 } }`. The types are named like in the galaxy files.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum PlanetMix {
+    /// Every type of `PLANET_ORDER` in turn, by planet ID
+    Cyclic,
+    /// The given types in turn, by planet ID
+    Explicit { types: Vec<String> },
+    /// A random type for every planet, with probability proportional to the weight
+    Weighted { weights: BTreeMap<String, f64> },
+    /// Like `cyclic` but only with the types that can make all these resources (basic or complex, by name)
+    Supporting { resources: Vec<String> }
+}
+
 // DTOs used to initialize the entities
 pub(crate) struct PlanetInit {
     pub planet: Planet,
@@ -42,6 +80,7 @@ pub(crate) struct PlanetInit {
 
 pub(crate) struct ExplorerInit {
     pub explorer: Box<dyn ExplorerBuilder>,
+    pub type_name: String,
     pub initial_planet: ID,
     pub orchestrator_to_explorer_tx: Sender<OrchestratorToExplorer>,
     pub planet_to_explorer_tx: Sender<PlanetToExplorer>
@@ -49,6 +88,7 @@ pub(crate) struct ExplorerInit {
 
 pub(crate) struct GalaxyBuilderResult {
     pub galaxy: Galaxy,
+    pub planet_types: HashMap<ID, PlanetType>,
     pub planet_inits: HashMap<ID, PlanetInit>,
     pub explorer_inits: HashMap<ID, ExplorerInit>,
     pub planet_to_orchestrator_rx: crossbeam_channel::Receiver<PlanetToOrchestrator>,
@@ -58,19 +98,29 @@ pub(crate) struct GalaxyBuilderResult {
 impl GalaxyBuilder {
     pub fn new() -> Self {
         GalaxyBuilder {
-            fully_connected: false,
-            circular: false,
+            topologies: vec![],
             n_planets: 0,
             explorers: vec![],
+            layout: None,
+            explorer_placement: ExplorerPlacement::Fixed,
+            planet_mix: PlanetMix::Cyclic,
+            planet_options: PlanetOptions::default(),
+            rng: StdRng::from_os_rng(),
             explorer_to_orchestrator: unbounded(),
             planet_to_orchestrator: unbounded()
         }
     }
 
-    pub fn with_fully_connected_topology(self) -> Self { GalaxyBuilder { fully_connected: true, ..self } }
+    pub fn with_topology(mut self, topology: GalaxyTopology) -> Self {
+        self.topologies.push(topology);
+        self
+    }
+
+    #[allow(dead_code)] // not currently used but still useful
+    pub fn with_fully_connected_topology(self) -> Self { self.with_topology(GalaxyTopology::FullyConnected) }
 
     #[allow(dead_code)] // not currently used but still useful
-    pub fn with_circular_topology(self) -> Self { GalaxyBuilder { circular: true, ..self } }
+    pub fn with_circular_topology(self) -> Self { self.with_topology(GalaxyTopology::Circular) }
 
     pub fn with_n_planets(self, n: u32) -> Self { GalaxyBuilder { n_planets: n, ..self } }
 
@@ -78,27 +128,55 @@ impl GalaxyBuilder {
         GalaxyBuilder { explorers, ..self }
     }
 
+    pub fn with_explorer_placement(self, explorer_placement: ExplorerPlacement) -> Self {
+        GalaxyBuilder { explorer_placement, ..self }
+    }
+
+    pub fn with_planet_mix(self, planet_mix: PlanetMix) -> Self { GalaxyBuilder { planet_mix, ..self } }
+
+    /// Options given to the planet factory for every planet.
+    pub fn with_planet_options(self, planet_options: PlanetOptions) -> Self {
+        GalaxyBuilder { planet_options, ..self }
+    }
+
+    /// Build exactly the galaxy described by the layout: planets, types, edges and explorers.
+    /// Cannot be combined with the other galaxy options.
+    pub fn with_layout(self, layout: GalaxyLayout) -> Self { GalaxyBuilder { layout: Some(layout), ..self } }
+
+    /// Seeds the random choices made while building, and the seeds given to the explorers.
+    pub fn with_seed(self, seed: u64) -> Self { GalaxyBuilder { rng: StdRng::seed_from_u64(seed), ..self } }
+
     pub fn build(mut self) -> Result<GalaxyBuilderResult, String> {
-        if self.fully_connected && self.circular {
-            return Err("Cannot have both fully connected and circular topology".to_string());
-        }
-        if !self.fully_connected && !self.circular {
-            return Err("Must specify either fully connected or circular topology".to_string());
-        }
-        if self.n_planets == 0 && !self.explorers.is_empty() {
-            return Err("Cannot have explorers without planets".to_string());
+        if let Some(layout) = &self.layout {
+            if !self.topologies.is_empty()
+                || self.n_planets != 0
+                || !self.explorers.is_empty()
+                || self.planet_mix != PlanetMix::Cyclic
+            {
+                return Err("Cannot combine a galaxy layout with topology, planets, explorers or mix".to_string());
+            }
+            layout.validate()?;
+        } else {
+            if self.topologies.len() > 1 {
+                return Err(format!("Cannot have more than one topology, got {:?}", self.topologies));
+            }
+            if self.topologies.is_empty() {
+                return Err("Must specify a topology".to_string());
+            }
+            if self.n_planets == 0 && !self.explorers.is_empty() {
+                return Err("Cannot have explorers without planets".to_string());
+            }
         }
 
+        self.planet_options.validate()?;
         let galaxy = self.get_galaxy()?;
-        let planet_inits = self.get_planets_init()?;
-        let explorer_inits = if let Some(first_planet_init) = &planet_inits.get(&AppConfig::get().initial_planet_id) {
-            self.get_explorers_init(&first_planet_init.explorer_to_planet_tx)
-        } else {
-            HashMap::new()
-        };
+        let planet_types = self.get_planet_types()?;
+        let planet_inits = self.get_planets_init(&planet_types)?;
+        let explorer_inits = self.get_explorers_init(&galaxy, &planet_inits)?;
 
         Ok(GalaxyBuilderResult {
             galaxy,
+            planet_types,
             planet_inits,
             explorer_inits,
             planet_to_orchestrator_rx: self.planet_to_orchestrator.1,
@@ -106,45 +184,130 @@ impl GalaxyBuilder {
         })
     }
 
-    fn get_galaxy(&self) -> Result<Galaxy, String> {
+    fn get_galaxy(&mut self) -> Result<Galaxy, String> {
         let planet_ids = self.get_planet_ids();
-        if self.fully_connected {
-            Galaxy::make_fully_connected(&planet_ids)
-        } else {
-            Galaxy::make_circular(&planet_ids)
+        if let Some(layout) = &self.layout {
+            return Galaxy::make_from_edges(&planet_ids, &layout.edges);
         }
+        Galaxy::make(self.topologies[0], &planet_ids, &mut self.rng) // Checked in build that there is exactly one
     }
 
-    fn get_explorers_init(&mut self, first_planet_sender: &Sender<ExplorerToPlanet>) -> HashMap<ID, ExplorerInit> {
+    fn get_planet_types(&mut self) -> Result<HashMap<ID, PlanetType>, String> {
+        if let Some(layout) = &self.layout {
+            return layout.planet_types();
+        }
+        let planet_ids = self.get_planet_ids();
+        let cycle = match &self.planet_mix {
+            PlanetMix::Cyclic => {
+                let mut order = PLANET_ORDER.to_vec();
+                order.rotate_left(1); // The first planet has always been the second type of the order
+                order
+            }
+            PlanetMix::Explicit { types } => types.iter().map(|name| name.parse()).collect::<Result<_, _>>()?,
+            PlanetMix::Weighted { weights } => {
+                let types: Vec<PlanetType> = weights.keys().map(|name| name.parse()).collect::<Result<_, _>>()?;
+                let distribution = WeightedIndex::new(weights.values())
+                    .map_err(|e| format!("Invalid planet mix weights {weights:?}: {e}"))?;
+                return Ok(planet_ids.into_iter().map(|id| (id, types[distribution.sample(&mut self.rng)])).collect());
+            }
+            PlanetMix::Supporting { resources } => self.get_supporting_types(resources)?
+        };
+        if cycle.is_empty() {
+            return Err("The planet mix has no planet type".to_string());
+        }
+        Ok(planet_ids.into_iter().enumerate().map(|(i, id)| (id, cycle[i % cycle.len()])).collect())
+    }
+
+    /// The types of `PLANET_ORDER` that can make all the resources, in order.
+    fn get_supporting_types(&self, resources: &[String]) -> Result<Vec<PlanetType>, String> {
+        let resources = resources
+            .iter()
+            .map(|name| resource_from_name(name).ok_or(format!("Unknown resource '{name}' in the planet mix")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut types = vec![];
+        for planet_type in PLANET_ORDER {
+            let supported = PlanetFactory::supported_resources(planet_type, &self.planet_options)?;
+            if resources.iter().all(|resource| supported.contains(resource)) {
+                types.push(planet_type);
+            }
+        }
+        Ok(types)
+    }
+
+    fn get_explorers_init(
+        &mut self,
+        galaxy: &Galaxy,
+        planet_inits: &HashMap<ID, PlanetInit>
+    ) -> Result<HashMap<ID, ExplorerInit>, String> {
         let mut handles = HashMap::new();
-        let explorer_ids = self.get_explorer_ids();
-        for (explorer, id) in self.explorers.drain(..).zip(explorer_ids) {
+        for (id, initial_planet, explorer) in self.get_explorer_placements(galaxy)? {
+            let planet_sender = &planet_inits
+                .get(&initial_planet)
+                .ok_or(format!("Explorer {id} cannot start on planet {initial_planet}, it does not exist"))?
+                .explorer_to_planet_tx;
             let orch_to_ex_channel = unbounded();
             let plan_to_ex_channel = unbounded();
+            let type_name = explorer.get_type_name();
             let explorer = explorer
                 .with_id(id)
-                .with_current_planet(AppConfig::get().initial_planet_id)
+                .with_seed(self.rng.random())
+                .with_current_planet(initial_planet)
                 .with_orchestrator_rx(orch_to_ex_channel.1)
                 .with_orchestrator_tx(self.explorer_to_orchestrator.0.clone())
                 .with_planet_rx(plan_to_ex_channel.1)
-                .with_current_planet_tx(first_planet_sender.clone());
+                .with_current_planet_tx(planet_sender.clone());
             handles.insert(id, ExplorerInit {
                 explorer,
-                initial_planet: AppConfig::get().initial_planet_id,
+                type_name,
+                initial_planet,
                 orchestrator_to_explorer_tx: orch_to_ex_channel.0,
                 planet_to_explorer_tx: plan_to_ex_channel.0
             });
         }
-        handles
+        Ok(handles)
+    }
+
+    /// Returns (explorer id, initial planet, builder) for every explorer to create
+    fn get_explorer_placements(&mut self, galaxy: &Galaxy) -> Result<Vec<(ID, ID, Box<dyn ExplorerBuilder>)>, String> {
+        if let Some(layout) = &self.layout {
+            return Ok(layout
+                .explorers
+                .iter()
+                .map(|e| (e.id, e.start, ExplorerFactory::make_from_name(&e.explorer_type)))
+                .collect());
+        }
+        let explorer_ids = self.get_explorer_ids();
+        let initial_planets = self.get_initial_planets(galaxy, explorer_ids.len())?;
+        Ok(self
+            .explorers
+            .drain(..)
+            .zip(explorer_ids.into_iter().zip(initial_planets))
+            .map(|(explorer, (id, initial_planet))| (id, initial_planet, explorer))
+            .collect())
     }
 
-    fn get_planets_init(&mut self) -> Result<HashMap<ID, PlanetInit>, String> {
+    fn get_initial_planets(&mut self, galaxy: &Galaxy, n_explorers: usize) -> Result<Vec<ID>, String> {
+        let galaxy_planets = galaxy.get_planets();
+        match &self.explorer_placement {
+            ExplorerPlacement::Fixed => Ok(vec![AppConfig::get().initial_planet_id; n_explorers]),
+            ExplorerPlacement::Explicit { planets } if planets.len() != n_explorers =>
+                Err(format!("Expected {n_explorers} explorer starting planets, got {}", planets.len())),
+            ExplorerPlacement::Explicit { planets } => Ok(planets.clone()),
+            ExplorerPlacement::Random =>
+                Ok((0..n_explorers).map(|_| galaxy_planets[self.rng.random_range(0..galaxy_planets.len())]).collect()),
+            ExplorerPlacement::Spread => Ok(galaxy.get_spread_planets(n_explorers, AppConfig::get().initial_planet_id))
+        }
+    }
+
+    fn get_planets_init(&self, planet_types: &HashMap<ID, PlanetType>) -> Result<HashMap<ID, PlanetInit>, String> {
         let mut handles = HashMap::new();
-        for planet_id in self.get_planet_ids() {
+        for (&planet_id, &planet_type) in planet_types {
             let orch_to_planet_channel = unbounded();
             let explorer_to_planet_channel = unbounded();
             handles.insert(planet_id, PlanetInit {
-                planet: GalaxyBuilder::get_planet(
+                planet: PlanetFactory::make_planet_with_options(
+                    planet_type,
+                    &self.planet_options,
                     planet_id,
                     self.planet_to_orchestrator.0.clone(),
                     orch_to_planet_channel.1,
@@ -157,19 +320,13 @@ impl GalaxyBuilder {
         Ok(handles)
     }
 
-    /// Create the planet instance based on its ID
-    fn get_planet(
-        id: ID,
-        p_to_o_tx: Sender<PlanetToOrchestrator>,
-        o_to_p_rx: Receiver<OrchestratorToPlanet>,
-        e_to_p: Receiver<ExplorerToPlanet>
-    ) -> Result<Planet, String> {
-        let planet_type = PLANET_ORDER[(id as usize) % PLANET_ORDER.len()];
-        PlanetFactory::make_planet(planet_type, id, p_to_o_tx, o_to_p_rx, e_to_p)
-    }
-
     #[allow(clippy::cast_possible_truncation)] // We will never have that many planets
-    fn get_planet_ids(&self) -> Vec<ID> { (1..=self.n_planets).map(|i| i as ID).collect() }
+    fn get_planet_ids(&self) -> Vec<ID> {
+        if let Some(layout) = &self.layout {
+            return layout.planet_ids();
+        }
+        (1..=self.n_planets).map(|i| i as ID).collect()
+    }
 
     #[allow(clippy::cast_possible_truncation)] // We will never have that many planets
     fn get_explorer_ids(&self) -> Vec<ID> {
@@ -195,4 +352,113 @@ mod tests {
         assert!(good_gb.is_ok());
         assert!(!bad_gb.is_ok());
     }
+
+    #[test]
+    fn test_galaxy_build_generated_topology() {
+        let result = GalaxyBuilder::new()
+            .with_seed(7)
+            .with_topology(GalaxyTopology::Random { edge_probability: 0.2 })
+            .with_n_planets(10)
+            .build()
+            .unwrap();
+
+        assert!(result.galaxy.is_connected());
+        assert_eq!(result.galaxy.get_planets().len(), 10);
+    }
+
+    #[test]
+    fn test_galaxy_build_from_layout() {
+        let layout = GalaxyLayout::from_content(
+            r#"
+            edges = [[1, 3]]
+            planets = [{ id = 1, type = "rust_eze" }, { id = 3, type = "rustrelli" }]
+            explorers = [{ id = 8, type = "example", start = 3 }]
+            "#,
+            config::FileFormat::Toml
+        )
+        .unwrap();
+        let result = GalaxyBuilder::new().with_layout(layout).build().unwrap();
+
+        assert_eq!(result.galaxy.get_planets(), vec![1, 3]);
+        assert!(result.galaxy.are_planets_connected(1, 3));
+        assert_eq!(result.planet_types[&1], PlanetType::RustEze);
+        assert_eq!(result.explorer_inits[&8].initial_planet, 3);
+    }
+
+    #[test]
+    fn test_galaxy_build_layout_conflicts() {
+        let layout = GalaxyLayout::from_content("planets = [{ id = 1, type = \"rust_eze\" }]", config::FileFormat::Toml)
+            .unwrap();
+        let result = GalaxyBuilder::new().with_layout(layout).with_n_planets(3).build();
+        assert!(result.is_err());
+    }
+
+    fn build_with_placement(placement: ExplorerPlacement) -> Result<GalaxyBuilderResult, String> {
+        let explorers = (0..3).map(|_| ExplorerFactory::make_from_name(&"example".to_string())).collect();
+        GalaxyBuilder::new()
+            .with_seed(3)
+            .with_circular_topology()
+            .with_n_planets(6)
+            .with_explorers(explorers)
+            .with_explorer_placement(placement)
+            .build()
+    }
+
+    fn initial_planets(result: &GalaxyBuilderResult) -> Vec<ID> {
+        let mut ids: Vec<&ID> = result.explorer_inits.keys().collect();
+        ids.sort();
+        ids.into_iter().map(|id| result.explorer_inits[id].initial_planet).collect()
+    }
+
+    #[test]
+    fn test_explorer_placement() {
+        let explicit = build_with_placement(ExplorerPlacement::Explicit { planets: vec![2, 5, 2] }).unwrap();
+        assert_eq!(initial_planets(&explicit), vec![2, 5, 2]);
+
+        let random = build_with_placement(ExplorerPlacement::Random).unwrap();
+        assert!(initial_planets(&random).iter().all(|planet| (1..=6).contains(planet)));
+
+        // On a ring of 6 the second explorer goes on the opposite side of the first one
+        let spread = build_with_placement(ExplorerPlacement::Spread).unwrap();
+        assert_eq!(initial_planets(&spread), vec![1, 4, 2]);
+    }
+
+    #[test]
+    fn test_explorer_placement_errors() {
+        assert!(build_with_placement(ExplorerPlacement::Explicit { planets: vec![1] }).is_err());
+        assert!(build_with_placement(ExplorerPlacement::Explicit { planets: vec![1, 2, 9] }).is_err());
+    }
+
+    fn build_with_mix(planet_mix: PlanetMix) -> Result<GalaxyBuilderResult, String> {
+        GalaxyBuilder::new().with_seed(5).with_circular_topology().with_n_planets(4).with_planet_mix(planet_mix).build()
+    }
+
+    #[test]
+    fn test_planet_mix() {
+        let explicit = PlanetMix::Explicit { types: vec!["rust_eze".to_string(), "carbonium".to_string()] };
+        let types = build_with_mix(explicit).unwrap().planet_types;
+        assert_eq!(types[&1], PlanetType::RustEze);
+        assert_eq!(types[&2], PlanetType::Carbonium);
+        assert_eq!(types[&3], PlanetType::RustEze);
+
+        let weighted = PlanetMix::Weighted {
+            weights: BTreeMap::from([("rustrelli".to_string(), 1.0), ("one_million_crabs".to_string(), 0.0)])
+        };
+        let types = build_with_mix(weighted).unwrap().planet_types;
+        assert!(types.values().all(|&planet_type| planet_type == PlanetType::Rustrelli));
+    }
+
+    #[test]
+    fn test_planet_mix_errors() {
+        assert!(build_with_mix(PlanetMix::Explicit { types: vec![] }).is_err());
+        assert!(build_with_mix(PlanetMix::Explicit { types: vec!["earth".to_string()] }).is_err());
+        let zero_weights = BTreeMap::from([("rust_eze".to_string(), 0.0)]);
+        assert!(build_with_mix(PlanetMix::Weighted { weights: zero_weights }).is_err());
+        assert!(build_with_mix(PlanetMix::Supporting { resources: vec!["Unobtainium".to_string()] }).is_err());
+    }
+
+    #[test]
+    fn test_galaxy_build_without_topology() {
+        assert!(GalaxyBuilder::new().with_n_planets(5).build().is_err());
+    }
 }
@@ -0,0 +1,172 @@
+//! Hand-crafted galaxy descriptions, loaded from a TOML or JSON file (the format is chosen by the
+//! file extension). See the `galaxies` folder for some examples.
+
+use std::collections::{HashMap, HashSet};
+
+use common_game::utils::ID;
+use config::{Config, File, FileFormat};
+use serde::Deserialize;
+
+use crate::explorers::ExplorerFactory;
+use crate::orchestrator::PlanetType;
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct GalaxyLayout {
+    pub planets: Vec<PlanetLayout>,
+    #[serde(default)]
+    pub edges: Vec<(ID, ID)>,
+    #[serde(default)]
+    pub explorers: Vec<ExplorerLayout>
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct PlanetLayout {
+    pub id: ID,
+    #[serde(rename = "type")]
+    pub planet_type: String
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ExplorerLayout {
+    pub id: ID,
+    #[serde(rename = "type")]
+    pub explorer_type: String,
+    pub start: ID
+}
+
+impl GalaxyLayout {
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        Self::from_source(File::with_name(path)).map_err(|e| format!("Failed to load galaxy layout {path}: {e}"))
+    }
+
+    #[allow(dead_code)] // used in tests
+    pub fn from_content(content: &str, format: FileFormat) -> Result<Self, String> {
+        Self::from_source(File::from_str(content, format)).map_err(|e| format!("Failed to parse galaxy layout: {e}"))
+    }
+
+    fn from_source<S: config::Source + Send + Sync + 'static>(source: S) -> Result<Self, config::ConfigError> {
+        Config::builder().add_source(source).build()?.try_deserialize()
+    }
+
+    /// Checks that the layout describes a consistent galaxy, returning the first problem found.
+    pub fn validate(&self) -> Result<(), String> {
+        let mut planet_ids = HashSet::new();
+        for planet in &self.planets {
+            if !planet_ids.insert(planet.id) {
+                return Err(format!("Duplicate planet ID found: {}", planet.id));
+            }
+            planet.planet_type.parse::<PlanetType>().map_err(|e| format!("Planet {}: {e}", planet.id))?;
+        }
+
+        for &(a, b) in &self.edges {
+            if !planet_ids.contains(&a) || !planet_ids.contains(&b) {
+                return Err(format!("Edge ({a}, {b}) references a planet that does not exist"));
+            }
+            if a == b {
+                return Err(format!("Edge ({a}, {b}) connects a planet to itself"));
+            }
+        }
+
+        let mut explorer_ids = HashSet::new();
+        for explorer in &self.explorers {
+            if planet_ids.contains(&explorer.id) || !explorer_ids.insert(explorer.id) {
+                return Err(format!("Duplicate explorer ID found: {}", explorer.id));
+            }
+            if !ExplorerFactory::is_known_type(&explorer.explorer_type) {
+                return Err(format!("Explorer {}: Unknown explorer type '{}'", explorer.id, explorer.explorer_type));
+            }
+            if !planet_ids.contains(&explorer.start) {
+                return Err(format!(
+                    "Explorer {} starts on planet {}, which does not exist",
+                    explorer.id, explorer.start
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn planet_ids(&self) -> Vec<ID> { self.planets.iter().map(|p| p.id).collect() }
+
+    pub fn planet_types(&self) -> Result<HashMap<ID, PlanetType>, String> {
+        self.planets.iter().map(|p| Ok((p.id, p.planet_type.parse::<PlanetType>()?))).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const VALID: &str = r#"
+        edges = [[1, 2], [2, 3]]
+
+        [[planets]]
+        id = 1
+        type = "rustrelli"
+
+        [[planets]]
+        id = 2
+        type = "Carbonium"
+
+        [[planets]]
+        id = 3
+        type = "one_million_crabs"
+
+        [[explorers]]
+        id = 10
+        type = "example"
+        start = 3
+    "#;
+
+    fn parse(content: &str) -> GalaxyLayout { GalaxyLayout::from_content(content, FileFormat::Toml).unwrap() }
+
+    #[test]
+    fn test_valid_layout() {
+        let layout = parse(VALID);
+        assert!(layout.validate().is_ok());
+        assert_eq!(layout.edges, vec![(1, 2), (2, 3)]);
+        assert_eq!(layout.explorers[0].start, 3);
+        assert_eq!(layout.planet_types().unwrap()[&3], PlanetType::OneMillionCrabs);
+    }
+
+    #[test]
+    fn test_duplicate_planet_ids() {
+        let layout = parse(&VALID.replace("id = 3", "id = 2"));
+        assert!(layout.validate().unwrap_err().contains("Duplicate planet ID"));
+    }
+
+    #[test]
+    fn test_dangling_edge() {
+        let layout = parse(&VALID.replace("[2, 3]", "[2, 4]"));
+        assert!(layout.validate().unwrap_err().contains("does not exist"));
+    }
+
+    #[test]
+    fn test_unknown_planet_type() {
+        let layout = parse(&VALID.replace("\"Carbonium\"", "\"Plutonium\""));
+        assert!(layout.validate().unwrap_err().contains("Unknown planet type"));
+    }
+
+    #[test]
+    fn test_unknown_explorer_type() {
+        let layout = parse(&VALID.replace("\"example\"", "\"columbus\""));
+        assert!(layout.validate().unwrap_err().contains("Unknown explorer type"));
+        let layout = parse(&VALID.replace("\"example\"", "\"remote:127.0.0.1:7000\""));
+        assert!(layout.validate().is_ok());
+    }
+
+    #[test]
+    fn test_explorer_on_missing_planet() {
+        let layout = parse(&VALID.replace("start = 3", "start = 7"));
+        assert!(layout.validate().is_err());
+    }
+
+    #[test]
+    fn test_bundled_layouts() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/galaxies");
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let layout = GalaxyLayout::from_file(path.to_str().unwrap()).unwrap();
+            assert!(layout.validate().is_ok(), "Invalid layout {}", path.display());
+        }
+    }
+}
@@ -2,7 +2,11 @@
 //! This includes galaxy building and planet factory logic, with all the
 //! channels setup and sharing.
 mod galaxy_builder;
+mod galaxy_layout;
 mod planet_factory;
+#[cfg(test)]
+mod planet_conformance;
 
-pub(crate) use galaxy_builder::{GalaxyBuilder, PLANET_ORDER};
-pub(crate) use planet_factory::{PlanetFactory, PlanetType};
+pub(crate) use galaxy_builder::{ExplorerPlacement, GalaxyBuilder, PLANET_ORDER, PlanetMix};
+pub(crate) use galaxy_layout::{ExplorerLayout, GalaxyLayout, PlanetLayout};
+pub(crate) use planet_factory::{PlanetFactory, PlanetOptions, PlanetType};
@@ -0,0 +1,304 @@
+//! Protocol conformance suite for the planets of the `PlanetFactory`. Every planet type is started alone
+//! and driven through the `orchestrator_planet` and `planet_explorer` protocols by a fake orchestrator
+//! and a fake explorer, the results are gathered in a compatibility matrix (one row per planet type).
+//! Run it with `cargo test planet_conformance -- --nocapture` to read the matrix.
+//!
+//! The checks run in order on the same planet, the state they leave matters: the generation comes after a
+//! sunray so that the planet has a charged cell, and the asteroid comes last before the kill.
+
+use std::fmt::Write;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use common_game::components::asteroid::Asteroid;
+use common_game::components::resource::BasicResourceType;
+use common_game::components::sunray::Sunray;
+use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetToOrchestrator,
+                                                  PlanetToOrchestratorKind as Kind};
+use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer, PlanetToExplorerKind};
+use common_game::utils::ID;
+use crossbeam_channel::{Receiver, Sender, unbounded};
+
+use crate::orchestrator::{PLANET_ORDER, PlanetFactory, PlanetType};
+
+const PLANET_ID: ID = 1;
+const EXPLORER_ID: ID = 10;
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// The column names of the matrix, in the order the checks run.
+pub(crate) const CHECKS: [&str; 12] = [
+    "start",
+    "internal_state",
+    "sunray",
+    "incoming_explorer",
+    "energy_cells",
+    "supported_resources",
+    "supported_combinations",
+    "generate",
+    "outgoing_explorer",
+    "asteroid",
+    "kill",
+    "thread_stops"
+];
+
+/// The outcome of every check of `CHECKS` on a planet type. A passed check may carry a short note, e.g.
+/// whether the asteroid was deflected.
+#[derive(Debug)]
+pub(crate) struct PlanetReport {
+    pub planet_type: PlanetType,
+    pub results: Vec<Result<String, String>>
+}
+
+impl PlanetReport {
+    /// The names of the failed checks, in order.
+    pub fn failures(&self) -> Vec<&'static str> {
+        CHECKS.iter().zip(&self.results).filter(|(_, result)| result.is_err()).map(|(&name, _)| name).collect()
+    }
+}
+
+struct Harness {
+    thread: thread::JoinHandle<()>,
+    to_planet: Sender<OrchestratorToPlanet>,
+    from_planet: Receiver<PlanetToOrchestrator>,
+    explorer_to_planet: Sender<ExplorerToPlanet>,
+    // The fake explorer: its sender is given to the planet with the incoming explorer request
+    tx_explorer: Sender<PlanetToExplorer>,
+    explorer_from_planet: Receiver<PlanetToExplorer>
+}
+
+/// Runs every check on a new planet of the given type.
+pub(crate) fn check_planet(planet_type: PlanetType) -> PlanetReport {
+    let harness = match Harness::start(planet_type) {
+        Ok(harness) => harness,
+        Err(e) => {
+            let results = CHECKS.iter().map(|_| Err(format!("cannot be created: {e}"))).collect();
+            return PlanetReport { planet_type, results };
+        }
+    };
+    let mut results = Vec::with_capacity(CHECKS.len());
+    results.push(harness.ack(OrchestratorToPlanet::StartPlanetAI, Kind::StartPlanetAIResult));
+    results.push(harness.internal_state());
+    results.push(harness.ack(OrchestratorToPlanet::Sunray(Sunray::default()), Kind::SunrayAck));
+    results.push(harness.incoming_explorer());
+    results.push(harness.energy_cells());
+    let supported = harness.supported_resources();
+    results.push(supported.as_ref().map(|resources| format!("{resources:?}")).map_err(Clone::clone));
+    results.push(harness.supported_combinations());
+    results.push(match supported {
+        Ok(resources) => harness.generate(resources.first().copied()),
+        Err(_) => Err("no supported resource to ask for".to_string())
+    });
+    results.push(harness.outgoing_explorer());
+    results.push(harness.asteroid());
+    results.push(harness.ack(OrchestratorToPlanet::KillPlanet, Kind::KillPlanetResult));
+    results.push(harness.thread_stops());
+    PlanetReport { planet_type, results }
+}
+
+impl Harness {
+    fn start(planet_type: PlanetType) -> Result<Self, String> {
+        let (to_planet, rx_orchestrator) = unbounded();
+        let (tx_orchestrator, from_planet) = unbounded();
+        let (explorer_to_planet, rx_explorer) = unbounded();
+        let (tx_explorer, explorer_from_planet) = unbounded();
+        let mut planet =
+            PlanetFactory::make_planet(planet_type, PLANET_ID, tx_orchestrator, rx_orchestrator, rx_explorer)?;
+        let thread = thread::spawn(move || {
+            if let Err(e) = planet.run() {
+                log::warn!("Planet under test terminated with error: {e}");
+            }
+        });
+        Ok(Harness { thread, to_planet, from_planet, explorer_to_planet, tx_explorer, explorer_from_planet })
+    }
+
+    fn exchange(&self, request: OrchestratorToPlanet, expected: Kind) -> Result<PlanetToOrchestrator, String> {
+        if self.thread.is_finished() {
+            return Err("the planet thread terminated before the request".to_string());
+        }
+        self.to_planet.send(request).map_err(|_| "the planet dropped its receiver".to_string())?;
+        let response = self.from_planet.recv_timeout(RESPONSE_TIMEOUT).map_err(|e| format!("no {expected:?}: {e}"))?;
+        if Kind::from(&response) != expected {
+            return Err(format!("expected {expected:?}, got {response:?}"));
+        }
+        Ok(response)
+    }
+
+    fn ack(&self, request: OrchestratorToPlanet, expected: Kind) -> Result<String, String> {
+        self.exchange(request, expected).map(|_| String::new())
+    }
+
+    fn explorer_exchange(
+        &self,
+        request: ExplorerToPlanet,
+        expected: PlanetToExplorerKind
+    ) -> Result<PlanetToExplorer, String> {
+        self.explorer_to_planet.send(request).map_err(|_| "the planet dropped its explorer receiver".to_string())?;
+        let response =
+            self.explorer_from_planet.recv_timeout(RESPONSE_TIMEOUT).map_err(|e| format!("no {expected:?}: {e}"))?;
+        if PlanetToExplorerKind::from(&response) != expected {
+            return Err(format!("expected {expected:?}, got {response:?}"));
+        }
+        Ok(response)
+    }
+
+    fn internal_state(&self) -> Result<String, String> {
+        let state = self
+            .exchange(OrchestratorToPlanet::InternalStateRequest, Kind::InternalStateResponse)?
+            .into_internal_state_response()
+            .unwrap() // Unwrap is safe due to expected kind
+            .1;
+        Ok(format!("{} cells", state.energy_cells.len()))
+    }
+
+    fn incoming_explorer(&self) -> Result<String, String> {
+        let request = OrchestratorToPlanet::IncomingExplorerRequest {
+            explorer_id: EXPLORER_ID,
+            new_sender: self.tx_explorer.clone()
+        };
+        let (_, explorer_id, accepted) = self
+            .exchange(request, Kind::IncomingExplorerResponse)?
+            .into_incoming_explorer_response()
+            .unwrap(); // Unwrap is safe due to expected kind
+        accepted.map_err(|e| format!("refused the explorer: {e}"))?;
+        if explorer_id != EXPLORER_ID {
+            return Err(format!("accepted explorer {explorer_id} instead of {EXPLORER_ID}"));
+        }
+        Ok(String::new())
+    }
+
+    fn outgoing_explorer(&self) -> Result<String, String> {
+        let request = OrchestratorToPlanet::OutgoingExplorerRequest { explorer_id: EXPLORER_ID };
+        let (_, explorer_id, left) = self
+            .exchange(request, Kind::OutgoingExplorerResponse)?
+            .into_outgoing_explorer_response()
+            .unwrap(); // Unwrap is safe due to expected kind
+        left.map_err(|e| format!("refused to let the explorer go: {e}"))?;
+        if explorer_id != EXPLORER_ID {
+            return Err(format!("let explorer {explorer_id} go instead of {EXPLORER_ID}"));
+        }
+        Ok(String::new())
+    }
+
+    fn energy_cells(&self) -> Result<String, String> {
+        let request = ExplorerToPlanet::AvailableEnergyCellRequest { explorer_id: EXPLORER_ID };
+        let cells = self
+            .explorer_exchange(request, PlanetToExplorerKind::AvailableEnergyCellResponse)?
+            .into_available_energy_cell_response()
+            .unwrap(); // Unwrap is safe due to expected kind
+        Ok(format!("{cells} charged"))
+    }
+
+    /// The basic resources of the planet, sorted.
+    fn supported_resources(&self) -> Result<Vec<BasicResourceType>, String> {
+        let request = ExplorerToPlanet::SupportedResourceRequest { explorer_id: EXPLORER_ID };
+        let mut resources: Vec<BasicResourceType> = self
+            .explorer_exchange(request, PlanetToExplorerKind::SupportedResourceResponse)?
+            .into_supported_resource_response()
+            .unwrap() // Unwrap is safe due to expected kind
+            .into_iter()
+            .collect();
+        resources.sort_by_key(|resource| format!("{resource:?}"));
+        Ok(resources)
+    }
+
+    fn supported_combinations(&self) -> Result<String, String> {
+        let request = ExplorerToPlanet::SupportedCombinationRequest { explorer_id: EXPLORER_ID };
+        let combinations = self
+            .explorer_exchange(request, PlanetToExplorerKind::SupportedCombinationResponse)?
+            .into_supported_combination_response()
+            .unwrap(); // Unwrap is safe due to expected kind
+        Ok(format!("{} recipes", combinations.len()))
+    }
+
+    /// After a sunray the planet has a charged cell, so it should generate one of its resources.
+    fn generate(&self, resource: Option<BasicResourceType>) -> Result<String, String> {
+        let resource = resource.ok_or("the planet supports no basic resource")?;
+        let request = ExplorerToPlanet::GenerateResourceRequest { explorer_id: EXPLORER_ID, resource };
+        let generated = self
+            .explorer_exchange(request, PlanetToExplorerKind::GenerateResourceResponse)?
+            .into_generate_resource_response()
+            .unwrap(); // Unwrap is safe due to expected kind
+        match generated {
+            Some(_) => Ok(format!("{resource:?}")),
+            None => Err(format!("did not generate {resource:?} with a charged cell"))
+        }
+    }
+
+    fn asteroid(&self) -> Result<String, String> {
+        let rocket = self
+            .exchange(OrchestratorToPlanet::Asteroid(Asteroid::default()), Kind::AsteroidAck)?
+            .into_asteroid_ack()
+            .unwrap() // Unwrap is safe due to expected kind
+            .1;
+        Ok(if rocket.is_some() { "deflected" } else { "destroyed" }.to_string())
+    }
+
+    fn thread_stops(&self) -> Result<String, String> {
+        let deadline = Instant::now() + RESPONSE_TIMEOUT;
+        while !self.thread.is_finished() {
+            if Instant::now() > deadline {
+                return Err(format!("still running {RESPONSE_TIMEOUT:?} after the kill"));
+            }
+            thread::sleep(Duration::from_millis(10));
+        }
+        Ok(String::new())
+    }
+}
+
+/// The compatibility matrix of the reports, as a Markdown table. The details of the failures follow it.
+pub(crate) fn format_matrix(reports: &[PlanetReport]) -> String {
+    let mut matrix = format!("| planet | {} |\n", CHECKS.join(" | "));
+    writeln!(matrix, "|---{}|", "|---".repeat(CHECKS.len())).expect("Writing to a String cannot fail");
+    for report in reports {
+        let cells: Vec<String> = report
+            .results
+            .iter()
+            .map(|result| match result {
+                Ok(note) if note.is_empty() => "ok".to_string(),
+                Ok(note) => format!("ok ({note})"),
+                Err(_) => "FAIL".to_string()
+            })
+            .collect();
+        writeln!(matrix, "| {:?} | {} |", report.planet_type, cells.join(" | "))
+            .expect("Writing to a String cannot fail");
+    }
+    for report in reports {
+        for (name, result) in CHECKS.iter().zip(&report.results) {
+            if let Err(reason) = result {
+                writeln!(matrix, "{:?} {name}: {reason}", report.planet_type).expect("Writing to a String cannot fail");
+            }
+        }
+    }
+    matrix
+}
+
+/// Checks all the planet types of the factory, each one in its own thread.
+pub(crate) fn check_all_planets() -> Vec<PlanetReport> {
+    let handles: Vec<thread::JoinHandle<PlanetReport>> =
+        PLANET_ORDER.into_iter().map(|planet_type| thread::spawn(move || check_planet(planet_type))).collect();
+    handles.into_iter().map(|handle| handle.join().expect("The conformance checks do not panic")).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_planet_conformance_matrix() {
+        let reports = check_all_planets();
+        println!("{}", format_matrix(&reports));
+
+        assert_eq!(reports.len(), PLANET_ORDER.len());
+        for report in &reports {
+            assert_eq!(report.results.len(), CHECKS.len());
+            // Every game relies on these, a planet failing them cannot be put in a galaxy
+            for required in ["start", "sunray", "asteroid"] {
+                assert!(
+                    !report.failures().contains(&required),
+                    "Planet {:?} fails the {required} check",
+                    report.planet_type
+                );
+            }
+        }
+    }
+}
@@ -1,14 +1,22 @@
 //! Factory for creating different types of planets.
 //! Hides the complexity of all the different APIs behind a simple interface.
 
+use std::str::FromStr;
+use std::thread;
+use std::time::Duration;
+
 use common_game::components::planet::Planet;
-use common_game::components::resource::BasicResourceType::Carbon;
-use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetToOrchestrator};
-use common_game::protocols::planet_explorer::ExplorerToPlanet;
+use common_game::components::resource::{BasicResourceType, ResourceType};
+use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetToOrchestrator, PlanetToOrchestratorKind};
+use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorerKind};
 use common_game::utils::ID;
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, Sender, unbounded};
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppConfig;
+use crate::orchestrator::{PLANET_ORDER, resource_from_name};
 
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub(crate) enum PlanetType {
     PanicOutOfOxygen,
     TheCompilerStrikesBack,
@@ -19,27 +27,102 @@ pub(crate) enum PlanetType {
     RustEze
 }
 
+/// Parses the variant name ignoring case and separators, e.g. `"rust_eze"` or `"RustEze"`.
+impl FromStr for PlanetType {
+    type Err = String;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        let normalized: String = name.chars().filter(char::is_ascii_alphanumeric).collect();
+        PLANET_ORDER
+            .into_iter()
+            .find(|planet_type| format!("{planet_type:?}").eq_ignore_ascii_case(&normalized))
+            .ok_or(format!("Unknown planet type '{name}'"))
+    }
+}
+
+/// The settings of the planets that take some at creation, from the `planet_options` table of the config.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub(crate) struct PlanetOptions {
+    pub rustrelli_request_limit: RustrelliRequestLimit,
+    pub houston_rocket_strategy: HoustonRocketStrategy,
+    /// Basic resource generated by Houston We Have A Borrow, by name, Carbon if not set
+    pub houston_basic_resource: Option<String>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum RustrelliRequestLimit {
+    None,
+    FairShare
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum HoustonRocketStrategy {
+    Disabled,
+    Safe,
+    EmergencyReserve,
+    Default
+}
+
+impl Default for PlanetOptions {
+    fn default() -> Self {
+        PlanetOptions {
+            rustrelli_request_limit: RustrelliRequestLimit::None,
+            houston_rocket_strategy: HoustonRocketStrategy::Default,
+            houston_basic_resource: Some("Carbon".to_string())
+        }
+    }
+}
+
+impl PlanetOptions {
+    pub fn validate(&self) -> Result<(), String> { self.houston_basic_resource().map(|_| ()) }
+
+    fn houston_basic_resource(&self) -> Result<Option<BasicResourceType>, String> {
+        match self.houston_basic_resource.as_deref().map(|name| (name, resource_from_name(name))) {
+            None => Ok(None),
+            Some((_, Some(ResourceType::Basic(resource)))) => Ok(Some(resource)),
+            Some((name, _)) => Err(format!("'{name}' is not a basic resource"))
+        }
+    }
+}
+
 pub(crate) struct PlanetFactory;
 
 impl PlanetFactory {
+    /// Creates a planet with the default options.
+    #[allow(dead_code)] // used in tests
     pub(crate) fn make_planet(
         planet_type: PlanetType,
         id: ID,
         sender: Sender<PlanetToOrchestrator>,
         receiver: Receiver<OrchestratorToPlanet>,
         explorer_receiver: Receiver<ExplorerToPlanet>
+    ) -> Result<Planet, String> {
+        Self::make_planet_with_options(planet_type, &PlanetOptions::default(), id, sender, receiver, explorer_receiver)
+    }
+
+    pub(crate) fn make_planet_with_options(
+        planet_type: PlanetType,
+        options: &PlanetOptions,
+        id: ID,
+        sender: Sender<PlanetToOrchestrator>,
+        receiver: Receiver<OrchestratorToPlanet>,
+        explorer_receiver: Receiver<ExplorerToPlanet>
     ) -> Result<Planet, String> {
         match planet_type {
             PlanetType::PanicOutOfOxygen =>
                 Self::create_panic_out_of_oxygen_planet(id, sender, receiver, explorer_receiver),
-            PlanetType::Rustrelli => Ok(Self::create_rustrelli_planet(id, sender, receiver, explorer_receiver)),
+            PlanetType::Rustrelli =>
+                Ok(Self::create_rustrelli_planet(id, sender, receiver, explorer_receiver, options)),
             PlanetType::TheCompilerStrikesBack =>
                 Ok(Self::create_the_compiler_strikes_back_planet(id, sender, receiver, explorer_receiver)),
             PlanetType::Carbonium => Ok(Self::create_carbonium_planet(id, sender, receiver, explorer_receiver)),
             PlanetType::OneMillionCrabs =>
                 Self::create_one_million_crabs_planet(id, sender, receiver, explorer_receiver),
             PlanetType::HoustonWeHaveABorrow =>
-                Self::create_houston_we_have_a_borrow_planet(id, sender, receiver, explorer_receiver),
+                Self::create_houston_we_have_a_borrow_planet(id, sender, receiver, explorer_receiver, options),
             PlanetType::RustEze => Ok(Self::create_rust_eze_planet(id, sender, receiver, explorer_receiver))
         }
     }
@@ -72,15 +155,14 @@ impl PlanetFactory {
         id: ID,
         sender: Sender<PlanetToOrchestrator>,
         receiver: Receiver<OrchestratorToPlanet>,
-        explorer_receiver: Receiver<ExplorerToPlanet>
+        explorer_receiver: Receiver<ExplorerToPlanet>,
+        options: &PlanetOptions
     ) -> Planet {
-        rustrelli::create_planet(
-            id,
-            receiver,
-            sender,
-            explorer_receiver,
-            rustrelli::ExplorerRequestLimit::None // Can be changed to FairShare
-        )
+        let request_limit = match options.rustrelli_request_limit {
+            RustrelliRequestLimit::None => rustrelli::ExplorerRequestLimit::None,
+            RustrelliRequestLimit::FairShare => rustrelli::ExplorerRequestLimit::FairShare
+        };
+        rustrelli::create_planet(id, receiver, sender, explorer_receiver, request_limit)
     }
 
     #[allow(clippy::needless_pass_by_value)]
@@ -107,15 +189,22 @@ impl PlanetFactory {
         id: ID,
         sender: Sender<PlanetToOrchestrator>,
         receiver: Receiver<OrchestratorToPlanet>,
-        explorer_receiver: Receiver<ExplorerToPlanet>
+        explorer_receiver: Receiver<ExplorerToPlanet>,
+        options: &PlanetOptions
     ) -> Result<Planet, String> {
+        let rocket_strategy = match options.houston_rocket_strategy {
+            HoustonRocketStrategy::Disabled => HWHAB::RocketStrategy::Disabled,
+            HoustonRocketStrategy::Safe => HWHAB::RocketStrategy::Safe,
+            HoustonRocketStrategy::EmergencyReserve => HWHAB::RocketStrategy::EmergencyReserve,
+            HoustonRocketStrategy::Default => HWHAB::RocketStrategy::Default
+        };
         HWHAB::houston_we_have_a_borrow(
             receiver,
             sender,
             explorer_receiver,
             id,
-            HWHAB::RocketStrategy::Default, // (Disabled, Safe, EmergencyReserve, Default)
-            Some(Carbon)                    // Any Option<BasicResourceType>, what a novel idea
+            rocket_strategy,
+            options.houston_basic_resource()? // Any Option<BasicResourceType>, what a novel idea
         )
     }
 
@@ -127,6 +216,76 @@ impl PlanetFactory {
     ) -> Planet {
         rust_eze::create_planet(id, receiver, sender, explorer_receiver)
     }
+
+    /// The basic and complex resources a planet type can make with these options, found by starting a
+    /// throwaway planet and asking it like an explorer would.
+    pub(crate) fn supported_resources(
+        planet_type: PlanetType,
+        options: &PlanetOptions
+    ) -> Result<Vec<ResourceType>, String> {
+        const PROBE_ID: ID = 0;
+        let timeout = Duration::from_millis(AppConfig::get().max_wait_time_ms);
+        let (to_planet, rx_orchestrator) = unbounded();
+        let (tx_orchestrator, from_planet) = unbounded();
+        let (explorer_to_planet, rx_explorer) = unbounded();
+        let (tx_explorer, explorer_from_planet) = unbounded();
+        let mut planet = Self::make_planet_with_options(
+            planet_type,
+            options,
+            PROBE_ID,
+            tx_orchestrator,
+            rx_orchestrator,
+            rx_explorer
+        )?;
+        let handle = thread::spawn(move || planet.run());
+
+        let ask_planet = |request: OrchestratorToPlanet, expected: PlanetToOrchestratorKind| {
+            to_planet.send(request).map_err(|e| e.to_string())?;
+            match from_planet.recv_timeout(timeout) {
+                Ok(response) if PlanetToOrchestratorKind::from(&response) == expected => Ok(()),
+                Ok(response) => Err(format!("expected {expected:?}, got {response:?}")),
+                Err(e) => Err(format!("no {expected:?}: {e}"))
+            }
+        };
+        let ask_explorer = |request: ExplorerToPlanet, expected: PlanetToExplorerKind| {
+            explorer_to_planet.send(request).map_err(|e| e.to_string())?;
+            match explorer_from_planet.recv_timeout(timeout) {
+                Ok(response) if PlanetToExplorerKind::from(&response) == expected => Ok(response),
+                Ok(response) => Err(format!("expected {expected:?}, got {response:?}")),
+                Err(e) => Err(format!("no {expected:?}: {e}"))
+            }
+        };
+        let probe = || -> Result<Vec<ResourceType>, String> {
+            ask_planet(OrchestratorToPlanet::StartPlanetAI, PlanetToOrchestratorKind::StartPlanetAIResult)?;
+            ask_planet(
+                OrchestratorToPlanet::IncomingExplorerRequest {
+                    explorer_id: PROBE_ID,
+                    new_sender: tx_explorer.clone()
+                },
+                PlanetToOrchestratorKind::IncomingExplorerResponse
+            )?;
+            let basic = ask_explorer(
+                ExplorerToPlanet::SupportedResourceRequest { explorer_id: PROBE_ID },
+                PlanetToExplorerKind::SupportedResourceResponse
+            )?
+            .into_supported_resource_response()
+            .unwrap(); // Unwrap is safe due to expected kind
+            let complex = ask_explorer(
+                ExplorerToPlanet::SupportedCombinationRequest { explorer_id: PROBE_ID },
+                PlanetToExplorerKind::SupportedCombinationResponse
+            )?
+            .into_supported_combination_response()
+            .unwrap(); // Unwrap is safe due to expected kind
+            let complex = complex.into_iter().map(ResourceType::Complex);
+            Ok(basic.into_iter().map(ResourceType::Basic).chain(complex).collect())
+        };
+        let resources = probe().map_err(|e| format!("Cannot ask planet {planet_type:?} its resources: {e}"));
+        let killed = ask_planet(OrchestratorToPlanet::KillPlanet, PlanetToOrchestratorKind::KillPlanetResult);
+        if killed.is_ok() {
+            let _ = handle.join(); // Only the resources matter, not how the planet stopped
+        }
+        resources
+    }
 }
 
 #[cfg(test)]
@@ -142,6 +301,37 @@ mod tests {
         (tx_planet, rx_orch, rx_explorer)
     }
 
+    #[test]
+    fn test_planet_type_from_str() {
+        assert_eq!("houston_we_have_a_borrow".parse(), Ok(PlanetType::HoustonWeHaveABorrow));
+        assert_eq!("TheCompilerStrikesBack".parse(), Ok(PlanetType::TheCompilerStrikesBack));
+        assert!("earth".parse::<PlanetType>().is_err());
+    }
+
+    #[test]
+    fn test_planet_options() {
+        let options = PlanetOptions { houston_basic_resource: Some("Water".to_string()), ..PlanetOptions::default() };
+        assert!(options.validate().is_err());
+        let options = PlanetOptions { houston_basic_resource: None, ..PlanetOptions::default() };
+        assert!(options.validate().is_ok());
+
+        let (tx_planet, rx_orch, rx_explorer) = get_channels();
+        let options = PlanetOptions {
+            rustrelli_request_limit: RustrelliRequestLimit::FairShare,
+            houston_rocket_strategy: HoustonRocketStrategy::Safe,
+            houston_basic_resource: Some("silicon".to_string())
+        };
+        let planet = PlanetFactory::make_planet_with_options(
+            PlanetType::HoustonWeHaveABorrow,
+            &options,
+            4,
+            tx_planet,
+            rx_orch,
+            rx_explorer
+        );
+        assert!(planet.is_ok());
+    }
+
     #[test]
     fn test_panic_out_of_oxygen_planet_creation() {
         let (tx_planet, rx_orch, rx_explorer) = get_channels();
@@ -1,18 +1,36 @@
 //! Module that contains the orchestrator
 mod communication;
 mod core;
+mod error;
 mod galaxy;
+mod game_over;
+mod history;
 mod init;
 mod probability;
+mod scheduler;
+mod snapshot;
 mod state;
+mod stats;
+mod supervision;
 mod update_strategy;
 
 pub(crate) use core::{Orchestrator, OrchestratorMode};
-pub(crate) use init::{GalaxyBuilder, PLANET_ORDER, PlanetFactory, PlanetType};
-pub(crate) use state::{ExplorerHandle, OrchestratorManualAction, OrchestratorState, PlanetHandle};
+pub(crate) use error::{Actor, OrchestratorError};
+pub(crate) use galaxy::GalaxyTopology;
+pub(crate) use game_over::{EndCondition, EndReason, GameResult, Standing};
+pub(crate) use history::HistoryAction;
+pub(crate) use init::{ExplorerPlacement, GalaxyBuilder, GalaxyLayout, PLANET_ORDER, PlanetFactory, PlanetMix,
+                      PlanetOptions, PlanetType};
+pub(crate) use scheduler::{CelestialEventScheduler, SchedulerConfig};
+pub(crate) use snapshot::{ALL_RESOURCES, GameSnapshot, resource_from_name, resource_name};
+pub(crate) use state::{ExplorerHandle, ManualActionResult, OrchestratorManualAction, OrchestratorState, PlanetHandle};
+pub(crate) use stats::OrchestratorStats;
+pub(crate) use supervision::FaultPolicy;
+pub(crate) use update_strategy::get_recipe;
 
 use communication::{ExplorerChannelDemultiplexer, ExplorerLoggingReceiver, ExplorerLoggingSender,
-                               PlanetChannelDemultiplexer, PlanetLoggingReceiver, PlanetLoggingSender};
-use probability::ProbabilityCalculator;
+                    PlanetChannelDemultiplexer, PlanetLoggingReceiver, PlanetLoggingSender};
 use galaxy::Galaxy;
+use history::ManualHistory;
+use probability::ProbabilityCalculator;
 use update_strategy::OrchestratorUpdateFactory;
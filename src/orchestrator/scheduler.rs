@@ -0,0 +1,325 @@
+//! Schedulers decide which planets get a sunray or an asteroid at every time step, selectable from the
+//! config with e.g. `scheduler = { type = "linear_ramp", ... }`. They tune the difficulty curve of a game.
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+
+use common_game::utils::ID;
+use config::{Config, File};
+use rand::Rng;
+use rand::rngs::StdRng;
+use serde::Deserialize;
+
+use crate::orchestrator::galaxy::Galaxy;
+use crate::orchestrator::{OrchestratorState, PlanetType, ProbabilityCalculator};
+
+/// What a scheduler can look at to pick the planets.
+pub(crate) struct SchedulerContext<'a> {
+    pub time: u32,
+    pub galaxy: &'a Galaxy,
+    pub planet_types: &'a HashMap<ID, PlanetType>
+}
+
+pub(crate) trait CelestialEventScheduler: Send + Sync {
+    /// The alive planets hit by a sunray at this time step, sorted.
+    fn sunrays(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID>;
+    /// The alive planets hit by an asteroid at this time step, sorted.
+    fn asteroids(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID>;
+}
+
+impl OrchestratorState {
+    pub fn scheduled_sunrays(&mut self) -> Vec<ID> {
+        let context = SchedulerContext { time: self.time, galaxy: &self.galaxy, planet_types: &self.planet_types };
+        self.scheduler.sunrays(&context, &mut self.rng)
+    }
+
+    pub fn scheduled_asteroids(&mut self) -> Vec<ID> {
+        let context = SchedulerContext { time: self.time, galaxy: &self.galaxy, planet_types: &self.planet_types };
+        self.scheduler.asteroids(&context, &mut self.rng)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum SchedulerConfig {
+    /// Constant sunrays, asteroids growing as a sigmoid from `initial_asteroid_probability`, see the
+    /// top-level probabilities of the config
+    Sigmoid,
+    FixedRate { sunray_probability: f32, asteroid_probability: f32 },
+    /// The asteroid probability grows by `asteroid_increment` at every time step
+    LinearRamp { sunray_probability: f32, initial_asteroid_probability: f32, asteroid_increment: f32 },
+    /// Every `period` time steps a shower hits a random planet and its closest neighbours, `size` planets
+    /// in total. The rest of the time is like `sigmoid`
+    MeteorShower { period: u32, size: usize },
+    /// Probabilities by planet type (e.g. `rust_eze = 0.2`), the missing types are like `sigmoid`
+    PerPlanetType {
+        #[serde(default)]
+        sunray: BTreeMap<String, f32>,
+        #[serde(default)]
+        asteroid: BTreeMap<String, f32>
+    },
+    /// Events at fixed time steps read from a TOML or JSON file, see `ScheduleFile`
+    Scripted { file: String }
+}
+
+impl SchedulerConfig {
+    pub fn build(&self) -> Result<Box<dyn CelestialEventScheduler>, String> {
+        Ok(match self {
+            SchedulerConfig::Sigmoid => Box::new(SigmoidScheduler),
+            SchedulerConfig::FixedRate { sunray_probability, asteroid_probability } =>
+                Box::new(FixedRateScheduler { sunray: *sunray_probability, asteroid: *asteroid_probability }),
+            SchedulerConfig::LinearRamp { sunray_probability, initial_asteroid_probability, asteroid_increment } =>
+                Box::new(LinearRampScheduler {
+                    sunray: *sunray_probability,
+                    initial_asteroid: *initial_asteroid_probability,
+                    increment: *asteroid_increment
+                }),
+            SchedulerConfig::MeteorShower { period, size } => {
+                if *period == 0 {
+                    return Err("The meteor shower period must be at least 1".to_string());
+                }
+                Box::new(MeteorShowerScheduler { period: *period, size: *size })
+            }
+            SchedulerConfig::PerPlanetType { sunray, asteroid } =>
+                Box::new(PlanetTypeScheduler { sunray: parse_rates(sunray)?, asteroid: parse_rates(asteroid)? }),
+            SchedulerConfig::Scripted { file } => Box::new(ScriptedScheduler::from_file(file)?)
+        })
+    }
+}
+
+fn parse_rates(rates: &BTreeMap<String, f32>) -> Result<Vec<(PlanetType, f32)>, String> {
+    rates.iter().map(|(name, &rate)| Ok((name.parse()?, rate))).collect()
+}
+
+/// Every alive planet is picked with its own probability, drawing from the rng in planet order so that
+/// seeded games are reproducible.
+fn pick_planets(galaxy: &Galaxy, rng: &mut StdRng, probability: impl Fn(ID) -> f32) -> Vec<ID> {
+    galaxy.get_planets().into_iter().filter(|&id| rng.random::<f32>() < probability(id)).collect()
+}
+
+struct SigmoidScheduler;
+
+impl CelestialEventScheduler for SigmoidScheduler {
+    fn sunrays(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        let probability = ProbabilityCalculator::get_sunray_probability(context.time);
+        pick_planets(context.galaxy, rng, |_| probability)
+    }
+
+    fn asteroids(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        let probability = ProbabilityCalculator::get_asteroid_probability(context.time);
+        pick_planets(context.galaxy, rng, |_| probability)
+    }
+}
+
+struct FixedRateScheduler {
+    sunray: f32,
+    asteroid: f32
+}
+
+impl CelestialEventScheduler for FixedRateScheduler {
+    fn sunrays(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        pick_planets(context.galaxy, rng, |_| self.sunray)
+    }
+
+    fn asteroids(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        pick_planets(context.galaxy, rng, |_| self.asteroid)
+    }
+}
+
+struct LinearRampScheduler {
+    sunray: f32,
+    initial_asteroid: f32,
+    increment: f32
+}
+
+impl CelestialEventScheduler for LinearRampScheduler {
+    fn sunrays(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        pick_planets(context.galaxy, rng, |_| self.sunray)
+    }
+
+    #[allow(clippy::cast_precision_loss)] // time will never be that big
+    fn asteroids(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        let probability = self.initial_asteroid + self.increment * context.time as f32;
+        pick_planets(context.galaxy, rng, |_| probability)
+    }
+}
+
+struct MeteorShowerScheduler {
+    period: u32,
+    size: usize
+}
+
+impl CelestialEventScheduler for MeteorShowerScheduler {
+    fn sunrays(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        SigmoidScheduler.sunrays(context, rng)
+    }
+
+    fn asteroids(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        let planets = context.galaxy.get_planets();
+        if context.time == 0 || context.time % self.period != 0 || planets.is_empty() {
+            return SigmoidScheduler.asteroids(context, rng);
+        }
+        // Breadth-first from a random planet, so the shower hits a cluster of neighbours
+        let start = planets[rng.random_range(0..planets.len())];
+        let mut hit = vec![start];
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        while let Some(planet_id) = queue.pop_front()
+            && hit.len() < self.size
+        {
+            let mut neighbours = context.galaxy.get_planet_neighbours(planet_id);
+            neighbours.sort_unstable();
+            for neighbour in neighbours {
+                if hit.len() < self.size && visited.insert(neighbour) {
+                    hit.push(neighbour);
+                    queue.push_back(neighbour);
+                }
+            }
+        }
+        hit.sort_unstable();
+        hit
+    }
+}
+
+struct PlanetTypeScheduler {
+    sunray: Vec<(PlanetType, f32)>,
+    asteroid: Vec<(PlanetType, f32)>
+}
+
+impl PlanetTypeScheduler {
+    fn rate(rates: &[(PlanetType, f32)], context: &SchedulerContext, planet_id: ID) -> Option<f32> {
+        let planet_type = context.planet_types.get(&planet_id)?;
+        rates.iter().find(|(rate_type, _)| rate_type == planet_type).map(|&(_, rate)| rate)
+    }
+}
+
+impl CelestialEventScheduler for PlanetTypeScheduler {
+    fn sunrays(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        let default = ProbabilityCalculator::get_sunray_probability(context.time);
+        pick_planets(context.galaxy, rng, |id| Self::rate(&self.sunray, context, id).unwrap_or(default))
+    }
+
+    fn asteroids(&self, context: &SchedulerContext, rng: &mut StdRng) -> Vec<ID> {
+        let default = ProbabilityCalculator::get_asteroid_probability(context.time);
+        pick_planets(context.galaxy, rng, |id| Self::rate(&self.asteroid, context, id).unwrap_or(default))
+    }
+}
+
+/// A scripted schedule, e.g. in TOML:
+/// ```toml
+/// [[events]]
+/// time = 3
+/// kind = "asteroid"
+/// planets = [4] # every alive planet if omitted
+/// ```
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ScheduleFile {
+    pub events: Vec<ScheduledEvent>
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub(crate) struct ScheduledEvent {
+    pub time: u32,
+    pub kind: CelestialEventKind,
+    #[serde(default)]
+    pub planets: Option<Vec<ID>>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum CelestialEventKind {
+    Sunray,
+    Asteroid
+}
+
+struct ScriptedScheduler {
+    events: Vec<ScheduledEvent>
+}
+
+impl ScriptedScheduler {
+    fn from_file(path: &str) -> Result<Self, String> {
+        let schedule: ScheduleFile = Config::builder()
+            .add_source(File::with_name(path))
+            .build()
+            .and_then(Config::try_deserialize)
+            .map_err(|e| format!("Failed to load schedule {path}: {e}"))?;
+        Ok(ScriptedScheduler { events: schedule.events })
+    }
+
+    fn planets(&self, context: &SchedulerContext, kind: CelestialEventKind) -> Vec<ID> {
+        let alive = context.galaxy.get_planets();
+        let mut planets: Vec<ID> = self
+            .events
+            .iter()
+            .filter(|event| event.time == context.time && event.kind == kind)
+            .flat_map(|event| event.planets.clone().unwrap_or_else(|| alive.clone()))
+            .filter(|id| alive.contains(id))
+            .collect();
+        planets.sort_unstable();
+        planets.dedup();
+        planets
+    }
+}
+
+impl CelestialEventScheduler for ScriptedScheduler {
+    fn sunrays(&self, context: &SchedulerContext, _rng: &mut StdRng) -> Vec<ID> {
+        self.planets(context, CelestialEventKind::Sunray)
+    }
+
+    fn asteroids(&self, context: &SchedulerContext, _rng: &mut StdRng) -> Vec<ID> {
+        self.planets(context, CelestialEventKind::Asteroid)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::SeedableRng;
+
+    use super::*;
+
+    fn context<'a>(time: u32, galaxy: &'a Galaxy, planet_types: &'a HashMap<ID, PlanetType>) -> SchedulerContext<'a> {
+        SchedulerContext { time, galaxy, planet_types }
+    }
+
+    #[test]
+    fn test_fixed_rate_extremes() {
+        let galaxy = Galaxy::make_circular(&[1, 2, 3]).unwrap();
+        let types = HashMap::new();
+        let scheduler =
+            SchedulerConfig::FixedRate { sunray_probability: 1.0, asteroid_probability: 0.0 }.build().unwrap();
+        let mut rng = StdRng::seed_from_u64(1);
+        assert_eq!(scheduler.sunrays(&context(5, &galaxy, &types), &mut rng), vec![1, 2, 3]);
+        assert!(scheduler.asteroids(&context(5, &galaxy, &types), &mut rng).is_empty());
+    }
+
+    #[test]
+    fn test_meteor_shower_hits_neighbours() {
+        let galaxy = Galaxy::make_circular(&[1, 2, 3, 4, 5, 6]).unwrap();
+        let types = HashMap::new();
+        let scheduler = SchedulerConfig::MeteorShower { period: 10, size: 3 }.build().unwrap();
+        let mut rng = StdRng::seed_from_u64(4);
+
+        let hit = scheduler.asteroids(&context(20, &galaxy, &types), &mut rng);
+        assert_eq!(hit.len(), 3);
+        // On a ring, three planets hit by a breadth-first shower are consecutive
+        let links = hit.iter().filter(|&&a| hit.iter().any(|&b| galaxy.are_planets_connected(a, b))).count();
+        assert_eq!(links, 3);
+    }
+
+    #[test]
+    fn test_per_planet_type_rates() {
+        let galaxy = Galaxy::make_circular(&[1, 2]).unwrap();
+        let types = HashMap::from([(1, PlanetType::RustEze), (2, PlanetType::Carbonium)]);
+        let config = SchedulerConfig::PerPlanetType {
+            sunray: BTreeMap::from([("rust_eze".to_string(), 1.0), ("carbonium".to_string(), 0.0)]),
+            asteroid: BTreeMap::new()
+        };
+        let mut rng = StdRng::seed_from_u64(2);
+        assert_eq!(config.build().unwrap().sunrays(&context(0, &galaxy, &types), &mut rng), vec![1]);
+
+        let invalid = SchedulerConfig::PerPlanetType {
+            sunray: BTreeMap::from([("pluto".to_string(), 1.0)]),
+            asteroid: BTreeMap::new()
+        };
+        assert!(invalid.build().is_err());
+    }
+}
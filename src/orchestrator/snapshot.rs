@@ -0,0 +1,301 @@
+//! Save and resume of in-progress games.
+//! A snapshot only contains what the orchestrator can observe, so resuming builds a new galaxy with the
+//! same layout and brings it to the saved state with manual actions: sunrays recharge the planets cells
+//! and resource generation/combination refills the explorers bags.
+
+use std::collections::BTreeMap;
+
+use common_game::components::resource::{BasicResourceType, ComplexResourceType, ResourceType};
+use common_game::utils::ID;
+use serde::{Deserialize, Serialize};
+
+use crate::app::AppConfig;
+use crate::explorers::BagContent;
+use crate::orchestrator::init::{ExplorerLayout, PlanetLayout};
+use crate::orchestrator::{GalaxyLayout, Orchestrator, OrchestratorError, OrchestratorManualAction, OrchestratorMode,
+                          PlanetType, get_recipe};
+
+pub(crate) const ALL_RESOURCES: [ResourceType; 10] = [
+    ResourceType::Basic(BasicResourceType::Carbon),
+    ResourceType::Basic(BasicResourceType::Silicon),
+    ResourceType::Basic(BasicResourceType::Oxygen),
+    ResourceType::Basic(BasicResourceType::Hydrogen),
+    ResourceType::Complex(ComplexResourceType::Water),
+    ResourceType::Complex(ComplexResourceType::Diamond),
+    ResourceType::Complex(ComplexResourceType::Life),
+    ResourceType::Complex(ComplexResourceType::Robot),
+    ResourceType::Complex(ComplexResourceType::Dolphin),
+    ResourceType::Complex(ComplexResourceType::AIPartner)
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct GameSnapshot {
+    pub time: u32,
+    pub planets: Vec<PlanetSnapshot>,
+    pub edges: Vec<(ID, ID)>,
+    pub explorers: Vec<ExplorerSnapshot>
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct PlanetSnapshot {
+    pub id: ID,
+    pub planet_type: PlanetType,
+    pub energy_cells: Vec<bool>,
+    pub charged_cells_count: usize,
+    pub has_rocket: bool
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct ExplorerSnapshot {
+    pub id: ID,
+    pub explorer_type: String,
+    pub planet: ID,
+    pub bag: BTreeMap<String, usize> // resource name (e.g. "Water") -> quantity
+}
+
+impl GameSnapshot {
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let content = serde_json::to_string_pretty(self).map_err(|e| format!("Failed to serialize snapshot: {e}"))?;
+        std::fs::write(path, content).map_err(|e| format!("Failed to write snapshot {path}: {e}"))
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let content = std::fs::read_to_string(path).map_err(|e| format!("Failed to read snapshot {path}: {e}"))?;
+        serde_json::from_str(&content).map_err(|e| format!("Failed to parse snapshot {path}: {e}"))
+    }
+
+    fn to_layout(&self) -> GalaxyLayout {
+        GalaxyLayout {
+            planets: self
+                .planets
+                .iter()
+                .map(|p| PlanetLayout { id: p.id, planet_type: format!("{:?}", p.planet_type) })
+                .collect(),
+            edges: self.edges.clone(),
+            explorers: self
+                .explorers
+                .iter()
+                .map(|e| ExplorerLayout { id: e.id, explorer_type: e.explorer_type.clone(), start: e.planet })
+                .collect()
+        }
+    }
+}
+
+pub(crate) fn resource_name(resource: ResourceType) -> String {
+    match resource {
+        ResourceType::Basic(basic) => format!("{basic:?}"),
+        ResourceType::Complex(complex) => format!("{complex:?}")
+    }
+}
+
+pub(crate) fn resource_from_name(name: &str) -> Option<ResourceType> {
+    ALL_RESOURCES.into_iter().find(|&resource| resource_name(resource).eq_ignore_ascii_case(name))
+}
+
+fn bag_to_names(bag: &BagContent) -> BTreeMap<String, usize> {
+    bag.content.iter().filter(|(_, qty)| **qty > 0).map(|(&resource, &qty)| (resource_name(resource), qty)).collect()
+}
+
+impl Orchestrator {
+    /// Captures the observable state of the game. Must be called between two steps.
+    pub fn snapshot(&self) -> Result<GameSnapshot, OrchestratorError> {
+        let planets = self
+            .get_alive_planets()
+            .into_iter()
+            .map(|id| {
+                let state = self.get_planet_state(id).ok_or(OrchestratorError::UnknownPlanet(id))??;
+                Ok(PlanetSnapshot {
+                    id,
+                    planet_type: self.get_planet_types()[&id],
+                    energy_cells: state.energy_cells,
+                    charged_cells_count: state.charged_cells_count,
+                    has_rocket: state.has_rocket
+                })
+            })
+            .collect::<Result<Vec<_>, OrchestratorError>>()?;
+
+        // Dead explorers are not saved
+        let explorers = self
+            .get_explorer_ids()
+            .into_iter()
+            .filter_map(|id| {
+                Some(ExplorerSnapshot {
+                    id,
+                    explorer_type: self.get_explorer_type(id)?.to_string(),
+                    planet: self.get_explorer_current_planet(id)?,
+                    bag: self.get_explorer_bag(id).map(bag_to_names).unwrap_or_default()
+                })
+            })
+            .collect();
+
+        let mut edges = self.get_topology();
+        edges.sort_unstable();
+        Ok(GameSnapshot { time: self.get_time(), planets, edges, explorers })
+    }
+
+    /// Builds a galaxy in the snapshot configuration and starts it. The restore is best effort: what
+    /// cannot be reproduced (e.g. a resource the current planet cannot generate) is logged as a warning.
+    pub fn from_snapshot(mode: OrchestratorMode, snapshot: &GameSnapshot) -> Result<Self, OrchestratorError> {
+        let mut orchestrator = Self::from_layout(mode, snapshot.to_layout())?;
+        orchestrator.manual_init()?;
+        orchestrator.set_time(snapshot.time);
+
+        // The restore actions cannot be undone
+        let history = std::mem::take(orchestrator.history_mut());
+        orchestrator.set_mode(OrchestratorMode::Manual);
+        for explorer in &snapshot.explorers {
+            orchestrator.restore_bag(explorer);
+        }
+        for planet in &snapshot.planets {
+            orchestrator.restore_planet(planet);
+        }
+        orchestrator.set_mode(mode);
+        *orchestrator.history_mut() = history;
+        Ok(orchestrator)
+    }
+
+    pub(super) fn save_checkpoint_if_due(&self) {
+        let config = AppConfig::get();
+        let Some(path) = &config.snapshot_file else {
+            return;
+        };
+        if self.get_replay().is_some() {
+            return; // nothing to save, the game is already recorded
+        }
+        if config.snapshot_interval == 0 || self.get_time() % config.snapshot_interval != 0 {
+            return;
+        }
+        match self.snapshot().and_then(|snapshot| snapshot.save(path).map_err(OrchestratorError::Io)) {
+            Ok(()) => log::info!("Saved checkpoint of time step {} to {path}", self.get_time()),
+            Err(e) => log::error!("Failed to save checkpoint: {e}")
+        }
+    }
+
+    fn restore_bag(&mut self, explorer: &ExplorerSnapshot) {
+        let mut resources: Vec<(ResourceType, usize)> = Vec::new();
+        for (name, &quantity) in &explorer.bag {
+            match resource_from_name(name) {
+                Some(resource) => resources.push((resource, quantity)),
+                None => log::warn!("Unknown resource {name} in the bag of explorer {}, skipping", explorer.id)
+            }
+        }
+        // Complex resources first, otherwise they would consume the basic ones restored before
+        resources.sort_by_key(|(resource, _)| matches!(resource, ResourceType::Basic(_)));
+
+        for (resource, quantity) in resources {
+            for _ in 0..quantity {
+                self.restore_resource(explorer.id, explorer.planet, resource);
+            }
+        }
+
+        let restored = self.get_explorer_bag(explorer.id).map(bag_to_names).unwrap_or_default();
+        if restored != explorer.bag {
+            log::warn!("Explorer {} bag restored as {restored:?} instead of {:?}", explorer.id, explorer.bag);
+        }
+    }
+
+    /// Generates a resource in the explorer bag, ingredients included, recharging the planet before every
+    /// generation.
+    fn restore_resource(&mut self, explorer_id: ID, planet_id: ID, resource: ResourceType) {
+        if let ResourceType::Complex(complex) = resource {
+            let (a, b) = get_recipe(complex);
+            self.restore_resource(explorer_id, planet_id, a);
+            self.restore_resource(explorer_id, planet_id, b);
+        }
+        self.run_manual_action(OrchestratorManualAction::SendSunray { planet_id });
+        self.run_manual_action(match resource {
+            ResourceType::Basic(resource) => OrchestratorManualAction::GenerateBasic { explorer_id, resource },
+            ResourceType::Complex(resource) => OrchestratorManualAction::GenerateComplex { explorer_id, resource }
+        });
+    }
+
+    fn restore_planet(&mut self, planet: &PlanetSnapshot) {
+        let current = match self.get_planet_state(planet.id) {
+            Some(Ok(state)) => state,
+            Some(Err(e)) => {
+                log::warn!("Cannot restore planet {}: {e}", planet.id);
+                return;
+            }
+            None => {
+                log::warn!("Cannot restore planet {}, it is not running", planet.id);
+                return;
+            }
+        };
+
+        for _ in current.charged_cells_count..planet.charged_cells_count {
+            self.run_manual_action(OrchestratorManualAction::SendSunray { planet_id: planet.id });
+        }
+
+        match self.get_planet_state(planet.id) {
+            Some(Ok(state))
+                if state.charged_cells_count == planet.charged_cells_count
+                    && state.has_rocket == planet.has_rocket => {}
+            Some(Ok(state)) => log::warn!(
+                "Planet {} restored with {} charged cells and rocket {} instead of {} and {}",
+                planet.id,
+                state.charged_cells_count,
+                state.has_rocket,
+                planet.charged_cells_count,
+                planet.has_rocket
+            ),
+            _ => log::warn!("Cannot verify the restore of planet {}", planet.id)
+        }
+    }
+
+    fn run_manual_action(&mut self, action: OrchestratorManualAction) {
+        let description = format!("{action:?}");
+        self.schedule_manual_action(action);
+        if let Err(e) = self.process_commands() {
+            log::warn!("Restore action {description} failed: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resource_names() {
+        for resource in ALL_RESOURCES {
+            assert_eq!(resource_from_name(&resource_name(resource)), Some(resource));
+        }
+        assert_eq!(resource_from_name("aipartner"), Some(ResourceType::Complex(ComplexResourceType::AIPartner)));
+        assert_eq!(resource_from_name("Gold"), None);
+    }
+
+    #[test]
+    fn test_snapshot_save_load() {
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, 4, vec![], 11).unwrap();
+        orchestrator.manual_init().unwrap();
+        orchestrator.manual_step().unwrap();
+
+        let snapshot = orchestrator.snapshot().unwrap();
+        assert_eq!(snapshot.time, 1);
+        assert_eq!(snapshot.planets.len(), orchestrator.get_alive_planets().len());
+
+        let path = std::env::temp_dir().join("rusty_crab_snapshot_test.json");
+        let path = path.to_str().unwrap();
+        snapshot.save(path).unwrap();
+        assert_eq!(GameSnapshot::load(path).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn test_resume_snapshot() {
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Manual, 5, vec![], 12).unwrap();
+        orchestrator.manual_init().unwrap();
+        orchestrator.set_time(42);
+        orchestrator.run_manual_action(OrchestratorManualAction::SendSunray { planet_id: 2 });
+        let snapshot = orchestrator.snapshot().unwrap();
+
+        let resumed = Orchestrator::from_snapshot(OrchestratorMode::Auto, &snapshot).unwrap();
+        let resumed_snapshot = resumed.snapshot().unwrap();
+
+        assert_eq!(resumed_snapshot.time, 42);
+        assert_eq!(resumed_snapshot.edges.len(), snapshot.edges.len());
+        assert_eq!(resumed.get_planet_types(), orchestrator.get_planet_types());
+        for (saved, restored) in snapshot.planets.iter().zip(&resumed_snapshot.planets) {
+            assert_eq!(saved.charged_cells_count, restored.charged_cells_count);
+        }
+    }
+}
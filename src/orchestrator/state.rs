@@ -1,16 +1,19 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter};
 use std::thread;
 
-use common_game::components::resource::{BasicResourceType, ComplexResourceType};
-use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestratorKind, OrchestratorToExplorer};
+use common_game::components::resource::{BasicResourceType, ComplexResourceType, ResourceType};
 use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetToOrchestratorKind};
 use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
 use common_game::utils::ID;
 use crossbeam_channel::Sender;
+use rand::rngs::StdRng;
+use serde::Deserialize;
 
 use crate::explorers::BagContent;
-use crate::gui::GuiEventBuffer;
+use crate::gui::{GuiEventBuffer, deserialize_basic, deserialize_complex};
 use crate::orchestrator::communication::{ExplorerCommunicationCenter, PlanetCommunicationCenter};
+use crate::orchestrator::{CelestialEventScheduler, OrchestratorStats, PlanetType, resource_name};
 use crate::orchestrator::galaxy::Galaxy;
 
 /// struct used to handle the list of planets.
@@ -33,6 +36,9 @@ pub(crate) struct OrchestratorState {
     pub time: u32,
 
     pub galaxy: Galaxy,
+    // Every planet and explorer of the game, dead ones included
+    pub planet_types: HashMap<ID, PlanetType>,
+    pub explorer_types: HashMap<ID, String>,
 
     // List of explorers
     pub explorers: HashMap<ID, ExplorerHandle>,
@@ -43,67 +49,165 @@ pub(crate) struct OrchestratorState {
     pub explorers_communication_center: ExplorerCommunicationCenter,
 
     pub gui_events_buffer: GuiEventBuffer,
-    pub explorer_bags: HashMap<ID, BagContent> // Used by GUI to show explorer bags
+    pub stats: OrchestratorStats,
+    pub explorer_bags: HashMap<ID, BagContent>, // Used by GUI to show explorer bags
+    pub explorer_strikes: HashMap<ID, u32>, // Faults of every explorer, see `supervision`
+    pub explorers_busy: HashSet<ID>, // Real-time mode: explorers that did not answer the last BagContentRequest
+    pub dead_explorers: HashSet<ID>, // Killed or evicted while other planets were still alive
+
+    // Picks the planets hit by sunrays and asteroids
+    pub scheduler: Box<dyn CelestialEventScheduler>,
+    // Every random choice of the orchestrator must use this, so that a game can be reproduced from its seed
+    pub rng: StdRng
 }
 
-#[derive(Debug)]
+/// The commands of manual mode, run with `Orchestrator::execute` to get their result. In JSON they look like
+/// `{"type": "generate_basic", "explorer_id": 8, "resource": "Carbon"}`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
 pub(crate) enum OrchestratorManualAction {
     SendSunray { planet_id: ID },
     SendAsteroid { planet_id: ID },
-    GenerateBasic { explorer_id: ID, resource: BasicResourceType },
-    GenerateComplex { explorer_id: ID, resource: ComplexResourceType },
-    MoveExplorer { explorer_id: ID, destination_planet_id: ID }
+    GenerateBasic {
+        explorer_id: ID,
+        #[serde(deserialize_with = "deserialize_basic")]
+        resource: BasicResourceType
+    },
+    GenerateComplex {
+        explorer_id: ID,
+        #[serde(deserialize_with = "deserialize_complex")]
+        resource: ComplexResourceType
+    },
+    MoveExplorer { explorer_id: ID, destination_planet_id: ID },
+    StartPlanet { planet_id: ID },
+    /// A stopped planet answers `Stopped` to everything but a start or a kill
+    StopPlanet { planet_id: ID },
+    /// The planet protocol has no reset, the AI is stopped and started again
+    ResetPlanet { planet_id: ID },
+    StartExplorer { explorer_id: ID },
+    StopExplorer { explorer_id: ID },
+    ResetExplorer { explorer_id: ID },
+    /// Removes the explorer from the game, like an eviction
+    KillExplorer { explorer_id: ID },
+    QueryPlanetState { planet_id: ID },
+    QueryExplorerPlanet { explorer_id: ID },
+    QueryExplorerBag { explorer_id: ID },
+    QueryExplorerResources { explorer_id: ID },
+    AddEdge { from: ID, to: ID },
+    CutEdge { from: ID, to: ID },
+    /// Plays turns in auto mode, then goes back to the current mode. Stops early if the game ends
+    AdvanceTurns { turns: u32 }
+}
+
+impl OrchestratorManualAction {
+    /// Queries only read the state of the game, they are not kept in the undo history.
+    pub fn is_query(&self) -> bool {
+        matches!(
+            self,
+            OrchestratorManualAction::QueryPlanetState { .. }
+                | OrchestratorManualAction::QueryExplorerPlanet { .. }
+                | OrchestratorManualAction::QueryExplorerBag { .. }
+                | OrchestratorManualAction::QueryExplorerResources { .. }
+        )
+    }
+}
+
+/// What a manual action did. A failed action returns an `OrchestratorError` instead.
+#[derive(Debug, Clone)]
+pub(crate) enum ManualActionResult {
+    Done,
+    /// The orchestrator is not in manual mode, the action was not run
+    Ignored,
+    Asteroid { deflected: bool },
+    PlanetState { energy_cells: Vec<bool>, charged_cells_count: usize, has_rocket: bool },
+    ExplorerPlanet { planet_id: ID },
+    ExplorerBag(BagContent),
+    ExplorerResources(HashSet<BasicResourceType>),
+    /// Whether the link was added or removed, false if it already was
+    Topology { changed: bool },
+    TurnsAdvanced { turns: u32 }
+}
+
+impl Display for ManualActionResult {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ManualActionResult::Done => write!(f, "done"),
+            ManualActionResult::Ignored => write!(f, "ignored, the orchestrator is not in manual mode"),
+            ManualActionResult::Asteroid { deflected: true } => write!(f, "the asteroid was deflected"),
+            ManualActionResult::Asteroid { deflected: false } => write!(f, "the planet was destroyed"),
+            ManualActionResult::PlanetState { energy_cells, charged_cells_count, has_rocket } => write!(
+                f,
+                "{charged_cells_count}/{} cells charged, {}",
+                energy_cells.len(),
+                if *has_rocket { "rocket ready" } else { "no rocket" }
+            ),
+            ManualActionResult::ExplorerPlanet { planet_id } => write!(f, "on planet {planet_id}"),
+            ManualActionResult::ExplorerBag(bag) => {
+                let mut content: Vec<String> = bag
+                    .content
+                    .iter()
+                    .filter(|(_, qty)| **qty > 0)
+                    .map(|(&resource, qty)| format!("{} x{qty}", resource_name(resource)))
+                    .collect();
+                content.sort();
+                if content.is_empty() { write!(f, "empty bag") } else { write!(f, "{}", content.join(", ")) }
+            }
+            ManualActionResult::ExplorerResources(resources) => {
+                let mut names: Vec<String> =
+                    resources.iter().map(|&resource| resource_name(ResourceType::Basic(resource))).collect();
+                names.sort();
+                write!(f, "{}", names.join(", "))
+            }
+            ManualActionResult::Topology { changed: true } => write!(f, "topology changed"),
+            ManualActionResult::Topology { changed: false } => write!(f, "topology unchanged"),
+            ManualActionResult::TurnsAdvanced { turns } => write!(f, "{turns} turns played")
+        }
+    }
 }
 
 impl OrchestratorState {
-    pub fn handle_planet_destroyed(&mut self, planet_id: ID) -> Result<(), String> {
+    pub fn handle_planet_destroyed(&mut self, planet_id: ID) {
         self.galaxy.remove_planet(planet_id);
 
-        self.kill_planet(planet_id)?;
+        self.kill_planet(planet_id);
 
         let explorers_to_remove = self.get_explorers_on_planet(planet_id);
         for explorer_id in explorers_to_remove {
-            self.kill_explorer(explorer_id)?;
+            self.kill_explorer(explorer_id);
         }
-        Ok(())
     }
 
-    fn kill_planet(&mut self, planet_id: ID) -> Result<(), String> {
+    /// A planet that does not acknowledge the kill is detached instead of joined, so that a stuck planet
+    /// cannot block the game.
+    fn kill_planet(&mut self, planet_id: ID) {
         let handle = self.planets.remove(&planet_id);
         if let Some(planet_handle) = handle {
-            self.planets_communication_center.req_ack(
-                planet_id,
-                OrchestratorToPlanet::KillPlanet,
-                PlanetToOrchestratorKind::KillPlanetResult
-            )?;
-
-            planet_handle.thread_handle.join().unwrap_or_else(|e| {
-                log::error!("Failed to join thread for killed planet {planet_id}: {e:?}");
-            });
+            if !planet_handle.thread_handle.is_finished()
+                && let Err(e) = self.planets_communication_center.req_ack(
+                    planet_id,
+                    OrchestratorToPlanet::KillPlanet,
+                    PlanetToOrchestratorKind::KillPlanetResult
+                )
+            {
+                log::warn!("Planet {planet_id} did not acknowledge the kill, detaching its thread: {e}");
+            } else {
+                planet_handle.thread_handle.join().unwrap_or_else(|e| {
+                    log::error!("Failed to join thread for killed planet {planet_id}: {e:?}");
+                });
+            }
             self.planets_communication_center.remove(planet_id);
             self.gui_events_buffer.planet_destroyed(planet_id);
+            self.stats.planet_destroyed();
         }
-        Ok(())
     }
 
-    fn kill_explorer(&mut self, explorer_id: ID) -> Result<(), String> {
-        let handle = self.explorers.remove(&explorer_id);
-        if let Some(explorer_handle) = handle {
-            self.explorers_communication_center.req_ack(
-                explorer_id,
-                OrchestratorToExplorer::KillExplorer,
-                ExplorerToOrchestratorKind::KillExplorerResult
-            )?;
-
-            explorer_handle.thread_handle.join().unwrap_or_else(|e| {
-                log::error!("Failed to join thread for killed explorer {explorer_id}: {e:?}");
-            });
-            self.explorers_communication_center.remove(explorer_id);
+    fn kill_explorer(&mut self, explorer_id: ID) {
+        if let Some(explorer_handle) = self.explorers.remove(&explorer_id) {
+            self.stop_explorer(explorer_id, explorer_handle);
         }
-        Ok(())
     }
 
-    fn get_explorers_on_planet(&self, planet_id: ID) -> Vec<ID> {
+    pub(super) fn get_explorers_on_planet(&self, planet_id: ID) -> Vec<ID> {
         self.explorers
             .iter()
             .filter(|(_, handle)| handle.current_planet == planet_id)
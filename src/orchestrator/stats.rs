@@ -0,0 +1,161 @@
+//! Per-turn statistics of a game, collected from what the orchestrator observes: the sunrays and asteroids
+//! it sends, the rockets fired by the planets, the planets destroyed, the explorers moves and the changes of
+//! the explorers bags. Available from code with `Orchestrator::stats`, and exported as CSV (one row per
+//! turn) with the `stats_file` option.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use common_game::utils::ID;
+use serde::Serialize;
+
+use crate::explorers::BagContent;
+use crate::orchestrator::{ALL_RESOURCES, OrchestratorState, resource_name};
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub(crate) struct TurnStats {
+    pub time: u32,
+    pub sunrays: usize,
+    pub asteroids: usize,
+    pub rockets: usize, // asteroids deflected by a rocket
+    pub planets_destroyed: usize,
+    pub alive_planets: usize,
+    pub alive_explorers: usize,
+    pub moves: Vec<ExplorerMove>,
+    /// Change of the explorers bags during the turn by resource name, summed over the explorers
+    pub bag_deltas: BTreeMap<String, i64>
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub(crate) struct ExplorerMove {
+    pub explorer_id: ID,
+    pub from: ID,
+    pub to: ID
+}
+
+#[derive(Debug, Default)]
+pub(crate) struct OrchestratorStats {
+    turns: Vec<TurnStats>,
+    current: TurnStats,
+    bags: HashMap<ID, BagContent> // at the end of the previous turn
+}
+
+impl OrchestratorStats {
+    pub fn new() -> Self { Self::default() }
+
+    pub fn sunray_sent(&mut self) { self.current.sunrays += 1; }
+
+    pub fn asteroid_sent(&mut self) { self.current.asteroids += 1; }
+
+    pub fn rocket_fired(&mut self) { self.current.rockets += 1; }
+
+    pub fn planet_destroyed(&mut self) { self.current.planets_destroyed += 1; }
+
+    pub fn explorer_moved(&mut self, explorer_id: ID, from: ID, to: ID) {
+        self.current.moves.push(ExplorerMove { explorer_id, from, to });
+    }
+
+    /// The completed turns, oldest first.
+    #[allow(dead_code)] // used in tests
+    pub fn turns(&self) -> &[TurnStats] { &self.turns }
+
+    /// Every move of the game, oldest first.
+    #[allow(dead_code)] // used in tests
+    pub fn moves(&self) -> impl Iterator<Item = &ExplorerMove> { self.turns.iter().flat_map(|turn| &turn.moves) }
+
+    /// Closes the turn, the bags are compared with the ones at the end of the previous turn.
+    fn end_turn(&mut self, time: u32, alive_planets: usize, alive_explorers: usize, bags: &HashMap<ID, BagContent>) {
+        let mut turn = std::mem::take(&mut self.current);
+        turn.time = time;
+        turn.alive_planets = alive_planets;
+        turn.alive_explorers = alive_explorers;
+        for (explorer_id, bag) in bags {
+            let previous = self.bags.get(explorer_id);
+            for resource in ALL_RESOURCES {
+                let before = previous.and_then(|bag| bag.content.get(&resource)).copied().unwrap_or(0);
+                let after = bag.content.get(&resource).copied().unwrap_or(0);
+                if after != before {
+                    *turn.bag_deltas.entry(resource_name(resource)).or_default() += to_i64(after) - to_i64(before);
+                }
+            }
+        }
+        self.bags.clone_from(bags);
+        self.turns.push(turn);
+    }
+
+    /// One row per turn, with a `delta_<resource>` column for every resource.
+    pub fn to_csv(&self) -> String {
+        let mut csv =
+            "time,sunrays,asteroids,rockets,planets_destroyed,alive_planets,alive_explorers,moves".to_string();
+        for resource in ALL_RESOURCES {
+            write!(csv, ",delta_{}", resource_name(resource)).expect("Writing to a String cannot fail");
+        }
+        csv.push('\n');
+        for turn in &self.turns {
+            write!(
+                csv,
+                "{},{},{},{},{},{},{},{}",
+                turn.time,
+                turn.sunrays,
+                turn.asteroids,
+                turn.rockets,
+                turn.planets_destroyed,
+                turn.alive_planets,
+                turn.alive_explorers,
+                turn.moves.len()
+            )
+            .expect("Writing to a String cannot fail");
+            for resource in ALL_RESOURCES {
+                let delta = turn.bag_deltas.get(&resource_name(resource)).copied().unwrap_or(0);
+                write!(csv, ",{delta}").expect("Writing to a String cannot fail");
+            }
+            csv.push('\n');
+        }
+        csv
+    }
+
+    pub fn save_csv(&self, path: &str) -> Result<(), String> {
+        std::fs::write(path, self.to_csv()).map_err(|e| format!("Failed to write statistics {path}: {e}"))
+    }
+}
+
+fn to_i64(quantity: usize) -> i64 { i64::try_from(quantity).unwrap_or(i64::MAX) }
+
+impl OrchestratorState {
+    /// Closes the statistics of the current time step, must be called once at the end of every step.
+    pub fn end_turn_stats(&mut self) {
+        let alive_planets = self.galaxy.get_planets().len();
+        self.stats.end_turn(self.time, alive_planets, self.explorers.len(), &self.explorer_bags);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_game::components::resource::{BasicResourceType, ResourceType};
+
+    use super::*;
+
+    #[test]
+    fn test_bag_deltas_and_csv() {
+        let carbon = ResourceType::Basic(BasicResourceType::Carbon);
+        let mut stats = OrchestratorStats::new();
+        stats.asteroid_sent();
+        stats.rocket_fired();
+        stats.explorer_moved(5, 1, 2);
+        let bags = HashMap::from([(5, BagContent { content: HashMap::from([(carbon, 2)]) })]);
+        stats.end_turn(0, 3, 1, &bags);
+        let bags = HashMap::from([(5, BagContent { content: HashMap::from([(carbon, 1)]) })]);
+        stats.end_turn(1, 3, 1, &bags);
+
+        assert_eq!(stats.turns()[0].bag_deltas, BTreeMap::from([("Carbon".to_string(), 2)]));
+        assert_eq!(stats.turns()[1].bag_deltas, BTreeMap::from([("Carbon".to_string(), -1)]));
+        assert_eq!(stats.moves().collect::<Vec<_>>(), vec![&ExplorerMove { explorer_id: 5, from: 1, to: 2 }]);
+
+        let csv = stats.to_csv();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("time,sunrays,asteroids,rockets,"));
+        assert_eq!(lines[1], "0,0,1,1,0,3,1,1,2,0,0,0,0,0,0,0,0,0");
+        assert_eq!(lines[2], "1,0,0,0,0,3,1,0,-1,0,0,0,0,0,0,0,0,0");
+    }
+}
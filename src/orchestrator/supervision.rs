@@ -0,0 +1,351 @@
+//! Supervision of the actors, so that the rest of the galaxy keeps playing when one of them misbehaves.
+//! - A misbehaving explorer (timeouts, protocol violations) gets a strike for every fault and is restarted
+//!   or evicted from the game. An explorer whose thread terminated (e.g. it panicked) is evicted right away.
+//! - A planet that stops answering or whose thread terminated is destroyed at the first fault, after
+//!   moving its explorers to a neighbour planet when possible.
+
+use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestratorKind, OrchestratorToExplorer};
+use common_game::utils::ID;
+use serde::Deserialize;
+
+use crate::app::AppConfig;
+use crate::orchestrator::{Actor, ExplorerHandle, OrchestratorError, OrchestratorState};
+
+/// What to do with an explorer that has strikes left, selectable from the config with e.g. `"restart"`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum FaultPolicy {
+    /// Reset the explorer AI at every fault, evict it when it runs out of strikes
+    Restart,
+    /// Let the explorer go on, evict it when it runs out of strikes
+    Evict
+}
+
+impl OrchestratorState {
+    /// Whether the error was caused by the explorer, and can be handled with `handle_explorer_fault`.
+    pub fn is_explorer_fault(error: &OrchestratorError, explorer_id: ID) -> bool {
+        match error {
+            // The receiver is shared by every explorer, it gets closed when all of them are gone
+            OrchestratorError::ChannelClosed { actor: Actor::Explorer, id: None } => true,
+            other => other.actor() == Some((Actor::Explorer, explorer_id))
+        }
+    }
+
+    /// Whether the error was caused by the planet, and can be handled with `handle_planet_failure`.
+    /// A planet refusing a request is not a fault.
+    pub fn is_planet_fault(error: &OrchestratorError, planet_id: ID) -> bool {
+        match error {
+            OrchestratorError::ChannelClosed { actor: Actor::Planet, id: None } => true,
+            OrchestratorError::Rejected { .. } => false,
+            other => other.actor() == Some((Actor::Planet, planet_id))
+        }
+    }
+
+    /// Destroys a faulty planet. Its explorers are moved to a neighbour planet, or killed if none accepts them.
+    pub fn handle_planet_failure(&mut self, planet_id: ID, reason: &str) {
+        if !self.planets.contains_key(&planet_id) {
+            return;
+        }
+        log::error!("Planet {planet_id} failed, destroying it: {reason}");
+        self.gui_events_buffer.planet_failed(planet_id, reason.to_string());
+
+        let mut neighbours = self.galaxy.get_planet_neighbours(planet_id);
+        neighbours.sort_unstable(); // deterministic evacuation
+        let mut explorers = self.get_explorers_on_planet(planet_id);
+        explorers.sort_unstable();
+        for explorer_id in explorers {
+            if let Some(&destination) = neighbours.iter().find(|&&dst| self.evacuate_explorer(explorer_id, dst)) {
+                log::info!("Explorer {explorer_id} evacuated from planet {planet_id} to planet {destination}");
+            }
+        }
+        self.handle_planet_destroyed(planet_id);
+    }
+
+    /// Destroys the planets whose thread is no longer running, they cannot answer anymore.
+    pub fn check_planet_threads(&mut self) {
+        let mut terminated: Vec<ID> =
+            self.planets.iter().filter(|(_, handle)| handle.thread_handle.is_finished()).map(|(&id, _)| id).collect();
+        terminated.sort_unstable(); // deterministic order of the events
+        for planet_id in terminated {
+            self.handle_planet_failure(planet_id, "thread terminated");
+        }
+    }
+
+    /// Moves the explorer to the destination without asking the failed planet it is leaving.
+    fn evacuate_explorer(&mut self, explorer_id: ID, destination: ID) -> bool {
+        if !self.planets.contains_key(&destination) {
+            return false;
+        }
+        let to_planet = self.explorers[&explorer_id].tx_planet.clone();
+        if let Err(e) =
+            self.planets_communication_center.notify_planet_incoming_explorer(explorer_id, destination, to_planet)
+        {
+            log::warn!("Planet {destination} did not accept evacuated explorer {explorer_id}: {e}");
+            return false;
+        }
+        let to_explorer = self.planets[&destination].tx_explorer.clone();
+        let moved = self.explorers_communication_center.notify_explorer_successful_movement(
+            explorer_id,
+            destination,
+            to_explorer
+        );
+        if let Err(e) = moved {
+            log::warn!("Explorer {explorer_id} could not be moved to planet {destination}: {e}");
+            return false;
+        }
+        let handle = self.explorers.get_mut(&explorer_id).unwrap(); // it was found on the failed planet
+        let from = std::mem::replace(&mut handle.current_planet, destination);
+        self.gui_events_buffer.explorer_moved(explorer_id, destination);
+        self.stats.explorer_moved(explorer_id, from, destination);
+        true
+    }
+
+    /// Gives a strike to the explorer, then restarts or evicts it following the configured policy.
+    pub fn handle_explorer_fault(&mut self, explorer_id: ID, error: &OrchestratorError) {
+        if !self.explorers.contains_key(&explorer_id) {
+            return;
+        }
+        let config = AppConfig::get();
+        let strikes = self.explorer_strikes.entry(explorer_id).or_default();
+        *strikes += 1;
+        let strikes = *strikes;
+        log::warn!("Explorer {explorer_id} misbehaved ({strikes}/{} strikes): {error}", config.explorer_max_strikes);
+
+        let reason = error.to_string();
+        if strikes >= config.explorer_max_strikes || self.explorers[&explorer_id].thread_handle.is_finished() {
+            self.evict_explorer(explorer_id, &reason);
+        } else if config.explorer_fault_policy == FaultPolicy::Restart {
+            self.restart_explorer(explorer_id, &reason);
+        }
+    }
+
+    /// Evicts the explorers whose thread is no longer running, they cannot answer anymore.
+    pub fn check_explorer_threads(&mut self) {
+        let mut terminated: Vec<ID> =
+            self.explorers.iter().filter(|(_, handle)| handle.thread_handle.is_finished()).map(|(&id, _)| id).collect();
+        terminated.sort_unstable(); // deterministic order of the events
+        for explorer_id in terminated {
+            self.evict_explorer(explorer_id, "thread terminated");
+        }
+    }
+
+    fn restart_explorer(&mut self, explorer_id: ID, reason: &str) {
+        let result = self.explorers_communication_center.req_ack(
+            explorer_id,
+            OrchestratorToExplorer::ResetExplorerAI,
+            ExplorerToOrchestratorKind::ResetExplorerAIResult
+        );
+        match result {
+            Ok(_) => {
+                log::info!("Explorer {explorer_id} restarted");
+                // The reset AI dropped the request it was serving, in real time it has to be asked again
+                self.explorers_busy.remove(&explorer_id);
+                self.gui_events_buffer.explorer_restarted(explorer_id, reason.to_string());
+            }
+            Err(e) => self.evict_explorer(explorer_id, &format!("{reason}, then failed to restart: {e}"))
+        }
+    }
+
+    /// Removes the explorer from the game, the other explorers keep playing.
+    pub(super) fn evict_explorer(&mut self, explorer_id: ID, reason: &str) {
+        let Some(handle) = self.explorers.remove(&explorer_id) else {
+            return;
+        };
+        log::error!("Evicting explorer {explorer_id}: {reason}");
+
+        let planet_id = handle.current_planet;
+        self.stop_explorer(explorer_id, handle);
+        if let Err(e) = self.planets_communication_center.notify_planet_explorer_left(explorer_id, planet_id) {
+            log::warn!("Failed to notify planet {planet_id} that explorer {explorer_id} left: {e}");
+        }
+        self.gui_events_buffer.explorer_evicted(explorer_id, reason.to_string());
+    }
+
+    /// Kills the explorer thread of an explorer already removed from the game. A thread that does not
+    /// acknowledge the kill is detached instead of joined, so that a stuck explorer cannot block the game.
+    pub(super) fn stop_explorer(&mut self, explorer_id: ID, handle: ExplorerHandle) {
+        if !handle.thread_handle.is_finished()
+            && let Err(e) = self.explorers_communication_center.req_ack(
+                explorer_id,
+                OrchestratorToExplorer::KillExplorer,
+                ExplorerToOrchestratorKind::KillExplorerResult
+            )
+        {
+            log::warn!("Explorer {explorer_id} did not acknowledge the kill, detaching its thread: {e}");
+        } else if let Err(e) = handle.thread_handle.join() {
+            log::error!("Explorer {explorer_id} thread panicked: {e:?}");
+        }
+        self.explorers_communication_center.remove(explorer_id);
+        self.explorer_strikes.remove(&explorer_id);
+        self.explorers_busy.remove(&explorer_id);
+        // The explorers on the last planet are still alive at the end of the game
+        if !self.galaxy.get_planets().is_empty() {
+            self.dead_explorers.insert(explorer_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common_game::protocols::orchestrator_explorer::ExplorerToOrchestrator;
+    use common_game::protocols::planet_explorer::{ExplorerToPlanet, PlanetToExplorer};
+    use crossbeam_channel::{Receiver, Sender};
+
+    use super::*;
+    use crate::explorers::{BagContent, Explorer, ExplorerBuilder, ExplorerBuilderImpl, ExplorerFactory};
+    use crate::gui::{GameEvent, SupervisionEvent};
+    use crate::orchestrator::{Orchestrator, OrchestratorMode};
+
+    /// Plays by the rules until asked for its bag, then panics.
+    struct PanickingExplorer {
+        id: ID,
+        rx_orchestrator: Receiver<OrchestratorToExplorer>,
+        tx_orchestrator: Sender<ExplorerToOrchestrator<BagContent>>
+    }
+
+    impl Explorer for PanickingExplorer {
+        fn new(
+            id: ID,
+            _current_planet: ID,
+            rx_orchestrator: Receiver<OrchestratorToExplorer>,
+            tx_orchestrator: Sender<ExplorerToOrchestrator<BagContent>>,
+            _tx_current_planet: Sender<ExplorerToPlanet>,
+            _rx_planet: Receiver<PlanetToExplorer>
+        ) -> Self {
+            PanickingExplorer { id, rx_orchestrator, tx_orchestrator }
+        }
+
+        fn run(&mut self) -> Result<(), String> {
+            loop {
+                let explorer_id = self.id;
+                let response = match self.rx_orchestrator.recv().map_err(|e| e.to_string())? {
+                    OrchestratorToExplorer::StartExplorerAI =>
+                        ExplorerToOrchestrator::StartExplorerAIResult { explorer_id },
+                    OrchestratorToExplorer::BagContentRequest => panic!("Explorer {explorer_id} panicked on purpose"),
+                    other => return Err(format!("Unexpected message {other:?}"))
+                };
+                self.tx_orchestrator.send(response).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    /// Answers every bag request with the wrong message, the rest of the protocol is followed.
+    struct UnexpectedMessageExplorer {
+        id: ID,
+        rx_orchestrator: Receiver<OrchestratorToExplorer>,
+        tx_orchestrator: Sender<ExplorerToOrchestrator<BagContent>>
+    }
+
+    impl Explorer for UnexpectedMessageExplorer {
+        fn new(
+            id: ID,
+            _current_planet: ID,
+            rx_orchestrator: Receiver<OrchestratorToExplorer>,
+            tx_orchestrator: Sender<ExplorerToOrchestrator<BagContent>>,
+            _tx_current_planet: Sender<ExplorerToPlanet>,
+            _rx_planet: Receiver<PlanetToExplorer>
+        ) -> Self {
+            UnexpectedMessageExplorer { id, rx_orchestrator, tx_orchestrator }
+        }
+
+        fn run(&mut self) -> Result<(), String> {
+            loop {
+                let explorer_id = self.id;
+                let response = match self.rx_orchestrator.recv().map_err(|e| e.to_string())? {
+                    OrchestratorToExplorer::StartExplorerAI | OrchestratorToExplorer::BagContentRequest =>
+                        ExplorerToOrchestrator::StartExplorerAIResult { explorer_id },
+                    OrchestratorToExplorer::ResetExplorerAI =>
+                        ExplorerToOrchestrator::ResetExplorerAIResult { explorer_id },
+                    OrchestratorToExplorer::KillExplorer => {
+                        self.tx_orchestrator
+                            .send(ExplorerToOrchestrator::KillExplorerResult { explorer_id })
+                            .map_err(|e| e.to_string())?;
+                        return Ok(());
+                    }
+                    other => return Err(format!("Unexpected message {other:?}"))
+                };
+                self.tx_orchestrator.send(response).map_err(|e| e.to_string())?;
+            }
+        }
+    }
+
+    #[test]
+    fn test_unexpected_messages_restart_then_evict_the_explorer() {
+        let explorers: Vec<Box<dyn ExplorerBuilder>> = vec![
+            Box::new(ExplorerBuilderImpl::<UnexpectedMessageExplorer>::new()),
+            ExplorerFactory::make_from_name(&"samufaz".to_string())
+        ];
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, 7, explorers, 3).unwrap();
+        orchestrator.manual_init().unwrap();
+        let ids = orchestrator.get_explorer_ids();
+        let (faulty, player) = (ids[0], ids[1]);
+
+        // One strike per turn, the explorer is restarted until the last one
+        let max_strikes = AppConfig::get().explorer_max_strikes;
+        for _ in 0..max_strikes {
+            orchestrator.manual_step().unwrap();
+        }
+
+        let (restarted, evicted) = drain_restarted_and_evicted(&mut orchestrator);
+        assert_eq!(restarted, vec![faulty; max_strikes as usize - 1]);
+        assert_eq!(evicted, vec![faulty]);
+        assert_eq!(orchestrator.get_explorer_current_planet(faulty), None);
+
+        // The other explorer kept playing every turn
+        assert!(orchestrator.get_explorer_current_planet(player).is_some());
+        assert!(orchestrator.get_explorer_bag(player).is_some());
+    }
+
+    #[test]
+    fn test_real_time_restarted_explorer_is_asked_again() {
+        let explorers: Vec<Box<dyn ExplorerBuilder>> =
+            vec![Box::new(ExplorerBuilderImpl::<UnexpectedMessageExplorer>::new())];
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::RealTime, 7, explorers, 2).unwrap();
+        orchestrator.manual_init().unwrap();
+        let faulty = orchestrator.get_explorer_ids()[0];
+
+        // Without a pending request the explorer is asked for its bag at every tick, and faults every time
+        let max_strikes = AppConfig::get().explorer_max_strikes;
+        for _ in 0..max_strikes {
+            orchestrator.manual_step().unwrap();
+        }
+
+        let (restarted, evicted) = drain_restarted_and_evicted(&mut orchestrator);
+        assert_eq!(restarted, vec![faulty; max_strikes as usize - 1]);
+        assert_eq!(evicted, vec![faulty]);
+    }
+
+    /// The IDs of the restarted explorers, once per restart, and of the evicted ones.
+    fn drain_restarted_and_evicted(orchestrator: &mut Orchestrator) -> (Vec<ID>, Vec<ID>) {
+        let mut restarted = Vec::new();
+        let mut evicted = Vec::new();
+        for event in orchestrator.get_gui_events_buffer().drain_game_events() {
+            match event {
+                GameEvent::Supervision(SupervisionEvent::ExplorerRestarted { explorer_id, .. }) =>
+                    restarted.push(explorer_id),
+                GameEvent::Supervision(SupervisionEvent::ExplorerEvicted { explorer_id, .. }) =>
+                    evicted.push(explorer_id),
+                _ => {}
+            }
+        }
+        (restarted, evicted)
+    }
+
+    #[test]
+    fn test_panicking_explorer_is_evicted() {
+        let explorers: Vec<Box<dyn ExplorerBuilder>> = vec![
+            Box::new(ExplorerBuilderImpl::<PanickingExplorer>::new()),
+            ExplorerFactory::make_from_name(&"samufaz".to_string())
+        ];
+        let mut orchestrator = Orchestrator::new_with_seed(OrchestratorMode::Auto, 5, explorers, 3).unwrap();
+        orchestrator.manual_init().unwrap();
+        for _ in 0..3 {
+            orchestrator.manual_step().unwrap();
+        }
+
+        let (_, evicted) = drain_restarted_and_evicted(&mut orchestrator);
+        assert_eq!(evicted.len(), 1);
+        assert_eq!(orchestrator.get_explorer_current_planet(evicted[0]), None);
+        assert_eq!(orchestrator.get_explorer_ids().len(), 2); // still listed, as dead
+    }
+}
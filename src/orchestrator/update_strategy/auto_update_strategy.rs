@@ -9,7 +9,7 @@ use common_game::utils::ID;
 
 use crate::explorers::BagContent;
 use crate::orchestrator::update_strategy::OrchestratorUpdateStrategy;
-use crate::orchestrator::{OrchestratorManualAction, OrchestratorState, ProbabilityCalculator};
+use crate::orchestrator::{Actor, ManualActionResult, OrchestratorError, OrchestratorManualAction, OrchestratorState};
 
 pub(crate) struct AutoUpdateStrategy<'a> {
     explorers_not_passed: HashSet<ID>, // explorers that have not passed the turn yet
@@ -21,10 +21,10 @@ impl AutoUpdateStrategy<'_> {
         AutoUpdateStrategy { explorers_not_passed: HashSet::default(), state }
     }
 
-    fn execute_cycle(&mut self) -> Result<(), String> {
-        self.send_sunrays()?;
-        self.send_asteroids()?;
+    fn execute_cycle(&mut self) -> Result<(), OrchestratorError> {
+        self.dispatch_celestial_events()?;
 
+        self.state.check_explorer_threads();
         self.explorers_not_passed = self.state.explorers.keys().copied().collect();
         self.send_bag_content_requests()?;
 
@@ -34,66 +34,136 @@ impl AutoUpdateStrategy<'_> {
         Ok(())
     }
 
-    fn send_asteroids(&mut self) -> Result<(), String> {
-        for planet_id in self.state.galaxy.get_planets() {
-            if rand::random::<f32>() < ProbabilityCalculator::get_asteroid_probability(self.state.time) {
-                self.state.gui_events_buffer.asteroid_sent(planet_id);
-                let rocket = self
-                    .state
-                    .planets_communication_center
-                    .req_ack(
-                        planet_id,
-                        OrchestratorToPlanet::Asteroid(Asteroid::default()),
-                        PlanetToOrchestratorKind::AsteroidAck
-                    )?
-                    .into_asteroid_ack()
-                    .unwrap()
-                    .1; // Unwrap is safe due to expected kind
-
-                if rocket.is_none() {
-                    self.state.handle_planet_destroyed(planet_id)?;
-                }
+    /// Sends the sunrays and asteroids of this time step, after removing the planets that stopped running.
+    pub(super) fn dispatch_celestial_events(&mut self) -> Result<(), OrchestratorError> {
+        self.state.check_planet_threads();
+        self.send_sunrays()?;
+        self.send_asteroids()
+    }
+
+    /// Serves a message sent by the explorer, whatever the explorer is waiting for.
+    pub(super) fn serve_explorer_message(
+        &mut self,
+        explorer_id: ID,
+        message: ExplorerToOrchestrator<BagContent>
+    ) -> Result<(), OrchestratorError> {
+        let result = self.process_explorer_message(explorer_id, message);
+        self.supervise(explorer_id, result)
+    }
+
+    /// All the asteroids of the turn are sent together, and the planets are destroyed once every ack is in.
+    fn send_asteroids(&mut self) -> Result<(), OrchestratorError> {
+        let targets = self.state.scheduled_asteroids();
+        for &planet_id in &targets {
+            self.state.gui_events_buffer.asteroid_sent(planet_id);
+            self.state.stats.asteroid_sent();
+        }
+        let requests =
+            targets.into_iter().map(|id| (id, OrchestratorToPlanet::Asteroid(Asteroid::default()))).collect();
+        let acks =
+            self.state.planets_communication_center.broadcast_req_ack(requests, PlanetToOrchestratorKind::AsteroidAck);
+
+        for (planet_id, result) in acks {
+            let Some(ack) = self.supervise_planet(planet_id, result)? else {
+                continue;
+            };
+            let rocket = ack.into_asteroid_ack().unwrap().1; // Unwrap is safe due to expected kind
+
+            if rocket.is_none() {
+                self.state.handle_planet_destroyed(planet_id);
+            } else {
+                self.state.stats.rocket_fired();
             }
         }
         Ok(())
     }
 
-    fn send_sunrays(&mut self) -> Result<(), String> {
-        for planet_id in self.state.galaxy.get_planets() {
-            if rand::random::<f32>() < ProbabilityCalculator::get_sunray_probability(self.state.time) {
-                self.state.gui_events_buffer.sunray_sent(planet_id);
-                self.state.planets_communication_center.req_ack(
-                    planet_id,
-                    OrchestratorToPlanet::Sunray(Sunray::default()),
-                    PlanetToOrchestratorKind::SunrayAck
-                )?;
+    /// All the sunrays of the turn are sent together, so that the planets process them in parallel.
+    fn send_sunrays(&mut self) -> Result<(), OrchestratorError> {
+        let targets = self.state.scheduled_sunrays();
+        for &planet_id in &targets {
+            self.state.gui_events_buffer.sunray_sent(planet_id);
+            self.state.stats.sunray_sent();
+        }
+        let requests = targets.into_iter().map(|id| (id, OrchestratorToPlanet::Sunray(Sunray::default()))).collect();
+        let acks =
+            self.state.planets_communication_center.broadcast_req_ack(requests, PlanetToOrchestratorKind::SunrayAck);
+
+        for (planet_id, result) in acks {
+            if self.supervise_planet(planet_id, result)?.is_some() {
                 self.state.gui_events_buffer.sunray_received(planet_id);
             }
         }
         Ok(())
     }
 
-    fn send_bag_content_requests(&self) -> Result<(), String> {
-        for id in self.state.explorers.keys() {
-            self.state.explorers_communication_center.send_to(*id, OrchestratorToExplorer::BagContentRequest)?;
+    fn send_bag_content_requests(&mut self) -> Result<(), OrchestratorError> {
+        for id in self.explorers_not_passed.iter().copied().collect::<Vec<ID>>() {
+            let result =
+                self.state.explorers_communication_center.send_to(id, OrchestratorToExplorer::BagContentRequest);
+            self.supervise(id, result)?;
         }
         Ok(())
     }
 
-    fn check_explorers_responses(&mut self) -> Result<(), String> {
+    fn check_explorers_responses(&mut self) -> Result<(), OrchestratorError> {
         // Copy is necessary since the cycle may alter the set, so we copy before iterating
         for explorer_id in self.explorers_not_passed.iter().copied().collect::<Vec<ID>>() {
-            let res = self.state.explorers_communication_center.recv_from(explorer_id)?;
-            self.process_explorer_message(explorer_id, res)?;
+            if !self.explorers_not_passed.contains(&explorer_id) {
+                continue; // killed with a failed planet during this cycle
+            }
+            let result = self
+                .state
+                .explorers_communication_center
+                .recv_from(explorer_id)
+                .and_then(|res| self.process_explorer_message(explorer_id, res));
+            self.supervise(explorer_id, result)?;
         }
         Ok(())
     }
 
+    /// A fault of the explorer costs it a strike and its turn, a fault of a planet it talked to destroys the
+    /// planet, instead of stopping the game.
+    fn supervise(&mut self, explorer_id: ID, result: Result<(), OrchestratorError>) -> Result<(), OrchestratorError> {
+        match result {
+            Err(e) if OrchestratorState::is_explorer_fault(&e, explorer_id) => {
+                self.explorers_not_passed.remove(&explorer_id);
+                self.state.handle_explorer_fault(explorer_id, &e);
+                Ok(())
+            }
+            Err(e) => match e.actor() {
+                Some((Actor::Planet, planet_id)) if OrchestratorState::is_planet_fault(&e, planet_id) => {
+                    self.state.handle_planet_failure(planet_id, &e.to_string());
+                    self.explorers_not_passed.retain(|id| self.state.explorers.contains_key(id));
+                    Ok(())
+                }
+                _ => Err(e)
+            },
+            Ok(()) => Ok(())
+        }
+    }
+
+    /// Returns `None` when the planet failed and was destroyed.
+    fn supervise_planet<T>(
+        &mut self,
+        planet_id: ID,
+        result: Result<T, OrchestratorError>
+    ) -> Result<Option<T>, OrchestratorError> {
+        match result {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if OrchestratorState::is_planet_fault(&e, planet_id) => {
+                self.state.handle_planet_failure(planet_id, &e.to_string());
+                Ok(None)
+            }
+            Err(e) => Err(e)
+        }
+    }
+
     fn process_explorer_message(
         &mut self,
         explorer_id: ID,
         response: ExplorerToOrchestrator<BagContent>
-    ) -> Result<(), String> {
+    ) -> Result<(), OrchestratorError> {
         match response {
             ExplorerToOrchestrator::BagContentResponse { explorer_id: _, bag_content } => {
                 log::info!("Received bag content from explorer {explorer_id}: {bag_content:?}");
@@ -106,17 +176,25 @@ impl AutoUpdateStrategy<'_> {
             ExplorerToOrchestrator::TravelToPlanetRequest { explorer_id, current_planet_id, dst_planet_id } =>
                 self.handle_travel_request(explorer_id, current_planet_id, dst_planet_id),
 
-            other => Err(format!("Unexpected response from explorer {explorer_id}: {other:?}"))
+            other => Err(OrchestratorError::UnexpectedMessage {
+                actor: Actor::Explorer,
+                id: explorer_id,
+                expected: "BagContentResponse, NeighborsRequest or TravelToPlanetRequest".to_string(),
+                got: format!("{other:?}")
+            })
         }
     }
 
-    fn handle_neighbours_request(&self, explorer_id: ID, current_planet_id: ID) -> Result<(), String> {
+    fn handle_neighbours_request(&self, explorer_id: ID, current_planet_id: ID) -> Result<(), OrchestratorError> {
         if current_planet_id != self.state.explorers[&explorer_id].current_planet {
-            return Err(format!(
-                "Explorer {explorer_id} requested neighbors for planet {current_planet_id}, but is currently on \
-                 planet {}",
-                self.state.explorers[&explorer_id].current_planet
-            ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Explorer,
+                id: explorer_id,
+                reason: format!(
+                    "requested neighbors for planet {current_planet_id}, but is currently on planet {}",
+                    self.state.explorers[&explorer_id].current_planet
+                )
+            });
         }
 
         let neighbors = self.state.galaxy.get_planet_neighbours(current_planet_id);
@@ -130,13 +208,16 @@ impl AutoUpdateStrategy<'_> {
         explorer_id: ID,
         current_planet_id: ID,
         dst_planet_id: ID
-    ) -> Result<(), String> {
+    ) -> Result<(), OrchestratorError> {
         if current_planet_id != self.state.explorers[&explorer_id].current_planet {
-            return Err(format!(
-                "Explorer {explorer_id} requested travel from planet {current_planet_id}, but is currently on planet \
-                 {}",
-                self.state.explorers[&explorer_id].current_planet
-            ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Explorer,
+                id: explorer_id,
+                reason: format!(
+                    "requested travel from planet {current_planet_id}, but is currently on planet {}",
+                    self.state.explorers[&explorer_id].current_planet
+                )
+            });
         }
 
         // Communicate invalid travel if planets are not connected
@@ -157,11 +238,16 @@ impl AutoUpdateStrategy<'_> {
         // Update internal state
         self.state.explorers.get_mut(&explorer_id).unwrap().current_planet = dst_planet_id;
         self.state.gui_events_buffer.explorer_moved(explorer_id, dst_planet_id);
+        self.state.stats.explorer_moved(explorer_id, current_planet_id, dst_planet_id);
 
         Ok(())
     }
 
-    fn notify_explorer_invalid_movement(&mut self, explorer_id: ID, current_planet_id: ID) -> Result<(), String> {
+    fn notify_explorer_invalid_movement(
+        &mut self,
+        explorer_id: ID,
+        current_planet_id: ID
+    ) -> Result<(), OrchestratorError> {
         let moved_planet_id = self
             .state
             .explorers_communication_center
@@ -175,20 +261,23 @@ impl AutoUpdateStrategy<'_> {
             .1; // Unwrap is safe due to expected kind
 
         if moved_planet_id != current_planet_id {
-            return Err(format!(
-                "Explorer {explorer_id} moved to planet {moved_planet_id}, but was expected to stay on planet \
-                 {current_planet_id}"
-            ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Explorer,
+                id: explorer_id,
+                reason: format!(
+                    "moved to planet {moved_planet_id}, but was expected to stay on planet {current_planet_id}"
+                )
+            });
         }
         Ok(())
     }
 }
 
 impl OrchestratorUpdateStrategy for AutoUpdateStrategy<'_> {
-    fn update(&mut self) -> Result<(), String> { self.execute_cycle() }
+    fn update(&mut self) -> Result<(), OrchestratorError> { self.execute_cycle() }
 
-    fn process_command(&mut self, command: OrchestratorManualAction) -> Result<(), String> {
+    fn process_command(&mut self, command: OrchestratorManualAction) -> Result<ManualActionResult, OrchestratorError> {
         log::warn!("AutoUpdateStrategy does not process commands: {command:?}");
-        Ok(())
+        Ok(ManualActionResult::Ignored)
     }
 }
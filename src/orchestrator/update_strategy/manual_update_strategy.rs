@@ -1,12 +1,13 @@
 use common_game::components::asteroid::Asteroid;
 use common_game::components::resource::{BasicResourceType, ComplexResourceType, ResourceType};
 use common_game::components::sunray::Sunray;
-use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestratorKind, OrchestratorToExplorer};
+use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestrator, ExplorerToOrchestratorKind,
+                                                    OrchestratorToExplorer};
 use common_game::protocols::orchestrator_planet::{OrchestratorToPlanet, PlanetToOrchestratorKind};
 use common_game::utils::ID;
 
 use crate::orchestrator::update_strategy::OrchestratorUpdateStrategy;
-use crate::orchestrator::{OrchestratorManualAction, OrchestratorState};
+use crate::orchestrator::{Actor, ManualActionResult, OrchestratorError, OrchestratorManualAction, OrchestratorState};
 
 pub(crate) struct ManualUpdateStrategy<'a> {
     state: &'a mut OrchestratorState
@@ -16,7 +17,7 @@ pub(crate) struct ManualUpdateStrategy<'a> {
 impl ManualUpdateStrategy<'_> {
     pub fn new(state: &'_ mut OrchestratorState) -> ManualUpdateStrategy<'_> { ManualUpdateStrategy { state } }
 
-    fn basic_resource_discovery(&mut self, explorer_id: ID) -> Result<(), String> {
+    fn basic_resource_discovery(&mut self, explorer_id: ID) -> Result<ManualActionResult, OrchestratorError> {
         self.check_explorer_id(explorer_id)?;
 
         let (explorer_id, basic_resources) = self
@@ -31,15 +32,16 @@ impl ManualUpdateStrategy<'_> {
             .unwrap(); // Unwrap is safe due to expected kind
 
         if basic_resources.is_empty() {
-            return Err(format!(
-                "A SupportedResourceRequest from explorer {explorer_id} returned that a planet produces no basic \
-                 resource"
-            ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Explorer,
+                id: explorer_id,
+                reason: "SupportedResourceRequest returned that the planet produces no basic resource".to_string()
+            });
         }
-        Ok(())
+        Ok(ManualActionResult::ExplorerResources(basic_resources))
     }
 
-    fn combination_resource_discovery(&mut self, explorer_id: ID) -> Result<(), String> {
+    fn combination_resource_discovery(&mut self, explorer_id: ID) -> Result<(), OrchestratorError> {
         self.check_explorer_id(explorer_id)?;
 
         let _ = self
@@ -55,7 +57,11 @@ impl ManualUpdateStrategy<'_> {
         Ok(())
     }
 
-    fn basic_resource_generation(&mut self, explorer_id: ID, resource: BasicResourceType) -> Result<(), String> {
+    fn basic_resource_generation(
+        &mut self,
+        explorer_id: ID,
+        resource: BasicResourceType
+    ) -> Result<ManualActionResult, OrchestratorError> {
         self.check_explorer_id(explorer_id)?;
 
         let result = self
@@ -83,17 +89,20 @@ impl ManualUpdateStrategy<'_> {
         }
 
         if result.is_err() {
-            log::error!(
-                "Basic resource generation failed for explorer {explorer_id} trying to create {resource:?}, going on."
-            );
-            // return Err(format!(
-            //     "Basic resource from explorer {exp_id} request has not been generated"
-            // ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Explorer,
+                id: explorer_id,
+                reason: format!("failed to generate {resource:?}")
+            });
         }
-        Ok(())
+        Ok(ManualActionResult::Done)
     }
 
-    fn resource_combination(&mut self, explorer_id: ID, complex: ComplexResourceType) -> Result<(), String> {
+    fn resource_combination(
+        &mut self,
+        explorer_id: ID,
+        complex: ComplexResourceType
+    ) -> Result<ManualActionResult, OrchestratorError> {
         self.check_explorer_id(explorer_id)?;
 
         let result = self
@@ -118,17 +127,20 @@ impl ManualUpdateStrategy<'_> {
         }
 
         if result.is_err() {
-            log::error!(
-                "Resource combination failed for explorer {explorer_id} trying to create {complex:?}, going on."
-            );
-            // return Err(format!(
-            //     "Basic resource from explorer {exp_id} request has not been generated"
-            // ));
+            return Err(OrchestratorError::Rejected {
+                actor: Actor::Explorer,
+                id: explorer_id,
+                reason: format!("failed to combine {complex:?}")
+            });
         }
-        Ok(())
+        Ok(ManualActionResult::Done)
     }
 
-    fn handle_travel_request(&mut self, explorer_id: ID, dst_planet_id: ID) -> Result<(), String> {
+    fn handle_travel_request(
+        &mut self,
+        explorer_id: ID,
+        dst_planet_id: ID
+    ) -> Result<ManualActionResult, OrchestratorError> {
         self.check_planet_id(dst_planet_id)?;
         self.check_explorer_id(explorer_id)?;
 
@@ -136,10 +148,7 @@ impl ManualUpdateStrategy<'_> {
 
         // Communicate invalid travel if planets are not connected
         if !self.state.galaxy.are_planets_connected(current_planet_id, dst_planet_id) {
-            return Err(format!(
-                "This travel request cannot be granted because the destination planet {dst_planet_id}is not directly \
-                 linked to the current one ({current_planet_id})"
-            ));
+            return Err(OrchestratorError::InvalidMove { explorer_id, from: current_planet_id, to: dst_planet_id });
         }
 
         let new_sender = self.state.explorers[&explorer_id].tx_planet.clone();
@@ -159,13 +168,15 @@ impl ManualUpdateStrategy<'_> {
             .unwrap() // It is checked above that the explorer exists
             .current_planet = dst_planet_id;
         self.state.gui_events_buffer.explorer_moved(explorer_id, dst_planet_id);
+        self.state.stats.explorer_moved(explorer_id, current_planet_id, dst_planet_id);
 
-        Ok(())
+        Ok(ManualActionResult::Done)
     }
 
-    fn handle_send_asteroid(&mut self, planet_id: ID) -> Result<(), String> {
+    fn handle_send_asteroid(&mut self, planet_id: ID) -> Result<ManualActionResult, OrchestratorError> {
         self.check_planet_id(planet_id)?;
         self.state.gui_events_buffer.asteroid_sent(planet_id);
+        self.state.stats.asteroid_sent();
         let rocket = self
             .state
             .planets_communication_center
@@ -179,15 +190,18 @@ impl ManualUpdateStrategy<'_> {
             .1; // Unwrap is safe due to expected kind
 
         if rocket.is_none() {
-            self.state.handle_planet_destroyed(planet_id)?;
+            self.state.handle_planet_destroyed(planet_id);
+        } else {
+            self.state.stats.rocket_fired();
         }
 
-        Ok(())
+        Ok(ManualActionResult::Asteroid { deflected: rocket.is_some() })
     }
 
-    fn handle_send_sunray(&mut self, planet_id: ID) -> Result<(), String> {
+    fn handle_send_sunray(&mut self, planet_id: ID) -> Result<ManualActionResult, OrchestratorError> {
         self.check_planet_id(planet_id)?;
         self.state.gui_events_buffer.sunray_sent(planet_id);
+        self.state.stats.sunray_sent();
         self.state
             .planets_communication_center
             .req_ack(planet_id, OrchestratorToPlanet::Sunray(Sunray::default()), PlanetToOrchestratorKind::SunrayAck)?
@@ -195,19 +209,112 @@ impl ManualUpdateStrategy<'_> {
             .unwrap(); // Unwrap is safe due to expected kind
 
         self.state.gui_events_buffer.sunray_received(planet_id);
-        Ok(())
+        Ok(ManualActionResult::Done)
+    }
+
+    fn planet_ai_request(
+        &mut self,
+        planet_id: ID,
+        request: OrchestratorToPlanet,
+        expected: PlanetToOrchestratorKind
+    ) -> Result<ManualActionResult, OrchestratorError> {
+        self.check_planet_id(planet_id)?;
+        self.state.planets_communication_center.req_ack(planet_id, request, expected)?;
+        Ok(ManualActionResult::Done)
+    }
+
+    fn reset_planet(&mut self, planet_id: ID) -> Result<ManualActionResult, OrchestratorError> {
+        let (stop, stopped) = (OrchestratorToPlanet::StopPlanetAI, PlanetToOrchestratorKind::StopPlanetAIResult);
+        self.planet_ai_request(planet_id, stop, stopped)?;
+        let (start, started) = (OrchestratorToPlanet::StartPlanetAI, PlanetToOrchestratorKind::StartPlanetAIResult);
+        self.planet_ai_request(planet_id, start, started)
+    }
+
+    fn explorer_ai_request(
+        &mut self,
+        explorer_id: ID,
+        request: OrchestratorToExplorer,
+        expected: ExplorerToOrchestratorKind
+    ) -> Result<ManualActionResult, OrchestratorError> {
+        self.check_explorer_id(explorer_id)?;
+        self.state.explorers_communication_center.req_ack(explorer_id, request, expected)?;
+        Ok(ManualActionResult::Done)
+    }
+
+    fn kill_explorer(&mut self, explorer_id: ID) -> Result<ManualActionResult, OrchestratorError> {
+        self.check_explorer_id(explorer_id)?;
+        self.state.evict_explorer(explorer_id, "killed manually");
+        Ok(ManualActionResult::Done)
+    }
+
+    fn query_planet_state(&mut self, planet_id: ID) -> Result<ManualActionResult, OrchestratorError> {
+        self.check_planet_id(planet_id)?;
+        let state = self
+            .state
+            .planets_communication_center
+            .req_ack(
+                planet_id,
+                OrchestratorToPlanet::InternalStateRequest,
+                PlanetToOrchestratorKind::InternalStateResponse
+            )?
+            .into_internal_state_response()
+            .unwrap() // Unwrap is safe due to expected kind
+            .1;
+        Ok(ManualActionResult::PlanetState {
+            energy_cells: state.energy_cells,
+            charged_cells_count: state.charged_cells_count,
+            has_rocket: state.has_rocket
+        })
+    }
+
+    fn query_explorer_planet(&mut self, explorer_id: ID) -> Result<ManualActionResult, OrchestratorError> {
+        self.check_explorer_id(explorer_id)?;
+        let response = self.state.explorers_communication_center.req_ack(
+            explorer_id,
+            OrchestratorToExplorer::CurrentPlanetRequest,
+            ExplorerToOrchestratorKind::CurrentPlanetResult
+        )?;
+        match response {
+            ExplorerToOrchestrator::CurrentPlanetResult { planet_id, .. } =>
+                Ok(ManualActionResult::ExplorerPlanet { planet_id }),
+            _ => unreachable!("The kind is checked by req_ack")
+        }
+    }
+
+    /// The bag known by the orchestrator is updated with the answer.
+    fn query_explorer_bag(&mut self, explorer_id: ID) -> Result<ManualActionResult, OrchestratorError> {
+        self.check_explorer_id(explorer_id)?;
+        let response = self.state.explorers_communication_center.req_ack(
+            explorer_id,
+            OrchestratorToExplorer::BagContentRequest,
+            ExplorerToOrchestratorKind::BagContentResponse
+        )?;
+        match response {
+            ExplorerToOrchestrator::BagContentResponse { bag_content, .. } => {
+                self.state.explorer_bags.insert(explorer_id, bag_content.clone());
+                Ok(ManualActionResult::ExplorerBag(bag_content))
+            }
+            _ => unreachable!("The kind is checked by req_ack")
+        }
+    }
+
+    fn change_edge(&mut self, from: ID, to: ID, add: bool) -> Result<ManualActionResult, OrchestratorError> {
+        self.check_planet_id(from)?;
+        self.check_planet_id(to)?;
+        let changed = if add { self.state.galaxy.add_link(from, to) } else { self.state.galaxy.remove_link(from, to) };
+        Ok(ManualActionResult::Topology { changed })
     }
 
-    fn check_planet_id(&self, id: ID) -> Result<(), String> {
+    fn check_planet_id(&self, id: ID) -> Result<(), OrchestratorError> {
         if !self.state.planets.contains_key(&id) {
-            return Err(format!("Planet with ID: {id} does not exist."));
+            return Err(OrchestratorError::UnknownPlanet(id));
         }
         Ok(())
     }
 
-    fn check_explorer_id(&self, id: ID) -> Result<(), String> {
+    fn check_explorer_id(&self, id: ID) -> Result<(), OrchestratorError> {
         if !self.state.explorers.contains_key(&id) {
-            return Err(format!("Explorer with ID: {id} does not exist."));
+            return Err(OrchestratorError::UnknownExplorer(id));
         }
         Ok(())
     }
@@ -232,12 +339,13 @@ pub fn get_recipe(complex: ComplexResourceType) -> (ResourceType, ResourceType)
 }
 
 impl OrchestratorUpdateStrategy for ManualUpdateStrategy<'_> {
-    fn update(&mut self) -> Result<(), String> {
+    fn update(&mut self) -> Result<(), OrchestratorError> {
         log::info!("Update called in manual mode. No automatic actions taken.");
+        self.state.check_explorer_threads();
         Ok(())
     }
 
-    fn process_command(&mut self, command: OrchestratorManualAction) -> Result<(), String> {
+    fn process_command(&mut self, command: OrchestratorManualAction) -> Result<ManualActionResult, OrchestratorError> {
         match command {
             OrchestratorManualAction::GenerateBasic { explorer_id, resource } =>
                 self.basic_resource_generation(explorer_id, resource),
@@ -247,6 +355,42 @@ impl OrchestratorUpdateStrategy for ManualUpdateStrategy<'_> {
             OrchestratorManualAction::SendSunray { planet_id } => self.handle_send_sunray(planet_id),
             OrchestratorManualAction::MoveExplorer { explorer_id, destination_planet_id } =>
                 self.handle_travel_request(explorer_id, destination_planet_id),
+            OrchestratorManualAction::StartPlanet { planet_id } => self.planet_ai_request(
+                planet_id,
+                OrchestratorToPlanet::StartPlanetAI,
+                PlanetToOrchestratorKind::StartPlanetAIResult
+            ),
+            OrchestratorManualAction::StopPlanet { planet_id } => self.planet_ai_request(
+                planet_id,
+                OrchestratorToPlanet::StopPlanetAI,
+                PlanetToOrchestratorKind::StopPlanetAIResult
+            ),
+            OrchestratorManualAction::ResetPlanet { planet_id } => self.reset_planet(planet_id),
+            OrchestratorManualAction::StartExplorer { explorer_id } => self.explorer_ai_request(
+                explorer_id,
+                OrchestratorToExplorer::StartExplorerAI,
+                ExplorerToOrchestratorKind::StartExplorerAIResult
+            ),
+            OrchestratorManualAction::StopExplorer { explorer_id } => self.explorer_ai_request(
+                explorer_id,
+                OrchestratorToExplorer::StopExplorerAI,
+                ExplorerToOrchestratorKind::StopExplorerAIResult
+            ),
+            OrchestratorManualAction::ResetExplorer { explorer_id } => self.explorer_ai_request(
+                explorer_id,
+                OrchestratorToExplorer::ResetExplorerAI,
+                ExplorerToOrchestratorKind::ResetExplorerAIResult
+            ),
+            OrchestratorManualAction::KillExplorer { explorer_id } => self.kill_explorer(explorer_id),
+            OrchestratorManualAction::QueryPlanetState { planet_id } => self.query_planet_state(planet_id),
+            OrchestratorManualAction::QueryExplorerPlanet { explorer_id } => self.query_explorer_planet(explorer_id),
+            OrchestratorManualAction::QueryExplorerBag { explorer_id } => self.query_explorer_bag(explorer_id),
+            OrchestratorManualAction::QueryExplorerResources { explorer_id } =>
+                self.basic_resource_discovery(explorer_id),
+            OrchestratorManualAction::AddEdge { from, to } => self.change_edge(from, to, true),
+            OrchestratorManualAction::CutEdge { from, to } => self.change_edge(from, to, false),
+            OrchestratorManualAction::AdvanceTurns { .. } =>
+                unreachable!("Advancing turns is done by the orchestrator, not by the strategy")
         }
     }
 }
@@ -1,11 +1,15 @@
 mod auto_update_strategy;
 mod manual_update_strategy;
+mod real_time_update_strategy;
 
-use crate::orchestrator::{OrchestratorManualAction, OrchestratorMode, OrchestratorState};
+pub(crate) use manual_update_strategy::get_recipe;
+
+use crate::orchestrator::{ManualActionResult, OrchestratorError, OrchestratorManualAction, OrchestratorMode,
+                          OrchestratorState};
 
 pub(super) trait OrchestratorUpdateStrategy {
-    fn update(&mut self) -> Result<(), String>;
-    fn process_command(&mut self, command: OrchestratorManualAction) -> Result<(), String>;
+    fn update(&mut self) -> Result<(), OrchestratorError>;
+    fn process_command(&mut self, command: OrchestratorManualAction) -> Result<ManualActionResult, OrchestratorError>;
 }
 
 pub(super) struct OrchestratorUpdateFactory;
@@ -17,7 +21,8 @@ impl OrchestratorUpdateFactory {
     ) -> Box<dyn OrchestratorUpdateStrategy + '_> {
         match mode {
             OrchestratorMode::Auto => Box::new(auto_update_strategy::AutoUpdateStrategy::new(state)),
-            OrchestratorMode::Manual => Box::new(manual_update_strategy::ManualUpdateStrategy::new(state))
+            OrchestratorMode::Manual => Box::new(manual_update_strategy::ManualUpdateStrategy::new(state)),
+            OrchestratorMode::RealTime => Box::new(real_time_update_strategy::RealTimeUpdateStrategy::new(state))
         }
     }
 }
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+use common_game::protocols::orchestrator_explorer::{ExplorerToOrchestrator, OrchestratorToExplorer};
+use common_game::utils::ID;
+
+use crate::app::AppConfig;
+use crate::orchestrator::update_strategy::OrchestratorUpdateStrategy;
+use crate::orchestrator::update_strategy::auto_update_strategy::AutoUpdateStrategy;
+use crate::orchestrator::{ManualActionResult, OrchestratorError, OrchestratorManualAction, OrchestratorState};
+
+/// Every update is a tick of wall-clock time: the sunrays and asteroids of the tick are sent at its start,
+/// then the explorers requests are served as they arrive until the tick is over. The explorers are never
+/// waited for, a slow one simply does less in the same time. The turn logic is shared with
+/// `AutoUpdateStrategy`.
+pub(crate) struct RealTimeUpdateStrategy<'a> {
+    state: &'a mut OrchestratorState
+}
+
+impl RealTimeUpdateStrategy<'_> {
+    pub(crate) fn new(state: &'_ mut OrchestratorState) -> RealTimeUpdateStrategy<'_> {
+        RealTimeUpdateStrategy { state }
+    }
+
+    fn execute_tick(&mut self) -> Result<(), OrchestratorError> {
+        let tick_end = Instant::now() + Duration::from_millis(AppConfig::get().real_time_tick_ms);
+        AutoUpdateStrategy::new(self.state).dispatch_celestial_events()?;
+
+        self.state.check_explorer_threads();
+        self.send_bag_content_requests();
+
+        if self.state.explorers.is_empty() {
+            std::thread::sleep(tick_end.saturating_duration_since(Instant::now()));
+            return Ok(());
+        }
+        while let Some(message) = self.state.explorers_communication_center.recv_any_before(tick_end)? {
+            let explorer_id = message.explorer_id();
+            if !self.state.explorers.contains_key(&explorer_id) {
+                log::debug!("Ignoring late message from removed explorer {explorer_id}: {message:?}");
+                continue;
+            }
+            if matches!(message, ExplorerToOrchestrator::BagContentResponse { .. }) {
+                self.state.explorers_busy.remove(&explorer_id);
+            }
+            AutoUpdateStrategy::new(self.state).serve_explorer_message(explorer_id, message)?;
+        }
+        Ok(())
+    }
+
+    /// The bag request starts the explorer turn, it is not sent again until the explorer answers.
+    fn send_bag_content_requests(&mut self) {
+        let mut idle: Vec<ID> =
+            self.state.explorers.keys().filter(|id| !self.state.explorers_busy.contains(id)).copied().collect();
+        idle.sort_unstable();
+        let center = &self.state.explorers_communication_center;
+        let results: Vec<(ID, Result<(), OrchestratorError>)> =
+            idle.into_iter().map(|id| (id, center.send_to(id, OrchestratorToExplorer::BagContentRequest))).collect();
+        for (explorer_id, sent) in results {
+            match sent {
+                Ok(()) => {
+                    self.state.explorers_busy.insert(explorer_id);
+                }
+                Err(e) => self.state.handle_explorer_fault(explorer_id, &e)
+            }
+        }
+    }
+}
+
+impl OrchestratorUpdateStrategy for RealTimeUpdateStrategy<'_> {
+    fn update(&mut self) -> Result<(), OrchestratorError> { self.execute_tick() }
+
+    fn process_command(&mut self, command: OrchestratorManualAction) -> Result<ManualActionResult, OrchestratorError> {
+        log::warn!("RealTimeUpdateStrategy does not process commands: {command:?}");
+        Ok(ManualActionResult::Ignored)
+    }
+}